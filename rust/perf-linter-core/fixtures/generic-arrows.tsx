@@ -0,0 +1,19 @@
+import { memo } from 'react';
+
+// the trailing comma / `extends` keep `<T,>` from reading as a JSX tag
+const identity = <T,>(value: T) => value;
+const first = <T extends unknown>(items: T[]) => items[0];
+
+export const List = <T,>({ items }: { items: T[] }) => (
+  <ul>
+    {items.map((item, i) => (
+      <li key={i}>{String(identity(item))}</li>
+    ))}
+  </ul>
+);
+
+export function Select<T>({ options }: { options: T[] }) {
+  return <select>{String(first(options))}</select>;
+}
+
+export default memo(List);
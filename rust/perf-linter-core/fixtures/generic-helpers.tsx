@@ -0,0 +1,3 @@
+// no JSX: bare `<T>` generics are only valid when parsed as plain TypeScript
+export const identity = <T>(value: T): T => value;
+export const pair = <A, B>(a: A, b: B): [A, B] => [a, b];
@@ -1,18 +1,40 @@
 use serde::{Serialize, Deserialize};
-use swc_common::{errors::{ColorConfig, Handler}, sync::Lrc, FileName, SourceMap, Span};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use swc_common::comments::{CommentKind, Comments, SingleThreadedComments};
+use swc_common::{BytePos, Span, Spanned};
 use swc_ecma_ast::*;
-use swc_ecma_parser::{EsConfig, Parser, StringInput, Syntax, TsConfig};
 use swc_ecma_visit::{Visit, VisitWith};
-use super::metadata::{ComponentMeta, PropInfo, PropKind};
+use super::findings::Finding;
+use crate::parser::{parse_source, LineIndex};
+use super::metadata::{ComponentMeta, InlineProp, PassedProp, PropInfo, PropKind};
+use super::rules::{hook_name, is_static_style, FileContext, RuleRegistry};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ExportKind { Named, Default }
 
+/// Optional extraction work, off by default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExtractOptions {
+    /// Keep comments while parsing and attach each component's leading doc comment
+    pub collect_docs: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportInfo {
     pub name: String,
     pub kind: ExportKind,
     pub line: usize,
+    /// Local symbol behind a default export, when it has a name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local: Option<String>,
+    /// Module a re-export comes from (`export { x } from './x'`; `export * from` is named `*`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
 // PropKind/PropInfo são definidos em metadata.rs
@@ -23,82 +45,578 @@ pub struct ImportSpecifierMeta {
     pub imported: Option<String>,
 }
 
+/// Where an import source points: a package, a path, or a tsconfig alias
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportSourceKind { External, Relative, Absolute, Alias }
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportMeta {
     pub source: String,
+    pub kind: ImportSourceKind,
     pub specifiers: Vec<ImportSpecifierMeta>,
     pub line: usize,
 }
 
+/// `compilerOptions.paths` from a tsconfig, used to recognise aliased imports
+#[derive(Debug, Clone, Default)]
+pub struct PathAliases {
+    /// Directory alias targets are relative to (tsconfig dir joined with `baseUrl`)
+    pub base_dir: String,
+    /// Alias pattern (e.g. `@/*`) -> target patterns (e.g. `src/*`)
+    pub paths: HashMap<String, Vec<String>>,
+}
+
+impl PathAliases {
+    /// Load aliases from a tsconfig.json; None if unreadable or not plain JSON
+    pub fn from_tsconfig(tsconfig_path: &str) -> Option<Self> {
+        let raw = std::fs::read_to_string(tsconfig_path).ok()?;
+        let json: serde_json::Value = serde_json::from_str(&raw).ok()?;
+        let opts = json.get("compilerOptions")?;
+        let dir = Path::new(tsconfig_path).parent().unwrap_or_else(|| Path::new("."));
+        let base_url = opts.get("baseUrl").and_then(|v| v.as_str()).unwrap_or(".");
+        let paths = opts
+            .get("paths")
+            .and_then(|p| p.as_object())
+            .map(|m| {
+                m.iter()
+                    .map(|(k, v)| {
+                        let targets = v.as_array().map(|a| a.iter().filter_map(|t| t.as_str().map(String::from)).collect()).unwrap_or_default();
+                        (k.clone(), targets)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(Self { base_dir: dir.join(base_url).to_string_lossy().to_string(), paths })
+    }
+
+    /// Whether `source` is covered by one of the alias patterns
+    pub fn matches(&self, source: &str) -> bool {
+        self.paths.keys().any(|pattern| match pattern.split_once('*') {
+            Some((prefix, suffix)) => source.len() >= prefix.len() + suffix.len() && source.starts_with(prefix) && source.ends_with(suffix),
+            None => source == pattern,
+        })
+    }
+
+    /// Paths an aliased `source` may point at, relative to `base_dir`
+    pub fn candidates(&self, source: &str) -> Vec<PathBuf> {
+        let mut out = Vec::new();
+        for (pattern, targets) in &self.paths {
+            let matched = match pattern.split_once('*') {
+                Some((prefix, suffix)) if source.len() >= prefix.len() + suffix.len() && source.starts_with(prefix) && source.ends_with(suffix) => {
+                    &source[prefix.len()..source.len() - suffix.len()]
+                }
+                None if source == pattern => "",
+                _ => continue,
+            };
+            out.extend(targets.iter().map(|t| Path::new(&self.base_dir).join(t.replacen('*', matched, 1))));
+        }
+        out
+    }
+}
+
+/// Classify an import source; aliases win over the bare-specifier default
+pub fn classify_import_source(source: &str, aliases: &PathAliases) -> ImportSourceKind {
+    if source == "." || source == ".." || source.starts_with("./") || source.starts_with("../") {
+        ImportSourceKind::Relative
+    } else if source.starts_with('/') {
+        ImportSourceKind::Absolute
+    } else if aliases.matches(source) {
+        ImportSourceKind::Alias
+    } else {
+        ImportSourceKind::External
+    }
+}
+
+/// Generous per-file budget; normal files never come close
+pub const DEFAULT_PARSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A file whose parse was abandoned after exceeding the timeout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseTimeout {
+    pub path: String,
+    pub elapsed_ms: u64,
+}
+
+/// Everything extracted from a single file
+pub type Extracted = (Vec<ComponentMeta>, Vec<ImportMeta>, Vec<ExportInfo>, Vec<Finding>);
+
+/// `Extracted` with named fields; what `analyze_source` returns and the index caches
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileAnalysis {
+    pub components: Vec<ComponentMeta>,
+    pub imports: Vec<ImportMeta>,
+    pub exports: Vec<ExportInfo>,
+    pub findings: Vec<Finding>,
+}
+
+impl From<Extracted> for FileAnalysis {
+    fn from((components, imports, exports, findings): Extracted) -> Self {
+        Self { components, imports, exports, findings }
+    }
+}
+
 #[derive(Default)]
 struct MetadataExtractor {
     components: Vec<ComponentMeta>,
     imports: Vec<ImportMeta>,
     exports: Vec<ExportInfo>,
+    lines: LineIndex,
+    /// Bodies of the components found, for the render-time rules
+    render_bodies: Vec<Span>,
+    /// Module-scope `const` bindings and the kind of value they hold
+    module_consts: HashMap<String, PropKind>,
+    /// Local symbols exported by name, and the one exported as default
+    named_export_locals: Vec<(String, Span)>,
+    default_export_local: Option<(String, Span)>,
 }
 
-fn span_line(span: Span) -> usize { span.lo.0 as usize }
-
-fn is_identifier_react_memo(expr: &Expr) -> bool {
+pub(crate) fn is_identifier_react_memo(expr: &Expr) -> bool {
     // Detect React.memo or memo (common import)
+    is_react_api(expr, "memo")
+}
+
+/// `React.forwardRef` or `forwardRef`
+pub(crate) fn is_identifier_react_forward_ref(expr: &Expr) -> bool {
+    is_react_api(expr, "forwardRef")
+}
+
+fn is_react_api(expr: &Expr, api: &str) -> bool {
     match expr {
         Expr::Member(MemberExpr { obj, prop, .. }) => {
             if let Expr::Ident(obj_ident) = &**obj {
                 if obj_ident.sym.as_ref() == "React" {
-                    if let MemberProp::Ident(p) = prop { return p.sym.as_ref() == "memo"; }
+                    if let MemberProp::Ident(p) = prop { return p.sym.as_ref() == api; }
                 }
             }
             false
         }
-        Expr::Ident(id) => id.sym.as_ref() == "memo",
+        Expr::Ident(id) => id.sym.as_ref() == api,
         _ => false,
     }
 }
 
+pub(crate) fn is_component_name(name: &str) -> bool {
+    name.chars().next().map(|c| c.is_uppercase()).unwrap_or(false)
+}
+
+/// Element name as written: `Row`, `Form.Item`, `svg:rect`
+pub(crate) fn jsx_element_name(name: &JSXElementName) -> Option<String> {
+    fn object_name(obj: &JSXObject) -> String {
+        match obj {
+            JSXObject::Ident(i) => i.sym.to_string(),
+            JSXObject::JSXMemberExpr(m) => format!("{}.{}", object_name(&m.obj), m.prop.sym),
+        }
+    }
+    match name {
+        JSXElementName::Ident(i) => Some(i.sym.to_string()),
+        JSXElementName::JSXMemberExpr(m) => Some(format!("{}.{}", object_name(&m.obj), m.prop.sym)),
+        JSXElementName::JSXNamespacedName(n) => Some(format!("{}:{}", n.ns.sym, n.name.sym)),
+    }
+}
+
+/// Member names (`motion.div`, `Menu.Item`) always reference a component; namespaced
+/// names (`svg:rect`) are XML tags; bare names are components when capitalized
+pub(crate) fn is_component_element(name: &str) -> bool {
+    !name.contains(':') && (name.contains('.') || is_component_name(name))
+}
+
+/// Kind of a prop value that is recreated on every render, if it is one;
+/// `{..} as const` and `Object.freeze([..])` written inline are recreated too
+fn inline_prop_kind(expr: &Expr) -> Option<PropKind> {
+    match strip_const_wrappers(expr) {
+        Expr::Object(_) => Some(PropKind::Object),
+        Expr::Array(_) => Some(PropKind::Array),
+        Expr::Arrow(_) | Expr::Fn(_) => Some(PropKind::Function),
+        _ => None,
+    }
+}
+
+/// Inline object/array/function props passed to components rendered by a component,
+/// including JSX built inside callbacks (`items.map(i => <Row onClick={() => ..} />)`)
+struct InlinePropScan<'a> {
+    lines: &'a LineIndex,
+    props: Vec<InlineProp>,
+}
+
+impl Visit for InlinePropScan<'_> {
+    fn visit_jsx_opening_element(&mut self, el: &JSXOpeningElement) {
+        if let Some(element) = jsx_element_name(&el.name).filter(|n| is_component_element(n)) {
+            for attr in &el.attrs {
+                let JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(prop), value: Some(JSXAttrValue::JSXExprContainer(c)), span, .. }) = attr else { continue };
+                let JSXExpr::Expr(value) = &c.expr else { continue };
+                if let Some(kind) = inline_prop_kind(value) {
+                    let is_static = matches!(strip_const_wrappers(value), Expr::Object(o) if !o.props.is_empty() && is_static_style(o));
+                    self.props.push(InlineProp { element: element.clone(), prop: prop.sym.to_string(), kind, is_static, line: self.lines.line(span.lo) });
+                }
+            }
+        }
+        el.visit_children_with(self);
+    }
+}
+
+fn inline_props<N>(node: &N, lines: &LineIndex) -> Vec<InlineProp>
+where
+    N: for<'a> VisitWith<InlinePropScan<'a>>,
+{
+    let mut scan = InlinePropScan { lines, props: Vec::new() };
+    node.visit_with(&mut scan);
+    scan.props
+}
+
+/// Every named prop passed to components rendered by a component, whatever its value
+struct PassedPropScan<'a> {
+    lines: &'a LineIndex,
+    props: Vec<PassedProp>,
+}
+
+impl Visit for PassedPropScan<'_> {
+    fn visit_jsx_opening_element(&mut self, el: &JSXOpeningElement) {
+        if let Some(element) = jsx_element_name(&el.name).filter(|n| is_component_element(n)) {
+            for attr in &el.attrs {
+                let JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(prop), span, .. }) = attr else { continue };
+                let (line, column) = self.lines.line_col(span.lo);
+                self.props.push(PassedProp { element: element.clone(), prop: prop.sym.to_string(), line, column });
+            }
+        }
+        el.visit_children_with(self);
+    }
+}
+
+fn passed_props<N>(node: &N, lines: &LineIndex) -> Vec<PassedProp>
+where
+    N: for<'a> VisitWith<PassedPropScan<'a>>,
+{
+    let mut scan = PassedPropScan { lines, props: Vec::new() };
+    node.visit_with(&mut scan);
+    scan.props
+}
+
+/// JSX (or `createElement`) anywhere in a component, render callbacks included
+#[derive(Default)]
+struct ContainsJsx(bool);
+
+impl Visit for ContainsJsx {
+    fn visit_jsx_element(&mut self, _: &JSXElement) { self.0 = true; }
+    fn visit_jsx_fragment(&mut self, _: &JSXFragment) { self.0 = true; }
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        let creates = match &call.callee {
+            Callee::Expr(e) => match &**e {
+                Expr::Ident(i) => i.sym.as_ref() == "createElement",
+                Expr::Member(MemberExpr { prop: MemberProp::Ident(p), .. }) => p.sym.as_ref() == "createElement",
+                _ => false,
+            },
+            _ => false,
+        };
+        self.0 |= creates;
+        call.visit_children_with(self);
+    }
+}
+
+fn renders_jsx<N: VisitWith<ContainsJsx>>(node: &N) -> bool {
+    let mut jsx = ContainsJsx::default();
+    node.visit_with(&mut jsx);
+    jsx.0
+}
+
+/// Distinct hooks a component calls, in call order; `useContext` keeps its argument
+/// (`useContext(AuthCtx)`) so context consumers can be looked up
+#[derive(Default)]
+struct HookCalls(Vec<String>);
+
+impl Visit for HookCalls {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            if let Some(mut hook) = hook_name(callee) {
+                if hook == "useContext" {
+                    let context = match call.args.first().map(|a| &*a.expr) {
+                        Some(Expr::Ident(id)) => Some(id.sym.to_string()),
+                        Some(Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(p), .. })) => expr_ident_name(obj).map(|o| format!("{}.{}", o, p.sym)),
+                        _ => None,
+                    };
+                    if let Some(context) = context {
+                        hook = format!("useContext({})", context);
+                    }
+                }
+                if !self.0.contains(&hook) {
+                    self.0.push(hook);
+                }
+            }
+        }
+        call.visit_children_with(self);
+    }
+    // hooks can't be called from callbacks; those belong to no component
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+}
+
+fn hooks_used<N: VisitWith<HookCalls>>(body: &N) -> Vec<String> {
+    let mut hooks = HookCalls::default();
+    body.visit_with(&mut hooks);
+    hooks.0
+}
+
+/// `hooks_used` of an arrow or function expression's body
+fn fn_expr_hooks(expr: &Expr) -> Vec<String> {
+    match expr {
+        Expr::Arrow(a) => hooks_used(&*a.body),
+        Expr::Fn(f) => hooks_used(&f.function.body),
+        Expr::Paren(p) => fn_expr_hooks(&p.expr),
+        _ => Vec::new(),
+    }
+}
+
+/// `x` of each `const [x, setX] = useState(..)`/`useReducer(..)` a component declares
+#[derive(Default)]
+struct StateNames(Vec<String>);
+
+impl Visit for StateNames {
+    fn visit_var_declarator(&mut self, d: &VarDeclarator) {
+        if let (Pat::Array(arr), Some(Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }))) = (&d.name, d.init.as_deref()) {
+            if matches!(hook_name(callee).as_deref(), Some("useState" | "useReducer")) {
+                if let Some(Some(Pat::Ident(state))) = arr.elems.first() {
+                    self.0.push(state.sym.to_string());
+                }
+            }
+        }
+        d.visit_children_with(self);
+    }
+    // as with hooks, callbacks declare no state
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+}
+
+fn state_names<N: VisitWith<StateNames>>(body: &N) -> Vec<String> {
+    let mut names = StateNames::default();
+    body.visit_with(&mut names);
+    names.0
+}
+
+/// `state_names` of an arrow or function expression's body
+fn fn_expr_state_names(expr: &Expr) -> Vec<String> {
+    match expr {
+        Expr::Arrow(a) => state_names(&*a.body),
+        Expr::Fn(f) => state_names(&f.function.body),
+        Expr::Paren(p) => fn_expr_state_names(&p.expr),
+        _ => Vec::new(),
+    }
+}
+
 fn expr_ident_name(expr: &Expr) -> Option<String> {
     match expr { Expr::Ident(i) => Some(i.sym.to_string()), _ => None }
 }
 
+fn module_export_name(n: &ModuleExportName) -> String {
+    match n { ModuleExportName::Ident(i) => i.sym.to_string(), ModuleExportName::Str(st) => st.value.to_string() }
+}
+
 fn extract_import_specifier(s: &ImportSpecifier) -> ImportSpecifierMeta {
     match s {
         ImportSpecifier::Named(n) => ImportSpecifierMeta {
             local: n.local.sym.to_string(),
-            imported: n.imported.as_ref().map(|im| match im { ModuleExportName::Ident(i) => i.sym.to_string(), ModuleExportName::Str(st) => st.value.to_string() }),
+            imported: n.imported.as_ref().map(module_export_name),
         },
         ImportSpecifier::Default(d) => ImportSpecifierMeta { local: d.local.sym.to_string(), imported: Some("default".into()) },
         ImportSpecifier::Namespace(ns) => ImportSpecifierMeta { local: ns.local.sym.to_string(), imported: Some("*".into()) },
     }
 }
 
-fn params_to_props(params: &[Param]) -> Vec<PropInfo> {
+/// `x as const`, `Object.freeze(x)` and parentheses don't change what `x` creates
+fn strip_const_wrappers(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::TsConstAssertion(c) => strip_const_wrappers(&c.expr),
+        Expr::Paren(p) => strip_const_wrappers(&p.expr),
+        Expr::Call(CallExpr { callee: Callee::Expr(callee), args, .. }) if args.len() == 1 && is_object_freeze(callee) => strip_const_wrappers(&args[0].expr),
+        _ => expr,
+    }
+}
+
+fn is_object_freeze(expr: &Expr) -> bool {
+    matches!(expr, Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(p), .. })
+        if matches!(&**obj, Expr::Ident(o) if o.sym.as_ref() == "Object") && p.sym.as_ref() == "freeze")
+}
+
+/// `const` bindings at module scope: created once, so stable whatever they hold
+/// (plain literals, `{...} as const`, `Object.freeze([...])`)
+fn module_consts(module: &Module) -> HashMap<String, PropKind> {
+    module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(v))) => Some(v),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl: Decl::Var(v), .. })) => Some(v),
+            _ => None,
+        })
+        .filter(|v| v.kind == VarDeclKind::Const)
+        .flat_map(|v| v.decls.iter())
+        .filter_map(|d| {
+            let id = d.name.as_ident()?;
+            let kind = d.init.as_deref().and_then(inline_prop_kind).unwrap_or(PropKind::Primitive);
+            Some((id.sym.to_string(), kind))
+        })
+        .collect()
+}
+
+/// Kind of a default prop value and whether it keeps its identity across renders:
+/// literals and module-scope consts do, while an object, array or function written
+/// in the default (even `as const` or frozen) is created again on every render.
+/// Any other identifier (a `let`, an import, another prop) isn't known to be stable.
+fn default_prop(value: &Expr, module_consts: &HashMap<String, PropKind>) -> (PropKind, bool) {
+    match strip_const_wrappers(value) {
+        Expr::Ident(i) if i.sym.as_ref() == "undefined" => (PropKind::Primitive, true),
+        Expr::Ident(i) => match module_consts.get(i.sym.as_ref()) {
+            Some(kind) => (kind.clone(), true),
+            None => (PropKind::Primitive, false),
+        },
+        Expr::Lit(_) | Expr::Tpl(_) => (PropKind::Primitive, true),
+        other => match inline_prop_kind(other) {
+            Some(kind) => (kind, false),
+            None => (PropKind::Primitive, false),
+        },
+    }
+}
+
+fn params_to_props(params: &[Param], lines: &LineIndex, module_consts: &HashMap<String, PropKind>) -> Vec<PropInfo> {
     let mut props = Vec::new();
     for p in params {
         match &p.pat {
-            Pat::Ident(bi) => props.push(PropInfo { name: bi.sym.to_string(), kind: PropKind::Primitive, is_stable: true, line: span_line(bi.id.span) }),
-            Pat::Object(_) => props.push(PropInfo { name: "props".into(), kind: PropKind::Object, is_stable: false, line: span_line(p.span) }),
-            Pat::Array(_) => props.push(PropInfo { name: "props".into(), kind: PropKind::Array, is_stable: false, line: span_line(p.span) }),
-            _ => props.push(PropInfo { name: "arg".into(), kind: PropKind::Primitive, is_stable: true, line: span_line(p.span) }),
+            Pat::Ident(bi) => props.push(PropInfo { name: bi.sym.to_string(), kind: PropKind::Primitive, is_stable: true, line: lines.line(bi.id.span.lo) }),
+            Pat::Object(o) => {
+                props.push(PropInfo { name: "props".into(), kind: PropKind::Object, is_stable: false, line: lines.line(p.span.lo) });
+                // `{ items = EMPTY }` / `{ style: s = {} }`: one entry per defaulted prop
+                for prop in &o.props {
+                    let (name, value, span) = match prop {
+                        ObjectPatProp::Assign(a) => match &a.value {
+                            Some(v) => (a.key.sym.to_string(), &**v, a.span),
+                            None => continue,
+                        },
+                        ObjectPatProp::KeyValue(KeyValuePatProp { key: PropName::Ident(k), value }) => match &**value {
+                            Pat::Assign(ap) => (k.sym.to_string(), &*ap.right, ap.span),
+                            _ => continue,
+                        },
+                        _ => continue,
+                    };
+                    let (kind, is_stable) = default_prop(value, module_consts);
+                    props.push(PropInfo { name, kind, is_stable, line: lines.line(span.lo) });
+                }
+            }
+            Pat::Array(_) => props.push(PropInfo { name: "props".into(), kind: PropKind::Array, is_stable: false, line: lines.line(p.span.lo) }),
+            Pat::Assign(ap) => {
+                let name = ap.left.as_ident().map(|bi| bi.sym.to_string()).unwrap_or_else(|| "props".into());
+                let (kind, is_stable) = default_prop(&ap.right, module_consts);
+                props.push(PropInfo { name, kind, is_stable, line: lines.line(p.span.lo) });
+            }
+            _ => props.push(PropInfo { name: "arg".into(), kind: PropKind::Primitive, is_stable: true, line: lines.line(p.span.lo) }),
         }
     }
     props
 }
 
+/// Props destructured from the first parameter (`{ a, b = 1, ...rest }` counts 2); 0 when not destructured
+pub(crate) fn prop_count(first: Option<&Pat>) -> usize {
+    match first {
+        Some(Pat::Object(o)) => o.props.iter().filter(|p| !matches!(p, ObjectPatProp::Rest(_))).count(),
+        Some(Pat::Assign(ap)) => prop_count(Some(&ap.left)),
+        _ => 0,
+    }
+}
+
+impl MetadataExtractor {
+    /// Remember a component's body (block or arrow expression) for the render-time rules
+    fn record_render_body(&mut self, span: Span) {
+        self.render_bodies.push(span);
+    }
+
+    fn mark_memoized(&mut self, comp_name: &str, span: Span) {
+        if let Some(c) = self.components.iter_mut().find(|c| c.name == comp_name) {
+            c.is_memoized = true;
+        } else {
+            self.components.push(ComponentMeta {
+                name: comp_name.to_string(),
+                file_path: String::new(),
+                is_memoized: true,
+                props: vec![],
+                prop_count: 0,
+                exports: vec![],
+                line: self.lines.line(span.lo),
+                inline_props: vec![],
+                doc: None,
+                // the wrapped component may be defined elsewhere; don't report it as JSX-less
+                renders_jsx: true,
+                hooks_used: vec![],
+                passed_props: vec![],
+                state_names: vec![],
+            });
+        }
+    }
+
+    /// `const Name = memo(<arrow or function>)`: the wrapped function is the component
+    fn push_memo_inline(&mut self, name: &str, wrapped: &Expr, span: Span) {
+        let (props, count) = match wrapped {
+            Expr::Arrow(a) => {
+                match &*a.body {
+                    BlockStmtOrExpr::BlockStmt(b) => self.record_render_body(b.span),
+                    BlockStmtOrExpr::Expr(e) => self.record_render_body(e.span()),
+                }
+                (vec![], prop_count(a.params.first()))
+            }
+            Expr::Fn(f) => {
+                if let Some(b) = &f.function.body { self.record_render_body(b.span); }
+                (params_to_props(&f.function.params, &self.lines, &self.module_consts), prop_count(f.function.params.first().map(|p| &p.pat)))
+            }
+            _ => return,
+        };
+        self.components.push(ComponentMeta {
+            name: name.to_string(),
+            file_path: String::new(),
+            is_memoized: true,
+            props,
+            prop_count: count,
+            exports: vec![],
+            line: self.lines.line(span.lo),
+            inline_props: inline_props(wrapped, &self.lines),
+            doc: None,
+            renders_jsx: renders_jsx(wrapped),
+            hooks_used: fn_expr_hooks(wrapped),
+            passed_props: passed_props(wrapped, &self.lines),
+            state_names: fn_expr_state_names(wrapped),
+        });
+    }
+
+    /// Point default exports at their local symbol once the whole module is seen
+    fn resolve_default_export(&mut self) {
+        let Some((local, _)) = &self.default_export_local else { return };
+        for e in self.exports.iter_mut().filter(|e| matches!(e.kind, ExportKind::Default)) {
+            e.local = Some(local.clone());
+        }
+    }
+}
+
 impl Visit for MetadataExtractor {
     fn visit_fn_decl(&mut self, func: &FnDecl) {
         // Heurística simples: considerar qualquer FnDecl como "componente" potencial
         // Se o nome começa com maiúscula, tratamos como React component.
         let name = func.ident.sym.to_string();
-        let is_component = name.chars().next().map(|c| c.is_uppercase()).unwrap_or(false);
+        let is_component = is_component_name(&name);
         if is_component {
-            let props = params_to_props(&func.function.params);
+            let props = params_to_props(&func.function.params, &self.lines, &self.module_consts);
             self.components.push(ComponentMeta {
                 name: name.clone(),
                 file_path: String::new(),
                 is_memoized: false,
                 props,
+                prop_count: prop_count(func.function.params.first().map(|p| &p.pat)),
                 exports: vec![],
-                line: span_line(func.ident.span),
+                line: self.lines.line(func.ident.span.lo),
+                inline_props: inline_props(&func.function.body, &self.lines),
+                doc: None,
+                renders_jsx: renders_jsx(&func.function.body),
+                hooks_used: hooks_used(&func.function.body),
+                passed_props: passed_props(&func.function.body, &self.lines),
+                state_names: state_names(&func.function.body),
             });
+            if let Some(body) = &func.function.body {
+                self.record_render_body(body.span);
+            }
         }
         func.visit_children_with(self);
     }
@@ -107,7 +625,7 @@ impl Visit for MetadataExtractor {
         // Detectar const Comp = React.memo(...) ou const Comp = () => <JSX/>
         if let Some(Ident { sym, span, .. }) = d.name.as_ident() {
             let name = sym.to_string();
-            let is_component = name.chars().next().map(|c| c.is_uppercase()).unwrap_or(false);
+            let is_component = is_component_name(&name);
             if let Some(init) = &d.init {
                 match &**init {
                     Expr::Call(CallExpr { callee, args, .. }) => {
@@ -116,31 +634,44 @@ impl Visit for MetadataExtractor {
                             // Primeiro arg deve ser ident do componente
                             if let Some(first) = args.get(0).map(|a| &*a.expr) {
                                 if let Some(comp_name) = expr_ident_name(first) {
-                                    if let Some(c) = self.components.iter_mut().find(|c| c.name == comp_name) {
-                                        c.is_memoized = true;
-                                    } else {
-                                        self.components.push(ComponentMeta {
-                                            name: comp_name,
-                                            file_path: String::new(),
-                                            is_memoized: true,
-                                            props: vec![],
-                                            exports: vec![],
-                                            line: span_line(*span),
-                                        });
-                                    }
+                                    self.mark_memoized(&comp_name, *span);
+                                } else if is_component {
+                                    // ou o componente inline: memo((props) => ...) / memo(function X() {})
+                                    self.push_memo_inline(&name, first, *span);
                                 }
                             }
                         }
                     }
                     Expr::Arrow(_) | Expr::Fn(_) => {
                         if is_component {
+                            match &**init {
+                                Expr::Arrow(ArrowExpr { body, .. }) => match &**body {
+                                    BlockStmtOrExpr::BlockStmt(b) => self.record_render_body(b.span),
+                                    BlockStmtOrExpr::Expr(e) => self.record_render_body(e.span()),
+                                },
+                                Expr::Fn(FnExpr { function, .. }) => {
+                                    if let Some(b) = &function.body { self.record_render_body(b.span); }
+                                }
+                                _ => {}
+                            }
                             self.components.push(ComponentMeta {
                                 name: name.clone(),
                                 file_path: String::new(),
                                 is_memoized: false,
                                 props: vec![],
+                                prop_count: match &**init {
+                                    Expr::Arrow(a) => prop_count(a.params.first()),
+                                    Expr::Fn(f) => prop_count(f.function.params.first().map(|p| &p.pat)),
+                                    _ => 0,
+                                },
                                 exports: vec![],
-                                line: span_line(*span),
+                                line: self.lines.line(span.lo),
+                                inline_props: inline_props(&**init, &self.lines),
+                                doc: None,
+                                renders_jsx: renders_jsx(&**init),
+                                hooks_used: fn_expr_hooks(init),
+                                passed_props: passed_props(&**init, &self.lines),
+                                state_names: fn_expr_state_names(init),
                             });
                         }
                     }
@@ -151,57 +682,562 @@ impl Visit for MetadataExtractor {
         d.visit_children_with(self);
     }
 
-    fn visit_call_expr(&mut self, call: &CallExpr) {
-        // já marcado em var_declarator quando possível
-        call.visit_children_with(self);
-    }
-
     fn visit_import_decl(&mut self, import: &ImportDecl) {
         let specifiers = import.specifiers.iter().map(extract_import_specifier).collect();
-        self.imports.push(ImportMeta { source: import.src.value.to_string(), specifiers, line: span_line(import.span) });
+        let source = import.src.value.to_string();
+        let kind = classify_import_source(&source, &PathAliases::default());
+        self.imports.push(ImportMeta { source, kind, specifiers, line: self.lines.line(import.span.lo) });
     }
 
     fn visit_export_decl(&mut self, export: &ExportDecl) {
         match &export.decl {
-            Decl::Fn(func) => self.exports.push(ExportInfo { name: func.ident.sym.to_string(), kind: ExportKind::Named, line: span_line(export.span) }),
+            Decl::Fn(func) => {
+                self.exports.push(ExportInfo { name: func.ident.sym.to_string(), kind: ExportKind::Named, line: self.lines.line(export.span.lo), local: None, source: None });
+                self.named_export_locals.push((func.ident.sym.to_string(), export.span));
+            }
             Decl::Var(v) => {
                 for d in &v.decls {
                     if let Some(id) = d.name.as_ident() {
-                        self.exports.push(ExportInfo { name: id.sym.to_string(), kind: ExportKind::Named, line: span_line(export.span) });
+                        self.exports.push(ExportInfo { name: id.sym.to_string(), kind: ExportKind::Named, line: self.lines.line(export.span.lo), local: None, source: None });
+                        self.named_export_locals.push((id.sym.to_string(), export.span));
                     }
                 }
             }
             _ => {}
         }
+        // descend so exported components are analyzed too
+        export.visit_children_with(self);
     }
 
     fn visit_export_default_expr(&mut self, e: &ExportDefaultExpr) {
         // name is not obvious; mark as default
-        self.exports.push(ExportInfo { name: "default".into(), kind: ExportKind::Default, line: span_line(e.span) });
+        self.exports.push(ExportInfo { name: "default".into(), kind: ExportKind::Default, line: self.lines.line(e.span.lo), local: None, source: None });
+        if let Some(local) = expr_ident_name(&e.expr) {
+            self.default_export_local = Some((local, e.span));
+        } else if let Expr::Call(CallExpr { callee: Callee::Expr(callee), args, .. }) = &*e.expr {
+            // export default memo(Comp)
+            if is_identifier_react_memo(callee) {
+                if let Some(local) = args.first().and_then(|a| expr_ident_name(&a.expr)) {
+                    self.mark_memoized(&local, e.span);
+                    self.default_export_local = Some((local, e.span));
+                }
+            }
+        }
+        e.visit_children_with(self);
+    }
+
+    fn visit_export_default_decl(&mut self, e: &ExportDefaultDecl) {
+        self.exports.push(ExportInfo { name: "default".into(), kind: ExportKind::Default, line: self.lines.line(e.span.lo), local: None, source: None });
+        let local = match &e.decl {
+            DefaultDecl::Fn(f) => f.ident.as_ref(),
+            DefaultDecl::Class(c) => c.ident.as_ref(),
+            _ => None,
+        };
+        if let Some(id) = local {
+            self.default_export_local = Some((id.sym.to_string(), e.span));
+        }
+        e.visit_children_with(self);
     }
+
+    fn visit_named_export(&mut self, e: &NamedExport) {
+        // re-exports (`export { x } from './y'`) don't refer to local symbols
+        if let Some(src) = &e.src {
+            let line = self.lines.line(e.span.lo);
+            for spec in &e.specifiers {
+                let name = match spec {
+                    ExportSpecifier::Named(n) => n.exported.as_ref().map(module_export_name).unwrap_or_else(|| module_export_name(&n.orig)),
+                    ExportSpecifier::Namespace(ns) => module_export_name(&ns.name),
+                    ExportSpecifier::Default(d) => d.exported.sym.to_string(),
+                };
+                let kind = if name == "default" { ExportKind::Default } else { ExportKind::Named };
+                self.exports.push(ExportInfo { name, kind, line, local: None, source: Some(src.value.to_string()) });
+            }
+            return;
+        }
+        for spec in &e.specifiers {
+            if let ExportSpecifier::Named(n) = spec {
+                let local = module_export_name(&n.orig);
+                let exported = n.exported.as_ref().map(module_export_name).unwrap_or_else(|| local.clone());
+                if exported == "default" {
+                    self.exports.push(ExportInfo { name: "default".into(), kind: ExportKind::Default, line: self.lines.line(n.span.lo), local: None, source: None });
+                    self.default_export_local = Some((local, n.span));
+                } else {
+                    self.exports.push(ExportInfo { name: exported, kind: ExportKind::Named, line: self.lines.line(n.span.lo), local: None, source: None });
+                    self.named_export_locals.push((local, n.span));
+                }
+            }
+        }
+    }
+
+    fn visit_export_all(&mut self, e: &ExportAll) {
+        let source = Some(e.src.value.to_string());
+        self.exports.push(ExportInfo { name: "*".into(), kind: ExportKind::Named, line: self.lines.line(e.span.lo), local: None, source });
+    }
+}
+
+fn parse_module(source: &str, filename: &str, comments: Option<&SingleThreadedComments>) -> Option<(Module, LineIndex)> {
+    let (module, fm) = parse_source(source, filename, comments.map(|c| c as &dyn Comments)).ok()?;
+    Some((module, LineIndex::new(&fm.src, fm.start_pos)))
+}
+
+/// Keep only the `<script>`/`<script setup>` contents of a Vue SFC, blanking everything
+/// else byte for byte (newlines kept) so positions still point into the `.vue` file.
+/// Also returns the extension the blocks parse as: `lang="tsx"`/`"jsx"`, else `.ts`.
+pub fn vue_script_source(sfc: &str) -> (String, &'static str) {
+    let mut keep = Vec::new();
+    let mut ext = ".ts";
+    let mut from = 0;
+    while let Some(open) = sfc[from..].find("<script").map(|i| from + i) {
+        let after = open + "<script".len();
+        if !sfc[after..].starts_with(|c: char| c == '>' || c.is_whitespace()) {
+            from = after;
+            continue;
+        }
+        let Some(tag_end) = sfc[after..].find('>').map(|i| after + i) else { break };
+        let attrs = &sfc[after..tag_end];
+        if attrs.contains("lang=\"tsx\"") || attrs.contains("lang='tsx'") {
+            ext = ".tsx";
+        } else if (attrs.contains("lang=\"jsx\"") || attrs.contains("lang='jsx'")) && ext == ".ts" {
+            ext = ".jsx";
+        }
+        let body = tag_end + 1;
+        let close = sfc[body..].find("</script>").map(|i| body + i).unwrap_or(sfc.len());
+        keep.push(body..close);
+        from = close;
+    }
+    let bytes = sfc
+        .bytes()
+        .enumerate()
+        .map(|(i, b)| if b == b'\n' || keep.iter().any(|r| r.contains(&i)) { b } else { b' ' })
+        .collect();
+    // blocks are delimited by ASCII, so the kept bytes are whole characters
+    (String::from_utf8(bytes).unwrap_or_default(), ext)
+}
+
+/// Keep only the frontmatter of an Astro component (between the `---` fence opening the
+/// file and the next `---` line), blanked around like `vue_script_source`. A file without
+/// frontmatter comes back all blank.
+pub fn astro_frontmatter_source(astro: &str) -> String {
+    let mut keep = 0..0;
+    let mut offset = 0;
+    let mut opened = None;
+    for line in astro.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        if line.trim().is_empty() && opened.is_none() {
+            continue;
+        }
+        match opened {
+            None if line.trim_end() == "---" => opened = Some(offset),
+            // content before the fence: no frontmatter
+            None => break,
+            Some(body) if line.trim_end() == "---" => {
+                keep = body..start;
+                break;
+            }
+            Some(_) => {}
+        }
+    }
+    let bytes = astro.bytes().enumerate().map(|(i, b)| if b == b'\n' || keep.contains(&i) { b } else { b' ' }).collect();
+    // the fences are ASCII lines, so the kept bytes are whole characters
+    String::from_utf8(bytes).unwrap_or_default()
 }
 
-fn parse_module(source: &str, filename: &str) -> Option<Module> {
-    let cm: Lrc<SourceMap> = Default::default();
-    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm.clone()));
-    let fname = FileName::Custom(filename.to_string());
-    let fm = cm.new_source_file(fname, source.into());
-    let input = StringInput::from(&*fm);
-    let is_ts = filename.ends_with(".ts") || filename.ends_with(".tsx") || filename.ends_with(".d.ts");
-    let syntax = if is_ts { Syntax::Typescript(TsConfig { tsx: filename.ends_with(".tsx"), decorators: true, dts: filename.ends_with(".d.ts"), no_early_errors: true, ..Default::default() }) } else { Syntax::Es(EsConfig { jsx: filename.ends_with(".jsx") || filename.ends_with(".tsx"), decorators: true, ..Default::default() }) };
-    let mut p = Parser::new(syntax, input, None);
-    match p.parse_module() {
-        Ok(m) => Some(m),
-        Err(e) => { e.into_diagnostic(&handler).emit(); None }
+/// Text of the comment right before `pos`: a `/** */` block without its `*` gutter,
+/// or a run of `//` lines
+fn leading_doc(comments: &SingleThreadedComments, pos: BytePos) -> Option<String> {
+    let leading = comments.get_leading(pos)?;
+    let last = leading.last()?;
+    let text = match last.kind {
+        CommentKind::Block => last
+            .text
+            .lines()
+            .map(|l| l.trim().trim_start_matches('*').trim())
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        CommentKind::Line => {
+            let run = leading.iter().rev().take_while(|c| c.kind == CommentKind::Line).count();
+            leading[leading.len() - run..].iter().map(|c| c.text.trim()).collect::<Vec<_>>().join("\n")
+        }
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// Fill `ComponentMeta::doc` from the comment leading each top-level declaration
+fn attach_docs(components: &mut [ComponentMeta], module: &Module, comments: &SingleThreadedComments) {
+    for item in &module.body {
+        let decl = match item {
+            ModuleItem::Stmt(Stmt::Decl(d)) => Some(d),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(e)) => Some(&e.decl),
+            _ => None,
+        };
+        let names: Vec<String> = match (item, decl) {
+            (_, Some(Decl::Fn(f))) => vec![f.ident.sym.to_string()],
+            (_, Some(Decl::Var(v))) => v.decls.iter().filter_map(|d| d.name.as_ident().map(|id| id.sym.to_string())).collect(),
+            (ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(ExportDefaultDecl { decl: DefaultDecl::Fn(f), .. })), _) => {
+                f.ident.iter().map(|id| id.sym.to_string()).collect()
+            }
+            _ => continue,
+        };
+        let Some(doc) = leading_doc(comments, item.span().lo) else { continue };
+        for c in components.iter_mut().filter(|c| names.contains(&c.name) && c.doc.is_none()) {
+            c.doc = Some(doc.clone());
+        }
     }
 }
 
-pub fn extract_all(source: &str, filename: &str) -> (Vec<ComponentMeta>, Vec<ImportMeta>, Vec<ExportInfo>) {
-    if let Some(module) = parse_module(source, filename) {
-        let mut ex = MetadataExtractor::default();
+/// Lines between `perf-linter-disable [types]` and the matching `perf-linter-enable [types]`
+/// comment (or the end of the file); `types` is `None` when the comment names no finding type
+struct DisabledRange {
+    types: Option<Vec<String>>,
+    start: usize,
+    end: usize,
+}
+
+/// `JsonInRender`, `json-in-render` and `json_in_render` all name the same finding type
+pub(crate) fn normalize_type(name: &str) -> String {
+    name.chars().filter(|c| *c != '-' && *c != '_').flat_map(char::to_lowercase).collect()
+}
+
+fn disabled_ranges(comments: &SingleThreadedComments, lines: &LineIndex) -> Vec<DisabledRange> {
+    let (leading, trailing) = comments.borrow_all();
+    let mut all: Vec<_> = leading.values().chain(trailing.values()).flatten().collect();
+    all.sort_by_key(|c| c.span.lo);
+    let mut ranges = Vec::new();
+    // open `disable` comments: normalized type list -> (types, start line)
+    let mut open: Vec<(Option<Vec<String>>, usize)> = Vec::new();
+    for comment in all {
+        let text = comment.text.trim().trim_start_matches('*').trim();
+        let (disable, rest) = if let Some(rest) = text.strip_prefix("perf-linter-disable") {
+            (true, rest)
+        } else if let Some(rest) = text.strip_prefix("perf-linter-enable") {
+            (false, rest)
+        } else {
+            continue;
+        };
+        // `perf-linter-disable-next-line` and friends aren't block directives
+        if rest.starts_with(|c: char| !c.is_whitespace()) {
+            continue;
+        }
+        let names: Vec<String> = rest.split(|c: char| c == ',' || c.is_whitespace()).filter(|n| !n.is_empty()).map(normalize_type).collect();
+        let types = (!names.is_empty()).then_some(names);
+        let line = lines.line(comment.span.lo);
+        if disable {
+            if !open.iter().any(|(t, _)| *t == types) {
+                open.push((types, line));
+            }
+        } else if let Some(i) = open.iter().position(|(t, _)| *t == types) {
+            let (types, start) = open.remove(i);
+            ranges.push(DisabledRange { types, start, end: line });
+        }
+    }
+    ranges.extend(open.into_iter().map(|(types, start)| DisabledRange { types, start, end: usize::MAX }));
+    ranges
+}
+
+/// Drop findings inside a block the file's comments disable
+fn retain_enabled(findings: &mut Vec<Finding>, ranges: &[DisabledRange]) {
+    findings.retain(|f| {
+        let kind = normalize_type(f.kind.type_name());
+        !ranges.iter().any(|r| r.start <= f.line && f.line <= r.end && r.types.as_ref().map_or(true, |t| t.contains(&kind)))
+    });
+}
+
+/// Analyze source that needn't exist on disk with the built-in rules; `filename` only
+/// picks the syntax (`.tsx`, `.vue`, ...). None when the file doesn't parse.
+pub fn analyze_source(source: &str, filename: &str) -> Option<FileAnalysis> {
+    extract_all(source, filename).map(Into::into)
+}
+
+/// Extract with the built-in rules; None when the file doesn't parse
+pub fn extract_all(source: &str, filename: &str) -> Option<Extracted> {
+    extract_all_with_rules(source, filename, &RuleRegistry::builtin())
+}
+
+/// `extract_all`, reporting only the findings of the rules in `registry`; None when the file doesn't parse
+pub fn extract_all_with_rules(source: &str, filename: &str, registry: &RuleRegistry) -> Option<Extracted> {
+    extract_all_with_options(source, filename, registry, ExtractOptions::default())
+}
+
+/// `extract_all_with_rules` doing the opt-in work in `options` as well
+pub fn extract_all_with_options(source: &str, filename: &str, registry: &RuleRegistry, options: ExtractOptions) -> Option<Extracted> {
+    let script;
+    let (source, syntax_hint) = if filename.ends_with(".vue") {
+        let (blocks, ext) = vue_script_source(source);
+        script = blocks;
+        (script.as_str(), format!("{}{}", filename, ext))
+    } else if filename.ends_with(".astro") {
+        script = astro_frontmatter_source(source);
+        (script.as_str(), format!("{}.ts", filename))
+    } else {
+        (source, filename.to_string())
+    };
+    // comments are only kept when something reads them
+    let comments = (options.collect_docs || source.contains("perf-linter-")).then(SingleThreadedComments::default);
+    if let Some((module, lines)) = parse_module(source, &syntax_hint, comments.as_ref()) {
+        let mut ex = MetadataExtractor { lines, module_consts: module_consts(&module), ..Default::default() };
         module.visit_with(&mut ex);
-        (ex.components, ex.imports, ex.exports)
+        ex.resolve_default_export();
+        if let Some(comments) = comments.as_ref().filter(|_| options.collect_docs) {
+            attach_docs(&mut ex.components, &module, comments);
+        }
+        let ctx = FileContext {
+            module: &module,
+            lines: &ex.lines,
+            render_bodies: &ex.render_bodies,
+            named_export_locals: &ex.named_export_locals,
+            default_export_local: ex.default_export_local.as_ref(),
+        };
+        let mut findings = registry.check(&ctx);
+        if let Some(comments) = &comments {
+            retain_enabled(&mut findings, &disabled_ranges(comments, &ex.lines));
+        }
+        Some((ex.components, ex.imports, ex.exports, findings))
     } else {
-        (Vec::new(), Vec::new(), Vec::new())
+        None
+    }
+}
+
+/// A parse handed to the pool; returns true when nobody was waiting for its result anymore
+type ParseJob = Box<dyn FnOnce() -> bool + Send>;
+
+/// Parse workers shared by every `extract_all_with_timeout` call, one per core. A worker
+/// stuck on an abandoned parse is replaced, up to one spare per core, and exits once that
+/// parse finishes; past that limit the pool just runs short until stuck workers free up.
+struct ParsePool {
+    jobs: mpsc::Sender<ParseJob>,
+    queue: Arc<Mutex<mpsc::Receiver<ParseJob>>>,
+    size: usize,
+    /// Replacements started for stuck workers that haven't exited yet
+    spares: Arc<AtomicUsize>,
+}
+
+impl ParsePool {
+    /// The shared pool; None when no worker thread can be started (e.g. on wasm)
+    fn shared() -> Option<&'static ParsePool> {
+        static POOL: OnceLock<Option<ParsePool>> = OnceLock::new();
+        POOL.get_or_init(|| {
+            let (jobs, queue) = mpsc::channel();
+            let size = thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+            let pool = ParsePool { jobs, queue: Arc::new(Mutex::new(queue)), size, spares: Arc::new(AtomicUsize::new(0)) };
+            let started = (0..size).filter(|_| pool.spawn_worker()).count();
+            (started > 0).then_some(pool)
+        })
+        .as_ref()
+    }
+
+    fn spawn_worker(&self) -> bool {
+        let queue = self.queue.clone();
+        let spares = self.spares.clone();
+        let worker = move || loop {
+            let job = queue.lock().unwrap_or_else(|e| e.into_inner()).recv();
+            let Ok(job) = job else { return };
+            // a spare took over while this worker was stuck
+            if job() && spares.fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1)).is_ok() {
+                return;
+            }
+        };
+        thread::Builder::new().name("perf-linter-parse".into()).spawn(worker).is_ok()
+    }
+
+    /// Start a spare for a worker stuck on an abandoned parse, unless the spares are used up
+    fn replace_stuck_worker(&self) {
+        if self.spares.fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < self.size).then_some(n + 1)).is_ok() && !self.spawn_worker() {
+            self.spares.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
+
+/// What a parse job sends back to the caller waiting on it
+enum ParseEvent {
+    /// A worker dequeued the job; the timeout counts from here
+    Started(Instant),
+    Done(Option<Extracted>),
+}
+
+/// Run `extract_all` on the shared parse pool and give up `timeout` after a worker picks it
+/// up, so a single pathological file can't stall indexing while time spent queued behind
+/// other files doesn't count. The abandoned parse keeps its worker until it finishes, and
+/// its result is discarded.
+pub fn extract_all_with_timeout(source: &str, filename: &str, timeout: Duration, rules: Arc<RuleRegistry>, options: ExtractOptions) -> Result<Option<Extracted>, ParseTimeout> {
+    let Some(pool) = ParsePool::shared() else {
+        // no thread available: parse inline rather than dropping the file
+        return Ok(extract_all_with_options(source, filename, &rules, options));
+    };
+    let (tx, rx) = mpsc::channel();
+    let src = source.to_string();
+    let fname = filename.to_string();
+    let job: ParseJob = Box::new(move || {
+        let _ = tx.send(ParseEvent::Started(Instant::now()));
+        tx.send(ParseEvent::Done(extract_all_with_options(&src, &fname, &rules, options))).is_err()
+    });
+    if let Err(mpsc::SendError(job)) = pool.jobs.send(job) {
+        job();
+    }
+    let started = match rx.recv() {
+        Ok(ParseEvent::Started(at)) => at,
+        Ok(ParseEvent::Done(result)) => return Ok(result),
+        // dropped without running; reported like a parse that never finished
+        Err(_) => Instant::now(),
+    };
+    match rx.recv_timeout(timeout.saturating_sub(started.elapsed())) {
+        Ok(ParseEvent::Done(result)) => Ok(result),
+        _ => {
+            pool.replace_stuck_worker();
+            Err(ParseTimeout { path: filename.to_string(), elapsed_ms: started.elapsed().as_millis() as u64 })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn props_of(source: &str, component: &str) -> Vec<PropInfo> {
+        let (components, _, _, _) = extract_all(source, "a.tsx").expect("parses");
+        components.into_iter().find(|c| c.name == component).expect("component found").props
+    }
+
+    #[test]
+    fn test_default_props_trust_only_module_consts() {
+        let source = r#"
+            const EMPTY = Object.freeze([]);
+            const THEME = { dark: true } as const;
+            let mutable = {};
+            export function List({ items = EMPTY, theme = THEME, extra = mutable, label = 'x', onPick = () => {}, rows = [] as const }) {
+                return <ul />;
+            }
+        "#;
+        let props = props_of(source, "List");
+        let find = |name: &str| props.iter().find(|p| p.name == name).expect("prop recorded");
+        assert!(matches!(find("items"), PropInfo { kind: PropKind::Array, is_stable: true, .. }));
+        assert!(matches!(find("theme"), PropInfo { kind: PropKind::Object, is_stable: true, .. }));
+        assert!(matches!(find("extra"), PropInfo { kind: PropKind::Primitive, is_stable: false, .. }));
+        assert!(matches!(find("label"), PropInfo { kind: PropKind::Primitive, is_stable: true, .. }));
+        assert!(matches!(find("onPick"), PropInfo { kind: PropKind::Function, is_stable: false, .. }));
+        assert!(matches!(find("rows"), PropInfo { kind: PropKind::Array, is_stable: false, .. }));
+    }
+
+    #[test]
+    fn test_inline_props_see_through_const_wrappers() {
+        let source = r#"
+            const EMPTY = Object.freeze([]);
+            export function Page() {
+                return <Row items={EMPTY} style={{ margin: 0 } as const} tags={Object.freeze(['a'])} />;
+            }
+        "#;
+        let (components, _, _, _) = extract_all(source, "a.tsx").expect("parses");
+        let inline: Vec<(&str, &PropKind)> = components[0].inline_props.iter().map(|p| (p.prop.as_str(), &p.kind)).collect();
+        assert!(matches!(inline[..], [("style", PropKind::Object), ("tags", PropKind::Array)]));
+    }
+
+    #[test]
+    fn test_tsx_generic_arrows() {
+        let (components, _, _, _) = extract_all(include_str!("../../fixtures/generic-arrows.tsx"), "generic-arrows.tsx").expect("parses");
+        let names: Vec<&str> = components.iter().map(|c| c.name.as_str()).collect();
+        assert!(names.contains(&"List"));
+        assert!(names.contains(&"Select"));
+    }
+
+    #[test]
+    fn test_block_disable_comments() {
+        let source = "import { useState } from 'react';
+export function A() {
+  /* perf-linter-disable eager-state-init */
+  const [a] = useState(compute());
+  /* perf-linter-enable eager-state-init */
+  const [b] = useState(compute());
+  // perf-linter-disable
+  const [c] = useState(compute());
+  return <div>{a}{b}{c}</div>;
+}
+";
+        let (_, _, _, findings) = extract_all(source, "a.tsx").expect("parses");
+        let lines: Vec<usize> = findings.iter().filter(|f| f.kind.type_name() == "EagerStateInit").map(|f| f.line).collect();
+        assert_eq!(lines, vec![6]);
+    }
+
+    #[test]
+    fn test_end_line_of_multiline_findings() {
+        let source = "export function A({ cond }) {
+  return <div>{cond
+    ? <B />
+    : <C />}</div>;
+}
+";
+        let (_, _, _, findings) = extract_all(source, "a.tsx").expect("parses");
+        let remount = findings.iter().find(|f| f.kind.type_name() == "ConditionalRemount").unwrap();
+        assert_eq!((remount.line, remount.end_line), (2, Some(4)));
+    }
+
+    #[test]
+    fn test_astro_frontmatter() {
+        let astro = "---\nimport Card from '../components/Card.astro';\nconst { title } = Astro.props;\n---\n<h1>{title}</h1>\n";
+        let script = astro_frontmatter_source(astro);
+        assert_eq!(script.len(), astro.len());
+        assert!(script.contains("import Card from '../components/Card.astro';"));
+        assert!(!script.contains("---") && !script.contains("<h1>"));
+        let (_, imports, _, _) = extract_all(astro, "Page.astro").expect("parses");
+        assert_eq!(imports.len(), 1);
+        // markup only
+        assert!(astro_frontmatter_source("<h1>Hi</h1>\n---\n").trim().is_empty());
+    }
+
+    #[test]
+    fn test_vue_script_setup_lines_point_into_the_sfc() {
+        let sfc = r#"<template>
+  <ul><li v-for="item in items">{{ item.label }}</li></ul>
+</template>
+
+<script setup lang="ts">
+import type { Item } from './types';
+import { useState } from 'react';
+const [items] = useState<Item[]>(loadItems());
+</script>
+"#;
+        let (blocks, ext) = vue_script_source(sfc);
+        assert_eq!((blocks.len(), ext), (sfc.len(), ".ts"));
+        assert!(!blocks.contains("<template>") && !blocks.contains("<script"));
+        let (_, imports, _, findings) = extract_all(sfc, "List.vue").expect("parses as TypeScript");
+        assert_eq!(imports.len(), 2);
+        let eager = findings.iter().find(|f| f.kind.type_name() == "EagerStateInit").expect("reported");
+        assert_eq!((eager.line, eager.column), (8, 17));
+    }
+
+    #[test]
+    fn test_tsx_without_jsx_falls_back_to_typescript() {
+        let source = include_str!("../../fixtures/generic-helpers.tsx");
+        assert!(extract_all_with_rules(source, "generic-helpers.tsx", &RuleRegistry::empty()).is_some());
+    }
+
+    #[test]
+    fn test_analyze_source_rejects_syntax_errors() {
+        assert!(analyze_source("export const A = () => <div>", "a.tsx").is_none());
+        let analysis = analyze_source("export const A = () => <div />;", "a.tsx").expect("parses");
+        assert_eq!(analysis.components.len(), 1);
+    }
+
+    #[test]
+    fn test_timeout_starts_when_a_worker_picks_the_parse_up() {
+        let pool = ParsePool::shared().expect("parse pool");
+        let hold = Duration::from_millis(300);
+        let (running, busy) = mpsc::channel();
+        for _ in 0..pool.size {
+            let running = running.clone();
+            pool.jobs.send(Box::new(move || {
+                let _ = running.send(());
+                thread::sleep(hold);
+                false
+            })).unwrap();
+        }
+        for _ in 0..pool.size {
+            busy.recv().unwrap();
+        }
+        // every worker is busy for longer than the timeout; only the parse itself counts
+        let queued = Instant::now();
+        let result = extract_all_with_timeout("export const A = () => <div />;", "a.tsx", Duration::from_millis(50), Arc::new(RuleRegistry::empty()), ExtractOptions::default());
+        assert!(result.expect("not timed out").is_some());
+        assert!(queued.elapsed() >= Duration::from_millis(200));
     }
 }
@@ -0,0 +1,346 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use super::extract::{normalize_type, ImportSourceKind};
+use crate::redos::RedosIssue;
+
+/// What a finding reports; serialized with its variant name under `type`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum FindingKind {
+    /// `children` built fresh on every render (inline JSX, arrays, render functions)
+    UnstableChildren { component: String },
+    /// Hook call placed after a conditional `return`, so it doesn't run on every render
+    HookAfterEarlyReturn { hook: String },
+    /// `new RegExp/Function/Map/Set/WeakMap` in render; hoist it or wrap it in `useMemo`
+    ExpensiveAllocation { ctor: String },
+    /// Symbol exported both as default and by name; bundlers may duplicate the module
+    AmbiguousExport { name: String, lines: Vec<usize> },
+    /// `{cond ? <A/> : <B/>}` swapping element types without keys; the subtree remounts and loses state
+    ConditionalRemount,
+    /// Linear `includes/indexOf/find` inside a render loop; index the collection in a `Set`/`Map`
+    QuadraticLookup { methods: Vec<String> },
+    /// `React.Children.*` or `cloneElement`: new child elements every render defeat memoized children
+    ChildrenManipulation { method: String },
+    /// `useEffect(async () => ...)`: the effect returns a Promise instead of a cleanup function
+    AsyncEffectCallback { hook: String },
+    /// Prop typed as a union with more members than the configured limit; slows down `tsc`
+    LargeUnionProp { component: String, prop: String, members: usize },
+    /// Dynamic `import('...')`, wrapped in `React.lazy` or not: a code-split boundary
+    CodeSplitPoint { module: String, kind: ImportSourceKind, lazy: bool },
+    /// State setter called inside a loop in an event handler: one update per iteration
+    SetStateInLoop { setter: String },
+    /// `style={{...}}` built only from literals: hoist it to a constant or a CSS class
+    StaticInlineStyle { element: String },
+    /// `...rest` from destructured props spread onto a child: an unknown-shaped, fresh object
+    RestSpreadForwarding { component: String, element: String },
+    /// `ref` destructured from props of a component not wrapped in `forwardRef`: React never passes it
+    RefPropWithoutForwardRef { component: String },
+    /// Function/object/array prop a custom `memo` comparator never reads: its changes are ignored
+    ComparatorMissingProp { component: String, prop: String },
+    /// `useState(compute())`: `compute` runs every render; `useState(() => compute())` runs it once
+    EagerStateInit,
+    /// HTML attribute spelling (`class`, `for`, `onclick`) or reserved `key` used as a prop
+    MisusedDomProp { name: String, suggestion: String },
+    /// `.map(x => <><Item /></>)`: a fragment around the only element adds a node per item
+    FragmentInMap,
+    /// `JSON.parse`/`JSON.stringify` on the render path: a full (de)serialization per render.
+    /// `escapes` when the result feeds a prop or a deps array, so it also defeats memoization
+    JsonInRender { method: String, escapes: bool },
+    /// `{a ? <X/> : b ? <Y/> : c ? <Z/> : <W/>}` in JSX children; `depth` counts the ternaries
+    DeepJsxTernary { depth: usize },
+    /// `onClick="..."`, `onSubmit={123}`: an `on*` prop set to something that can't be called
+    NonFunctionHandler { prop: String },
+    /// Same element returned from different branches with `prop` set to literals of different kinds
+    InconsistentPropType { prop: String },
+    /// `const name = useMemo(..)`/`useCallback(..)` never read: the memoization is pure overhead
+    UnusedMemo { name: String },
+    /// `useEffect(fn, [{ a: 1 }])`: an object/array/function literal in a deps array changes every render
+    LiteralInDeps { hook: String },
+    /// Import of a module known to bloat bundles, with the lighter alternative
+    HeavyDependency { source: String, advice: String },
+    /// `items.push(x)`, `state.count = 1`: a prop or state value changed in place, which React can't see
+    DirectMutation { target: String },
+    /// Effect that always calls `setter` while the state it sets is in its deps: it can re-run forever
+    PotentialRenderLoop { setter: String },
+    /// Element with both `className` and a non-empty inline `style`: which one wins is unclear
+    StyleClassOverlap,
+    /// `[...source]` or `Array.from(source)` of a prop or state value in render: a new array
+    /// every render, which breaks memoization downstream; wrap it in `useMemo`
+    CollectionCloneInRender { source: String },
+    /// Destructured prop the component never reads: the parent computes it for nothing
+    UnusedProp { component: String, prop: String },
+    /// `useThing({ a: 1 })`: a literal argument to a custom hook is new on every call, so
+    /// anything inside keyed on it re-runs every render
+    UnstableHookArg { hook: String },
+    /// `{(() => { ..; return <X /> })()}`: a function created and run on every render;
+    /// compute the value with `useMemo` or move it into a subcomponent
+    JsxIife,
+    /// `useEffect(fn, [ref])` with `ref` from `useRef`: its identity never changes, so it never
+    /// triggers a re-run (and `ref.current` changes aren't seen either)
+    RefInDeps { hook: String },
+    /// Component destructuring more props than the configured limit: likely doing too much
+    WideProps { component: String, count: usize },
+    /// `const Title = styled.h1`...`` with no `Title.displayName = ..`: DevTools shows `styled.h1`
+    MissingDisplayName { component: String },
+    /// `<button {...props} onClick={handle} />`: the explicit prop and one the spread may carry
+    /// collide, and their order silently decides which handler or value wins
+    SpreadClobber { prop: String },
+    /// `className={"btn " + (active ? "on" : "")}` or `className={getClasses(x)}`: a string
+    /// rebuilt every render; `callee` is set for calls, which are reported above concatenations.
+    /// `ternary` when the value only picks between branches (`active ? "on" : "off"`)
+    DynamicClassNameComputation {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        callee: Option<String>,
+        #[serde(default)]
+        ternary: bool,
+    },
+    /// `const theme = ..; function Card({ theme }) { .. }`: a prop or parameter hiding a
+    /// module-level binding, so closures in the component read the prop, maybe not as intended
+    ShadowedBinding { name: String },
+    /// `<Child open={..} />` where `Child` keeps `const [open, setOpen] = useState(..)`: the
+    /// prop looks like it controls the child, but the child's own state shadows it. Found by
+    /// the graph across files, in the parent's file
+    PropStateNameClash { child: String, name: String },
+    /// Regex literal or `new RegExp("...")` open to catastrophic backtracking
+    UnsafeRegex {
+        pattern: String,
+        issues: Vec<RedosIssue>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rewrite: Option<String>,
+    },
+}
+
+impl FindingKind {
+    /// Every `type_name`
+    pub const TYPE_NAMES: &'static [&'static str] = &[
+        "UnstableChildren",
+        "HookAfterEarlyReturn",
+        "ExpensiveAllocation",
+        "AmbiguousExport",
+        "ConditionalRemount",
+        "QuadraticLookup",
+        "ChildrenManipulation",
+        "AsyncEffectCallback",
+        "LargeUnionProp",
+        "CodeSplitPoint",
+        "SetStateInLoop",
+        "StaticInlineStyle",
+        "RestSpreadForwarding",
+        "RefPropWithoutForwardRef",
+        "ComparatorMissingProp",
+        "EagerStateInit",
+        "MisusedDomProp",
+        "UnsafeRegex",
+        "FragmentInMap",
+        "JsonInRender",
+        "DeepJsxTernary",
+        "NonFunctionHandler",
+        "InconsistentPropType",
+        "UnusedMemo",
+        "LiteralInDeps",
+        "HeavyDependency",
+        "DirectMutation",
+        "PotentialRenderLoop",
+        "StyleClassOverlap",
+        "CollectionCloneInRender",
+        "UnusedProp",
+        "UnstableHookArg",
+        "JsxIife",
+        "RefInDeps",
+        "WideProps",
+        "MissingDisplayName",
+        "SpreadClobber",
+        "DynamicClassNameComputation",
+        "ShadowedBinding",
+        "PropStateNameClash",
+    ];
+
+    /// `TYPE_NAMES` reported by the graph's cross-file checks rather than by a rule
+    pub const CROSS_FILE_TYPE_NAMES: &'static [&'static str] = &["PropStateNameClash"];
+
+    /// The `TYPE_NAMES` entry `name` spells, in any case and with or without `-`/`_`
+    /// (`json-in-render` is `JsonInRender`)
+    pub fn canonical_type_name(name: &str) -> Option<&'static str> {
+        let wanted = normalize_type(name);
+        Self::TYPE_NAMES.iter().copied().find(|t| normalize_type(t) == wanted)
+    }
+
+    /// Variant name, as serialized under `type`
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            FindingKind::UnstableChildren { .. } => "UnstableChildren",
+            FindingKind::HookAfterEarlyReturn { .. } => "HookAfterEarlyReturn",
+            FindingKind::ExpensiveAllocation { .. } => "ExpensiveAllocation",
+            FindingKind::AmbiguousExport { .. } => "AmbiguousExport",
+            FindingKind::ConditionalRemount => "ConditionalRemount",
+            FindingKind::QuadraticLookup { .. } => "QuadraticLookup",
+            FindingKind::ChildrenManipulation { .. } => "ChildrenManipulation",
+            FindingKind::AsyncEffectCallback { .. } => "AsyncEffectCallback",
+            FindingKind::LargeUnionProp { .. } => "LargeUnionProp",
+            FindingKind::CodeSplitPoint { .. } => "CodeSplitPoint",
+            FindingKind::SetStateInLoop { .. } => "SetStateInLoop",
+            FindingKind::StaticInlineStyle { .. } => "StaticInlineStyle",
+            FindingKind::RestSpreadForwarding { .. } => "RestSpreadForwarding",
+            FindingKind::RefPropWithoutForwardRef { .. } => "RefPropWithoutForwardRef",
+            FindingKind::ComparatorMissingProp { .. } => "ComparatorMissingProp",
+            FindingKind::EagerStateInit => "EagerStateInit",
+            FindingKind::MisusedDomProp { .. } => "MisusedDomProp",
+            FindingKind::UnsafeRegex { .. } => "UnsafeRegex",
+            FindingKind::FragmentInMap => "FragmentInMap",
+            FindingKind::JsonInRender { .. } => "JsonInRender",
+            FindingKind::DeepJsxTernary { .. } => "DeepJsxTernary",
+            FindingKind::NonFunctionHandler { .. } => "NonFunctionHandler",
+            FindingKind::InconsistentPropType { .. } => "InconsistentPropType",
+            FindingKind::UnusedMemo { .. } => "UnusedMemo",
+            FindingKind::LiteralInDeps { .. } => "LiteralInDeps",
+            FindingKind::HeavyDependency { .. } => "HeavyDependency",
+            FindingKind::DirectMutation { .. } => "DirectMutation",
+            FindingKind::PotentialRenderLoop { .. } => "PotentialRenderLoop",
+            FindingKind::StyleClassOverlap => "StyleClassOverlap",
+            FindingKind::CollectionCloneInRender { .. } => "CollectionCloneInRender",
+            FindingKind::UnusedProp { .. } => "UnusedProp",
+            FindingKind::UnstableHookArg { .. } => "UnstableHookArg",
+            FindingKind::JsxIife => "JsxIife",
+            FindingKind::RefInDeps { .. } => "RefInDeps",
+            FindingKind::WideProps { .. } => "WideProps",
+            FindingKind::MissingDisplayName { .. } => "MissingDisplayName",
+            FindingKind::SpreadClobber { .. } => "SpreadClobber",
+            FindingKind::DynamicClassNameComputation { .. } => "DynamicClassNameComputation",
+            FindingKind::ShadowedBinding { .. } => "ShadowedBinding",
+            FindingKind::PropStateNameClash { .. } => "PropStateNameClash",
+        }
+    }
+
+    /// One-line description for people, as `lint --format human` prints it
+    pub fn message(&self) -> String {
+        match self {
+            FindingKind::UnstableChildren { component } => format!("children of `{}` are recreated every render", component),
+            FindingKind::HookAfterEarlyReturn { hook } => format!("`{}` is called after an early return", hook),
+            FindingKind::ExpensiveAllocation { ctor } => format!("`new {}` in render; hoist it or wrap it in `useMemo`", ctor),
+            FindingKind::AmbiguousExport { name, lines } => {
+                let lines: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+                format!("`{}` is exported both as default and by name (lines {})", name, lines.join(", "))
+            }
+            FindingKind::ConditionalRemount => "ternary swaps element types without keys; the subtree remounts".to_string(),
+            FindingKind::QuadraticLookup { methods } => format!("`{}` inside a render loop; index the collection in a Set/Map", methods.join("`, `")),
+            FindingKind::ChildrenManipulation { method } => format!("`{}` creates new child elements every render", method),
+            FindingKind::AsyncEffectCallback { hook } => format!("async `{}` callback returns a Promise instead of a cleanup", hook),
+            FindingKind::LargeUnionProp { component, prop, members } => format!("prop `{}` of `{}` is a union of {} members", prop, component, members),
+            FindingKind::CodeSplitPoint { module, lazy, .. } => {
+                format!("code-split point `{}`{}", module, if *lazy { " (React.lazy)" } else { "" })
+            }
+            FindingKind::SetStateInLoop { setter } => format!("`{}` is called inside a loop", setter),
+            FindingKind::StaticInlineStyle { element } => format!("static inline style on `<{}>`; hoist it to a constant or a class", element),
+            FindingKind::RestSpreadForwarding { component, element } => format!("`{}` spreads its rest props onto `<{}>`", component, element),
+            FindingKind::RefPropWithoutForwardRef { component } => format!("`{}` reads `ref` from props but isn't wrapped in `forwardRef`", component),
+            FindingKind::ComparatorMissingProp { component, prop } => format!("memo comparator of `{}` ignores prop `{}`", component, prop),
+            FindingKind::EagerStateInit => "`useState` initializer runs every render; pass a function instead".to_string(),
+            FindingKind::MisusedDomProp { name, suggestion } => format!("`{}` is not a React prop; use `{}`", name, suggestion),
+            FindingKind::FragmentInMap => "fragment around the only element in `.map`".to_string(),
+            FindingKind::JsonInRender { method, escapes } => {
+                format!("`JSON.{}` in render{}", method, if *escapes { "; its result also defeats memoization" } else { "" })
+            }
+            FindingKind::DeepJsxTernary { depth } => format!("{} nested ternaries in JSX", depth),
+            FindingKind::NonFunctionHandler { prop } => format!("`{}` is set to something that can't be called", prop),
+            FindingKind::InconsistentPropType { prop } => format!("`{}` is set to literals of different types across branches", prop),
+            FindingKind::UnusedMemo { name } => format!("memoized `{}` is never read", name),
+            FindingKind::LiteralInDeps { hook } => format!("literal in `{}` deps changes every render", hook),
+            FindingKind::HeavyDependency { source, advice } => format!("`{}` is heavy: {}", source, advice),
+            FindingKind::DirectMutation { target } => format!("`{}` is mutated in place", target),
+            FindingKind::PotentialRenderLoop { setter } => format!("effect always calls `{}` and depends on its state", setter),
+            FindingKind::StyleClassOverlap => "element has both `className` and an inline `style`".to_string(),
+            FindingKind::CollectionCloneInRender { source } => format!("`{}` is copied every render; wrap it in `useMemo`", source),
+            FindingKind::UnusedProp { component, prop } => format!("`{}` never reads prop `{}`", component, prop),
+            FindingKind::UnstableHookArg { hook } => format!("literal argument to `{}` is new on every call", hook),
+            FindingKind::JsxIife => "function invoked inline in JSX; use `useMemo` or a subcomponent".to_string(),
+            FindingKind::RefInDeps { hook } => format!("ref in `{}` deps never triggers a re-run", hook),
+            FindingKind::WideProps { component, count } => format!("`{}` takes {} props; consider splitting it", component, count),
+            FindingKind::MissingDisplayName { component } => format!("styled component `{}` has no `displayName`", component),
+            FindingKind::SpreadClobber { prop } => format!("`{}` may also come from a spread; attribute order decides which wins", prop),
+            FindingKind::DynamicClassNameComputation { callee, ternary } => match callee {
+                Some(callee) => format!("`className` is computed by `{}` on every render", callee),
+                None if *ternary => "`className` is picked by a ternary on every render; consider `clsx` in hot lists".to_string(),
+                None => "`className` is concatenated on every render; consider `clsx` or a template".to_string(),
+            },
+            FindingKind::ShadowedBinding { name } => format!("`{}` hides a module-level binding of the same name", name),
+            FindingKind::PropStateNameClash { child, name } => format!("`{}` is passed to `{}`, which keeps its own `{}` state", name, child, name),
+            FindingKind::UnsafeRegex { pattern, rewrite, .. } => match rewrite {
+                Some(rewrite) => format!("`/{}/` can backtrack catastrophically; try `/{}/`", pattern, rewrite),
+                None => format!("`/{}/` can backtrack catastrophically", pattern),
+            },
+        }
+    }
+
+    /// Severity `lint` reports this kind with when the config doesn't override it
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            FindingKind::DynamicClassNameComputation { callee: Some(_), .. } => Severity::Warning,
+            FindingKind::StyleClassOverlap
+            | FindingKind::WideProps { .. }
+            | FindingKind::DynamicClassNameComputation { .. }
+            | FindingKind::ShadowedBinding { .. } => Severity::Info,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+/// Position in the original source a finding maps back to through a sourcemap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OriginalLocation {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A single analysis result attached to a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    #[serde(flatten)]
+    pub kind: FindingKind,
+    /// 1-based line in the analyzed file
+    pub line: usize,
+    /// 1-based column in the analyzed file
+    pub column: usize,
+    /// Last line of the reported node, when it spans more than `line`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    /// Set when the analyzed file is build output with a sourcemap
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original: Option<OriginalLocation>,
+}
+
+/// How much a finding counts in `lint`; see `FindingKind::default_severity`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Dropped from the report
+    Off,
+    /// Style nudges; reported, but never worth failing a build over by default
+    Info,
+    #[default]
+    #[serde(alias = "warn")]
+    Warning,
+    Error,
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Severity::Off),
+            "info" => Ok(Severity::Info),
+            "warn" | "warning" => Ok(Severity::Warning),
+            "error" => Ok(Severity::Error),
+            other => Err(format!("unknown severity `{}` (expected off, info, warn or error)", other)),
+        }
+    }
+}
+
+/// A finding as reported by `lint`, with its effective severity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintFinding {
+    #[serde(flatten)]
+    pub finding: Finding,
+    pub severity: Severity,
+}
@@ -1,9 +1,24 @@
-use crate::analyzer::extract::{extract_all, ExportInfo, ImportMeta};
+use crate::analyzer::extract::{classify_import_source, extract_all_with_timeout, ExportInfo, ExportKind, ExtractOptions, FileAnalysis, ImportMeta, ImportSourceKind, ParseTimeout, PathAliases, DEFAULT_PARSE_TIMEOUT};
+use crate::analyzer::findings::{Finding, FindingKind, LintFinding, Severity};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::analyzer::provider::{FileProvider, RealFs};
+use crate::analyzer::remap::{load_adjacent_sourcemap, remap_findings};
+use crate::analyzer::rules::{DeepJsxTernaryRule, HeavyDependencyRule, LargeUnionPropRule, RuleRegistry, UnsafeRegexRule, WidePropsRule};
+use crate::cache::content_hash;
+use crate::diff::{ChangedFiles, ChangedLines};
+#[cfg(not(target_arch = "wasm32"))]
 use crate::cache::IncrementalCache;
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
+#[cfg(not(target_arch = "wasm32"))]
 use rayon::prelude::*;
 use serde::{Serialize, Deserialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, debug_span, info, info_span, trace, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComponentMeta {
@@ -11,8 +26,93 @@ pub struct ComponentMeta {
     pub file_path: String,
     pub is_memoized: bool,
     pub props: Vec<PropInfo>,
+    /// Props destructured in the signature; the width of the component's prop surface
+    #[serde(default)]
+    pub prop_count: usize,
     pub exports: Vec<ExportInfo>,
     pub line: usize,
+    /// Inline object/array/function props this component passes to other components
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inline_props: Vec<InlineProp>,
+    /// False when no JSX or `createElement` was found: possibly not a component at all
+    #[serde(default)]
+    pub renders_jsx: bool,
+    /// Leading JSDoc or `//` comment of the declaration; only with `IndexOptions::collect_docs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+    /// Distinct `use*` calls in the body, built-in and custom; `useContext(Ctx)` names its context
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks_used: Vec<String>,
+    /// Every named prop this component passes to other components
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub passed_props: Vec<PassedProp>,
+    /// `x` of each `const [x, setX] = useState(..)`/`useReducer(..)` in the body
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub state_names: Vec<String>,
+}
+
+/// Why part of the project went unanalyzed or was analyzed on a guess
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DiagnosticReason {
+    /// Couldn't be read as UTF-8 text; `attempts` counts the reads tried before giving up
+    Unreadable {
+        error: String,
+        #[serde(default)]
+        attempts: usize,
+    },
+    /// The parser rejected the file; nothing was extracted
+    ParseFailed,
+    /// Registered as a component by its name, but it renders no JSX
+    ComponentWithoutJsx { component: String, line: usize },
+}
+
+/// A skip or fallback during indexing; an empty list means full coverage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisDiagnostic {
+    pub path: String,
+    #[serde(flatten)]
+    pub reason: DiagnosticReason,
+}
+
+/// `<Child prop={{...}} />` inside a component: a new value on every render
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InlineProp {
+    /// Component being rendered, as written in the JSX
+    pub element: String,
+    pub prop: String,
+    pub kind: PropKind,
+    /// An object of literals only (`{{ margin: 0 }}`): hoisting it to a constant fixes it,
+    /// where a dynamic value needs `useMemo`
+    #[serde(default)]
+    pub is_static: bool,
+    pub line: usize,
+}
+
+/// `<Child prop=.. />` inside a component, whatever the value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassedProp {
+    /// Component being rendered, as written in the JSX
+    pub element: String,
+    pub prop: String,
+    pub line: usize,
+    #[serde(default)]
+    pub column: usize,
+}
+
+/// A memoized component re-rendered anyway because its parent passes a fresh prop value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefeatedMemo {
+    pub child: String,
+    /// File the memoized child is defined in
+    pub child_file: String,
+    pub parent: String,
+    /// File of the parent, where `line` points
+    pub file: String,
+    pub prop: String,
+    /// See `InlineProp::is_static`
+    pub is_static: bool,
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,91 +131,940 @@ pub enum PropKind {
     Primitive,
 }
 
-/// Cacheable extraction result
+/// Knobs for `MetadataGraph::index_project_with_options`
+#[derive(Debug, Clone)]
+pub struct IndexOptions {
+    /// Reuse and populate the incremental cache
+    pub use_cache: bool,
+    /// Per-file parse budget before the file is abandoned
+    pub parse_timeout: Duration,
+    /// tsconfig `paths` used to tell aliased imports from external packages
+    pub path_aliases: PathAliases,
+    /// When non-empty, only files matching one of these globs (relative to the root) are indexed
+    pub include_globs: Vec<String>,
+    /// Report props typed as unions with more members than this; off when `None`
+    pub max_union_members: Option<usize>,
+    /// Attach each component's leading doc comment as `ComponentMeta::doc`
+    pub collect_docs: bool,
+    /// Run regex literals through the ReDoS checker
+    pub check_regex: bool,
+    /// Rules switched off by name
+    pub disabled_rules: Vec<String>,
+    /// JSX ternary chains at least this deep are reported; 3 when `None`
+    pub min_ternary_depth: Option<usize>,
+    /// Components destructuring more props than this get `WideProps`; 12 when `None`
+    pub max_props: Option<usize>,
+    /// Where the incremental cache lives; `None` falls back to `$PERF_LINTER_CACHE_DIR`,
+    /// then `perf_linter_cache` in the system temp directory
+    pub cache_dir: Option<PathBuf>,
+    /// Key cache entries by content, so files with identical content share one; off by
+    /// default, since each file then costs a second lookup
+    pub dedup_cache: bool,
+    /// Additions to `HEAVY_DEPENDENCIES` (source -> advice); an empty advice drops a default
+    pub heavy_dependencies: BTreeMap<String, String>,
+    /// Directories (a name like `utils`, or a root-relative path like `src/lib`) where a
+    /// capitalized function only counts as a component if it renders JSX
+    pub non_component_dirs: Vec<String>,
+}
+
+/// Environment variable overriding the default cache directory
+pub const CACHE_DIR_ENV: &str = "PERF_LINTER_CACHE_DIR";
+
+impl Default for IndexOptions {
+    fn default() -> Self {
+        Self { use_cache: true, parse_timeout: DEFAULT_PARSE_TIMEOUT, path_aliases: PathAliases::default(), include_globs: Vec::new(), max_union_members: None, collect_docs: false, check_regex: false, disabled_rules: Vec::new(), min_ternary_depth: None, max_props: None, cache_dir: None, dedup_cache: false, heavy_dependencies: BTreeMap::new(), non_component_dirs: Vec::new() }
+    }
+}
+
+/// An unmemoized component ranked by how much `memo` would likely save; see `MetadataGraph::memo_priorities`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoPriority {
+    pub component: String,
+    pub file: String,
+    pub line: usize,
+    pub prop_count: usize,
+    /// `prop_count`, doubled above the `min_props` threshold
+    pub score: usize,
+}
+
+/// Import whose source doesn't lead to an indexed file or one on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnresolvedImport {
+    pub source: String,
+    /// Relative, absolute or alias; external packages are never reported
+    pub kind: ImportSourceKind,
+    pub from_file: String,
+    pub line: usize,
+}
+
+/// Named import from a barrel file (one that mostly re-exports other modules);
+/// importing the defining module directly keeps the rest of the barrel out of the bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarrelImport {
+    pub source: String,
+    /// Imported name; `*` for a namespace import
+    pub symbol: String,
+    /// Barrel the source resolves to
+    pub barrel: String,
+    pub from_file: String,
+    pub line: usize,
+}
+
+/// Indexed files and the indexed files they import or re-export from
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportGraph {
+    /// File -> files its imports resolve to; every indexed file is a key
+    pub edges: BTreeMap<String, Vec<String>>,
+    /// Groups of files that import each other, directly or through others; sorted
+    pub cycles: Vec<Vec<String>>,
+}
+
+impl ImportGraph {
+    /// Graphviz DOT; with `highlight_cycles`, files and imports in a cycle are drawn red
+    pub fn to_dot(&self, highlight_cycles: bool) -> String {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let cycle_of: HashMap<&str, usize> = if highlight_cycles {
+            self.cycles.iter().enumerate().flat_map(|(i, cycle)| cycle.iter().map(move |f| (f.as_str(), i))).collect()
+        } else {
+            HashMap::new()
+        };
+        let mut out = String::from("digraph imports {\n  node [shape=box];\n");
+        for file in self.edges.keys() {
+            let style = if cycle_of.contains_key(file.as_str()) { " [color=red]" } else { "" };
+            out.push_str(&format!("  {}{};\n", quote(file), style));
+        }
+        for (from, targets) in &self.edges {
+            for to in targets {
+                let in_cycle = cycle_of.get(from.as_str()).is_some_and(|c| cycle_of.get(to.as_str()) == Some(c));
+                let style = if in_cycle { " [color=red]" } else { "" };
+                out.push_str(&format!("  {} -> {}{};\n", quote(from), quote(to), style));
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// A module's export imported under more than one local name across the project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InconsistentImportAlias {
+    /// Indexed file the imports resolve to, or the package name for external imports
+    pub source: String,
+    /// `default` or the exported name
+    pub export: String,
+    /// Local name -> files importing it under that name
+    pub aliases: BTreeMap<String, Vec<String>>,
+}
+
+/// Output of the `lint` command: every finding plus the cross-file memo checks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintReport {
+    pub total_findings: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub infos: usize,
+    /// Findings per file, in path and source order; those switched `off` are left out
+    pub findings: BTreeMap<String, Vec<LintFinding>>,
+    pub defeated_memos: Vec<DefeatedMemo>,
+    pub memo_priorities: Vec<MemoPriority>,
+    pub timeouts: Vec<ParseTimeout>,
+    pub diagnostics: Vec<AnalysisDiagnostic>,
+}
+
+/// Per-file rollup for dashboards; see `MetadataGraph::file_metrics`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileMetrics {
+    /// Lines of source, blank lines and comments included
+    pub loc: usize,
+    pub component_count: usize,
+    pub memoized_count: usize,
+    /// Finding count per finding `type`
+    pub finding_count_by_rule: BTreeMap<String, usize>,
+}
+
+/// Findings for one file, as ranked in `GraphStats::top_files`
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct FileAnalysis {
-    components: Vec<ComponentMeta>,
-    imports: Vec<ImportMeta>,
-    exports: Vec<ExportInfo>,
+pub struct FileFindingCount {
+    pub file: String,
+    pub findings: usize,
+}
+
+/// Project-wide rollup of an index; see `MetadataGraph::stats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphStats {
+    pub total_findings: usize,
+    /// Finding count per finding `type`
+    pub by_rule: BTreeMap<String, usize>,
+    /// Files with the most findings, most first
+    pub top_files: Vec<FileFindingCount>,
+    pub components: usize,
+    pub memoized_components: usize,
+    /// `memoized_components / components`, 0 when there are no components
+    pub memoized_ratio: f64,
+}
+
+/// Entries of files changed since the last serialization; see `MetadataGraph::to_json_delta`
+#[derive(Serialize)]
+struct GraphDelta {
+    /// Changed files; their entries below replace whatever the client held for them
+    changed: Vec<String>,
+    components: BTreeMap<String, ComponentMeta>,
+    imports: BTreeMap<String, Vec<ImportMeta>>,
+    exports: BTreeMap<String, Vec<ExportInfo>>,
+    findings: BTreeMap<String, Vec<Finding>>,
+    timeouts: Vec<ParseTimeout>,
+    diagnostics: Vec<AnalysisDiagnostic>,
+    removed: Vec<String>,
+}
+
+/// Appended to a snapshot whose findings were cut at `--max-findings`
+#[derive(Serialize)]
+struct Truncation {
+    truncated: bool,
+    /// Findings the index produced before the cap
+    total_estimated: usize,
+}
+
+/// Totals over every component of every file; `GraphSnapshot::components` keeps one per file
+#[derive(Serialize)]
+struct ComponentCounts {
+    components: usize,
+    memoized_components: usize,
 }
 
 #[derive(Serialize)]
 struct GraphSnapshot {
-    components: std::collections::HashMap<String, ComponentMeta>,
-    imports: std::collections::HashMap<String, Vec<ImportMeta>>,
-    exports: std::collections::HashMap<String, Vec<ExportInfo>>,
+    components: BTreeMap<String, ComponentMeta>,
+    component_counts: ComponentCounts,
+    imports: BTreeMap<String, Vec<ImportMeta>>,
+    exports: BTreeMap<String, Vec<ExportInfo>>,
+    findings: BTreeMap<String, Vec<Finding>>,
+    timeouts: Vec<ParseTimeout>,
+    diagnostics: Vec<AnalysisDiagnostic>,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    truncation: Option<Truncation>,
 }
 
 pub struct MetadataGraph {
+    /// Last component seen per file (the shape the JSON snapshot exposes)
     pub components: Arc<DashMap<String, ComponentMeta>>,
+    /// Every component defined in each file
+    pub file_components: Arc<DashMap<String, Vec<ComponentMeta>>>,
     pub imports: Arc<DashMap<String, Vec<ImportMeta>>>,
     pub exports: Arc<DashMap<String, Vec<ExportInfo>>>,
+    pub findings: Arc<DashMap<String, Vec<Finding>>>,
+    /// Files abandoned because parsing exceeded `IndexOptions::parse_timeout`
+    pub timeouts: Arc<DashMap<String, ParseTimeout>>,
+    /// Skips and fallbacks per file; see `AnalysisDiagnostic`
+    pub diagnostics: Arc<DashMap<String, Vec<AnalysisDiagnostic>>>,
+    /// Content hash each file was last analyzed with
+    hashes: Arc<DashMap<String, u64>>,
+    /// When each file's entries were recorded, in seconds since the epoch (a cache hit keeps
+    /// the time of the cached analysis); `merge` keeps the newer of two
+    modified_at: Arc<DashMap<String, u64>>,
+    /// Source lines per read file, for `file_metrics`
+    line_counts: Arc<DashMap<String, usize>>,
+    /// Files updated or removed since the last `to_json`/`to_json_delta`
+    dirty: Arc<DashSet<String>>,
+    removed: Arc<DashSet<String>>,
+    options: IndexOptions,
+    rules: Arc<RuleRegistry>,
+    /// Project root emitted paths are made relative to; None outside `index_project*`
+    root: Option<PathBuf>,
 }
 
 impl MetadataGraph {
     /// Index a project with incremental caching for performance
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn index_project(project_root: &str) -> Self {
         Self::index_project_with_cache(project_root, true)
     }
 
     /// Index a project with optional cache control
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn index_project_with_cache(project_root: &str, use_cache: bool) -> Self {
-        let files = find_all_source_files(project_root);
+        Self::index_project_with_options(project_root, &IndexOptions { use_cache, ..Default::default() })
+    }
 
-        let components = Arc::new(DashMap::new());
-        let imports = Arc::new(DashMap::new());
-        let exports = Arc::new(DashMap::new());
+    /// Index a project with full control over caching and parse limits
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn index_project_with_options(project_root: &str, options: &IndexOptions) -> Self {
+        Self::index_project_with_provider(project_root, options, &RealFs)
+    }
 
-        // Create cache in system temp directory
-        let cache_dir = std::env::temp_dir().join("perf_linter_cache");
-        let cache = if use_cache {
-            Some(Arc::new(IncrementalCache::<FileAnalysis>::new(&cache_dir, "0.6.0")))
-        } else {
-            None
+    /// `index_project_with_options` listing and reading files through `provider`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn index_project_with_provider(project_root: &str, options: &IndexOptions, provider: &dyn FileProvider) -> Self {
+        Self::index_files(project_root, options, provider, None, None)
+    }
+
+    /// `index_project_with_options` calling `on_progress(done, total)` as each file
+    /// completes; it runs on the rayon workers, so calls may arrive out of order
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn index_project_with_progress(project_root: &str, options: &IndexOptions, on_progress: impl Fn(usize, usize) + Sync) -> Self {
+        Self::index_files(project_root, options, &RealFs, Some(&on_progress), None)
+    }
+
+    /// Only analyze the files in `changes` (paths relative to `project_root`). The others are
+    /// loaded from the cache without being read, so cross-file checks keep their context,
+    /// but report no findings; ones missing from the cache are analyzed as usual. Deleted
+    /// and renamed-away files are dropped from the cache.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn index_project_since(project_root: &str, options: &IndexOptions, changes: &ChangedFiles) -> Self {
+        Self::index_files(project_root, options, &RealFs, None, Some(changes))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn index_files(
+        project_root: &str,
+        options: &IndexOptions,
+        provider: &dyn FileProvider,
+        on_progress: Option<&(dyn Fn(usize, usize) + Sync)>,
+        changes: Option<&ChangedFiles>,
+    ) -> Self {
+        let _index = info_span!("index_project", root = project_root).entered();
+        let files = {
+            let _walk = info_span!("walk").entered();
+            let files = provider.list(project_root);
+            filter_included(project_root, files, &options.include_globs)
         };
+        info!(files = files.len(), "walk complete");
 
-        files.par_iter().for_each(|file_path| {
-            if let Ok(source) = std::fs::read_to_string(file_path) {
-                // Try to get from cache first
-                let (mut comps, imps, exps) = if let Some(ref cache) = cache {
-                    if let Some(cached) = cache.get(file_path, &source) {
-                        (cached.components, cached.imports, cached.exports)
+        let mut graph = Self::empty(options.clone());
+        graph.root = Some(PathBuf::from(project_root));
+
+        let cache_dir = options
+            .cache_dir
+            .clone()
+            .or_else(|| std::env::var_os(CACHE_DIR_ENV).filter(|dir| !dir.is_empty()).map(PathBuf::from))
+            .unwrap_or_else(|| std::env::temp_dir().join("perf_linter_cache"));
+        let cache = match (options.use_cache, options.dedup_cache) {
+            (false, _) => None,
+            (true, false) => Some(Arc::new(IncrementalCache::<FileAnalysis>::new(&cache_dir, &cache_version(options)))),
+            (true, true) => Some(Arc::new(IncrementalCache::<FileAnalysis>::new_content_addressed(&cache_dir, &cache_version(options)))),
+        };
+
+        if let (Some(cache), Some(changes)) = (&cache, changes) {
+            for deleted in changes.deleted() {
+                cache.clear_file(&Path::new(project_root).join(deleted).to_string_lossy());
+            }
+        }
+
+        let total = files.len();
+        let done = AtomicUsize::new(0);
+        let index_file = |file_path: &String| {
+            let unchanged = changes.is_some_and(|changes| {
+                let relative = Path::new(file_path).strip_prefix(project_root).unwrap_or(Path::new(file_path));
+                !changes.contains(&relative.to_string_lossy())
+            });
+            if unchanged {
+                let cached = cache.as_ref().and_then(|cache| Some((cache.peek(file_path)?, cache.get_last(file_path)?)));
+                if let Some((meta, analysis)) = cached {
+                    trace!(file = file_path.as_str(), "unchanged, loaded from cache");
+                    graph.hashes.insert(file_path.clone(), meta.content_hash);
+                    graph.modified_at.insert(file_path.clone(), meta.modified_at);
+                    graph.line_counts.insert(file_path.clone(), meta.line_count);
+                    graph.store(file_path, FileAnalysis { findings: Vec::new(), ..analysis });
+                    return;
+                }
+            }
+            match read_with_retry(provider, file_path) {
+                Ok(source) => {
+                    graph.hashes.insert(file_path.clone(), content_hash(&source));
+                    graph.modified_at.insert(file_path.clone(), unix_now());
+                    graph.line_counts.insert(file_path.clone(), source.lines().count());
+                    // Try to get from cache first
+                    let analysis = if let Some(ref cache) = cache {
+                        let cached = {
+                            let _lookup = debug_span!("cache", file = file_path.as_str()).entered();
+                            cache.get(file_path, &source)
+                        };
+                        if let Some(cached) = cached {
+                            trace!(file = file_path.as_str(), "cache hit");
+                            cached
+                        } else {
+                            trace!(file = file_path.as_str(), "cache miss");
+                            let Some(analysis) = graph.extract(file_path, &source) else { return };
+                            // Store in cache for next time
+                            cache.set(file_path, &source, analysis.clone());
+                            analysis
+                        }
                     } else {
-                        let result = extract_all(&source, file_path);
-                        // Store in cache for next time
-                        cache.set(file_path, &source, FileAnalysis {
-                            components: result.0.clone(),
-                            imports: result.1.clone(),
-                            exports: result.2.clone(),
+                        let Some(analysis) = graph.extract(file_path, &source) else { return };
+                        analysis
+                    };
+                    let findings = if unchanged { Vec::new() } else { analysis.findings };
+                    graph.store(file_path, FileAnalysis { findings, ..analysis });
+                }
+                Err((err, attempts)) => {
+                    debug!(file = file_path.as_str(), attempts, "skipped unreadable file");
+                    graph.modified_at.insert(file_path.clone(), unix_now());
+                    graph.diagnose(file_path, DiagnosticReason::Unreadable { error: err.to_string(), attempts });
+                }
+            }
+        };
+        files.par_iter().for_each(|file_path| {
+            index_file(file_path);
+            if let Some(on_progress) = on_progress {
+                on_progress(done.fetch_add(1, Ordering::Relaxed) + 1, total);
+            }
+        });
+        info!(components = graph.components.len(), timeouts = graph.timeouts.len(), "index complete");
+
+        graph
+    }
+
+    fn empty(options: IndexOptions) -> Self {
+        Self {
+            components: Arc::new(DashMap::new()),
+            file_components: Arc::new(DashMap::new()),
+            imports: Arc::new(DashMap::new()),
+            exports: Arc::new(DashMap::new()),
+            findings: Arc::new(DashMap::new()),
+            timeouts: Arc::new(DashMap::new()),
+            diagnostics: Arc::new(DashMap::new()),
+            hashes: Arc::new(DashMap::new()),
+            modified_at: Arc::new(DashMap::new()),
+            line_counts: Arc::new(DashMap::new()),
+            dirty: Arc::new(DashSet::new()),
+            removed: Arc::new(DashSet::new()),
+            rules: Arc::new(rule_registry(&options)),
+            options,
+            root: None,
+        }
+    }
+
+    /// Parse one file within the configured budget; timeouts are recorded on the graph
+    fn extract(&self, file_path: &str, source: &str) -> Option<FileAnalysis> {
+        let _parse = debug_span!("parse", file = file_path).entered();
+        match extract_all_with_timeout(source, file_path, self.options.parse_timeout, self.rules.clone(), ExtractOptions { collect_docs: self.options.collect_docs }) {
+            Ok(Some(extracted)) => Some(extracted.into()),
+            Ok(None) => {
+                debug!(file = file_path, "parse failed");
+                self.diagnose(file_path, DiagnosticReason::ParseFailed);
+                None
+            }
+            Err(timeout) => {
+                warn!(file = file_path, elapsed_ms = timeout.elapsed_ms, "parse timed out");
+                self.timeouts.insert(file_path.to_string(), timeout);
+                None
+            }
+        }
+    }
+
+    fn diagnose(&self, file_path: &str, reason: DiagnosticReason) {
+        let diagnostic = AnalysisDiagnostic { path: file_path.to_string(), reason };
+        self.diagnostics.entry(file_path.to_string()).or_default().push(diagnostic);
+    }
+
+    fn store(&self, file_path: &str, analysis: FileAnalysis) {
+        let FileAnalysis { components, mut imports, exports, mut findings } = analysis;
+        // classified after the cache so aliases can change without invalidating it
+        for imp in imports.iter_mut() {
+            imp.kind = classify_import_source(&imp.source, &self.options.path_aliases);
+        }
+        for f in findings.iter_mut() {
+            if let FindingKind::CodeSplitPoint { module, kind, .. } = &mut f.kind {
+                *kind = classify_import_source(module, &self.options.path_aliases);
+            }
+        }
+        if !imports.is_empty() {
+            self.imports.insert(file_path.to_string(), imports);
+        }
+        if !exports.is_empty() {
+            self.exports.insert(file_path.to_string(), exports.clone());
+        }
+        if !findings.is_empty() {
+            self.findings.insert(file_path.to_string(), findings);
+        }
+
+        let mut all = Vec::with_capacity(components.len());
+        let strict = self.in_non_component_dir(file_path);
+        for c in components {
+            if !c.renders_jsx && strict {
+                trace!(file = file_path, component = c.name.as_str(), "not a component outside component directories");
+                continue;
+            }
+            if !c.renders_jsx {
+                self.diagnose(file_path, DiagnosticReason::ComponentWithoutJsx { component: c.name.clone(), line: c.line });
+            }
+            let mut comp = c;
+            comp.file_path = file_path.to_string();
+            // anexar exports do mesmo arquivo
+            comp.exports = exports.clone();
+            all.push(comp.clone());
+            self.components.insert(file_path.to_string(), comp);
+        }
+        if !all.is_empty() {
+            self.file_components.insert(file_path.to_string(), all);
+        }
+    }
+
+    /// Whether `file_path` lies in one of `IndexOptions::non_component_dirs`
+    fn in_non_component_dir(&self, file_path: &str) -> bool {
+        if self.options.non_component_dirs.is_empty() {
+            return false;
+        }
+        let relative = self.portable_path(file_path);
+        let dirs: Vec<&str> = relative.split('/').collect();
+        let dirs = &dirs[..dirs.len().saturating_sub(1)];
+        self.options.non_component_dirs.iter().any(|d| {
+            let d = d.trim_matches('/');
+            if d.contains('/') {
+                relative.starts_with(&format!("{}/", d))
+            } else {
+                dirs.contains(&d)
+            }
+        })
+    }
+
+    /// Drop every entry recorded for `file_path`
+    fn forget(&self, file_path: &str) {
+        self.components.remove(file_path);
+        self.file_components.remove(file_path);
+        self.imports.remove(file_path);
+        self.exports.remove(file_path);
+        self.findings.remove(file_path);
+        self.timeouts.remove(file_path);
+        self.diagnostics.remove(file_path);
+        self.hashes.remove(file_path);
+        self.modified_at.remove(file_path);
+        self.line_counts.remove(file_path);
+    }
+
+    /// Re-analyze a single file (e.g. on save) and mark it and its `consumers` for the next
+    /// delta, so clients refresh the cross-file results that depend on it. A save that
+    /// leaves the content unchanged is a no-op.
+    pub fn update_file(&self, file_path: &str, source: &str) {
+        let hash = content_hash(source);
+        if self.hashes.get(file_path).map(|h| *h == hash).unwrap_or(false) {
+            trace!(file = file_path, "content unchanged, skipping update");
+            return;
+        }
+        self.forget(file_path);
+        self.hashes.insert(file_path.to_string(), hash);
+        self.modified_at.insert(file_path.to_string(), unix_now());
+        self.line_counts.insert(file_path.to_string(), source.lines().count());
+        if let Some(analysis) = self.extract(file_path, source) {
+            self.store(file_path, analysis);
+        }
+        self.removed.remove(file_path);
+        self.dirty.insert(file_path.to_string());
+        self.invalidate_consumers(file_path);
+    }
+
+    /// Forget a deleted file and report it as removed, and its `consumers` as changed, in the next delta
+    pub fn remove_file(&self, file_path: &str) {
+        self.forget(file_path);
+        self.dirty.remove(file_path);
+        self.removed.insert(file_path.to_string());
+        self.invalidate_consumers(file_path);
+    }
+
+    /// Cross-file checks are computed on demand, so the graph itself never holds stale
+    /// results; re-emitting consumers tells delta clients to fetch them again
+    fn invalidate_consumers(&self, file_path: &str) {
+        for consumer in self.consumers(file_path) {
+            trace!(file = file_path, consumer = consumer.as_str(), "consumer invalidated");
+            self.dirty.insert(consumer);
+        }
+    }
+
+    /// Combine graphs indexed separately, e.g. one per monorepo package. A file present in
+    /// several keeps only the entries of the graph that analyzed it last (by `modified_at`;
+    /// on a tie, the later graph in `graphs`). The result runs the first graph's rules and
+    /// emits paths relative to the roots' common ancestor.
+    pub fn merge(graphs: Vec<MetadataGraph>) -> MetadataGraph {
+        let mut graphs = graphs.into_iter();
+        let Some(mut merged) = graphs.next() else { return Self::empty(IndexOptions::default()) };
+        for graph in graphs {
+            merged.root = match (merged.root.take(), &graph.root) {
+                (Some(a), Some(b)) => a.ancestors().find(|p| b.starts_with(p)).map(Path::to_path_buf),
+                _ => None,
+            };
+            // unreadable files only have a diagnostic
+            let mut files: Vec<String> = graph.hashes.iter().map(|e| e.key().clone()).chain(graph.diagnostics.iter().map(|e| e.key().clone())).collect();
+            files.sort();
+            files.dedup();
+            for file in &files {
+                let theirs = graph.modified_at.get(file).map(|t| *t).unwrap_or(0);
+                if merged.modified_at.get(file).is_some_and(|ours| *ours > theirs) {
+                    trace!(file = file.as_str(), "kept the newer analysis while merging");
+                    continue;
+                }
+                merged.forget(file);
+                merged.removed.remove(file);
+                absorb(&merged.components, &graph.components, file);
+                absorb(&merged.file_components, &graph.file_components, file);
+                absorb(&merged.imports, &graph.imports, file);
+                absorb(&merged.exports, &graph.exports, file);
+                absorb(&merged.findings, &graph.findings, file);
+                absorb(&merged.timeouts, &graph.timeouts, file);
+                absorb(&merged.diagnostics, &graph.diagnostics, file);
+                absorb(&merged.hashes, &graph.hashes, file);
+                absorb(&merged.modified_at, &graph.modified_at, file);
+                absorb(&merged.line_counts, &graph.line_counts, file);
+                if graph.dirty.contains(file) {
+                    merged.dirty.insert(file.clone());
+                }
+            }
+            graph.removed.iter().filter(|f| !merged.hashes.contains_key(f.key())).for_each(|f| {
+                merged.removed.insert(f.key().clone());
+            });
+        }
+        merged
+    }
+
+    /// For files with an adjacent `.map`, record each finding's original source position
+    pub fn remap_to_original_sources(&self) {
+        self.findings.iter_mut().for_each(|mut entry| {
+            if let Some(map) = load_adjacent_sourcemap(entry.key()) {
+                let file = entry.key().clone();
+                let source = std::fs::read_to_string(&file).unwrap_or_default();
+                remap_findings(&file, &source, &map, entry.value_mut());
+            }
+        });
+    }
+
+    /// Owned snapshot of every indexed component, without a JSON round-trip
+    pub fn iter_components(&self) -> impl Iterator<Item = ComponentMeta> + '_ {
+        self.components.iter().map(|e| e.value().clone())
+    }
+
+    /// Every finding paired with the file it was found in
+    pub fn iter_findings(&self) -> impl Iterator<Item = (String, Finding)> + '_ {
+        self.findings.iter().flat_map(|e| {
+            let file = e.key().clone();
+            e.value().clone().into_iter().map(move |f| (file.clone(), f))
+        })
+    }
+
+    /// Component a JSX element name refers to from `file`: defined in the same file,
+    /// or imported by a relative path from an indexed file. For member names only
+    /// the root is looked up, and `<ns.Button>` resolves through `import * as ns`.
+    fn resolve_element(&self, file: &str, element: &str, by_path: &HashMap<PathBuf, String>) -> Option<ComponentMeta> {
+        let (root, member) = match element.split_once('.') {
+            Some((root, member)) => (root, Some(member)),
+            None => (element, None),
+        };
+        if member.is_none() {
+            if let Some(local) = self.file_components.get(file).and_then(|cs| cs.iter().find(|c| c.name == element).cloned()) {
+                return Some(local);
+            }
+        }
+        let imports = self.imports.get(file)?;
+        let (import, spec) = imports
+            .iter()
+            .filter(|i| i.kind == ImportSourceKind::Relative)
+            .find_map(|i| i.specifiers.iter().find(|s| s.local == root).map(|s| (i, s)))?;
+        let base = Path::new(file).parent().unwrap_or_else(|| Path::new("")).join(&import.source);
+        let target = resolve_module_path(&base, by_path)?;
+        let components = self.file_components.get(target)?;
+        let wanted = match (spec.imported.as_deref(), member) {
+            (Some("*"), Some(name)) if !name.contains('.') => name.to_string(),
+            // a bare namespace, or a static on a component (`Form.Item`): nothing we index
+            (Some("*"), _) | (_, Some(_)) => return None,
+            (Some("default"), None) => components
+                .first()
+                .and_then(|c| c.exports.iter().find(|e| matches!(e.kind, ExportKind::Default) && e.source.is_none()))
+                .and_then(|e| e.local.clone())?,
+            (Some(name), None) => name.to_string(),
+            (None, None) => spec.local.clone(),
+        };
+        let found = components.iter().find(|c| c.name == wanted).cloned();
+        found
+    }
+
+    /// Inline props passed to memoized components, which re-render the child anyway.
+    /// A static `style` is left out: `StaticInlineStyle` already reports it.
+    pub fn defeated_memos(&self) -> Vec<DefeatedMemo> {
+        let by_path: HashMap<PathBuf, String> = self
+            .file_components
+            .iter()
+            .map(|e| (normalize_path(Path::new(e.key())), e.key().clone()))
+            .collect();
+        let mut out = Vec::new();
+        for entry in self.file_components.iter() {
+            let file = entry.key();
+            for parent in entry.value() {
+                for usage in parent.inline_props.iter().filter(|u| !(u.is_static && u.prop == "style")) {
+                    let Some(child) = self.resolve_element(file, &usage.element, &by_path) else { continue };
+                    if child.is_memoized {
+                        out.push(DefeatedMemo {
+                            child: child.name,
+                            child_file: child.file_path,
+                            parent: parent.name.clone(),
+                            file: file.clone(),
+                            prop: usage.prop.clone(),
+                            is_static: usage.is_static,
+                            line: usage.line,
                         });
-                        result
                     }
-                } else {
-                    extract_all(&source, file_path)
-                };
+                }
+            }
+        }
+        out.sort_by(|a, b| (&a.file, a.line, &a.prop).cmp(&(&b.file, b.line, &b.prop)));
+        out
+    }
 
-                if !imps.is_empty() {
-                    imports.insert(file_path.clone(), imps);
+    /// `PropStateNameClash` findings, each with the file of the parent passing the prop
+    pub fn prop_state_clashes(&self) -> Vec<(String, Finding)> {
+        let by_path: HashMap<PathBuf, String> = self
+            .file_components
+            .iter()
+            .map(|e| (normalize_path(Path::new(e.key())), e.key().clone()))
+            .collect();
+        let mut out = Vec::new();
+        for entry in self.file_components.iter() {
+            let file = entry.key();
+            for parent in entry.value() {
+                for usage in &parent.passed_props {
+                    let Some(child) = self.resolve_element(file, &usage.element, &by_path) else { continue };
+                    if child.state_names.contains(&usage.prop) {
+                        let kind = FindingKind::PropStateNameClash { child: child.name, name: usage.prop.clone() };
+                        out.push((file.clone(), Finding { kind, line: usage.line, column: usage.column, end_line: None, original: None }));
+                    }
                 }
-                if !exps.is_empty() {
-                    exports.insert(file_path.clone(), exps.clone());
+            }
+        }
+        out.sort_by(|a, b| (&a.0, a.1.line, a.1.column).cmp(&(&b.0, b.1.line, b.1.column)));
+        out
+    }
+
+    /// Unmemoized components with props, widest prop surface first. Those with more
+    /// than `min_props` props score double: they re-render for the most reasons.
+    /// Paths are relative to the root, as in `to_json`.
+    pub fn memo_priorities(&self, min_props: usize) -> Vec<MemoPriority> {
+        let mut out: Vec<MemoPriority> = self
+            .file_components
+            .iter()
+            .flat_map(|e| {
+                e.value()
+                    .iter()
+                    .filter(|c| !c.is_memoized && c.prop_count > 0)
+                    .map(|c| MemoPriority {
+                        component: c.name.clone(),
+                        file: self.portable_path(e.key()),
+                        line: c.line,
+                        prop_count: c.prop_count,
+                        score: if c.prop_count > min_props { c.prop_count * 2 } else { c.prop_count },
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        out.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| (&a.file, a.line).cmp(&(&b.file, b.line))));
+        out
+    }
+
+    /// Counts per rule, the `top_n` files with most findings and the memoized share of components
+    pub fn stats(&self, top_n: usize) -> GraphStats {
+        let mut by_rule = BTreeMap::new();
+        let mut per_file = Vec::new();
+        for entry in self.findings.iter() {
+            for f in entry.value() {
+                *by_rule.entry(f.kind.type_name().to_string()).or_insert(0) += 1;
+            }
+            per_file.push(FileFindingCount { file: self.portable_path(entry.key()), findings: entry.value().len() });
+        }
+        let total_findings = per_file.iter().map(|f| f.findings).sum();
+        per_file.sort_by(|a, b| b.findings.cmp(&a.findings).then_with(|| a.file.cmp(&b.file)));
+        per_file.truncate(top_n);
+
+        let (components, memoized_components) = self.component_counts();
+        let memoized_ratio = if components == 0 { 0.0 } else { memoized_components as f64 / components as f64 };
+
+        GraphStats { total_findings, by_rule, top_files: per_file, components, memoized_components, memoized_ratio }
+    }
+
+    /// (components, memoized components) over every file
+    fn component_counts(&self) -> (usize, usize) {
+        self.file_components
+            .iter()
+            .fold((0, 0), |(all, memo), e| (all + e.value().len(), memo + e.value().iter().filter(|c| c.is_memoized).count()))
+    }
+
+    /// `FileMetrics` for every file that was read, keyed by path as `to_json` emits it
+    pub fn file_metrics(&self) -> BTreeMap<String, FileMetrics> {
+        let mut metrics: BTreeMap<String, FileMetrics> = BTreeMap::new();
+        for entry in self.line_counts.iter() {
+            metrics.entry(entry.key().clone()).or_default().loc = *entry.value();
+        }
+        for entry in self.file_components.iter() {
+            let m = metrics.entry(entry.key().clone()).or_default();
+            m.component_count = entry.value().len();
+            m.memoized_count = entry.value().iter().filter(|c| c.is_memoized).count();
+        }
+        for entry in self.findings.iter() {
+            let m = metrics.entry(entry.key().clone()).or_default();
+            for f in entry.value() {
+                *m.finding_count_by_rule.entry(f.kind.type_name().to_string()).or_insert(0) += 1;
+            }
+        }
+        self.portable_keys(metrics)
+    }
+
+    /// Relative, absolute and aliased imports that resolve to no file: typos,
+    /// deleted modules, misconfigured aliases. Sorted by file and line; paths are
+    /// relative to the root, as in `to_json`.
+    pub fn unresolved_imports(&self) -> Vec<UnresolvedImport> {
+        let by_path: HashMap<PathBuf, String> = self
+            .imports
+            .iter()
+            .map(|e| e.key().clone())
+            .chain(self.exports.iter().map(|e| e.key().clone()))
+            .chain(self.file_components.iter().map(|e| e.key().clone()))
+            .map(|f| (normalize_path(Path::new(&f)), f))
+            .collect();
+        let mut out = Vec::new();
+        for entry in self.imports.iter() {
+            let file = entry.key();
+            for import in entry.value().iter().filter(|i| i.kind != ImportSourceKind::External) {
+                let bases = self.import_bases(file, import);
+                let resolved = bases.iter().any(|base| resolve_module_path(base, &by_path).is_some() || module_exists_on_disk(base));
+                if !resolved {
+                    out.push(UnresolvedImport { source: import.source.clone(), kind: import.kind, from_file: self.portable_path(file), line: import.line });
                 }
+            }
+        }
+        out.sort_by(|a, b| (&a.from_file, a.line).cmp(&(&b.from_file, b.line)));
+        out
+    }
 
-                for c in comps.drain(..) {
-                    let mut comp = c;
-                    comp.file_path = file_path.clone();
-                    // anexar exports do mesmo arquivo
-                    comp.exports = exps.clone();
-                    components.insert(file_path.clone(), comp);
+    /// Paths an import of `file` may point at, before extensions and index files are tried
+    fn import_bases(&self, file: &str, import: &ImportMeta) -> Vec<PathBuf> {
+        let dir = Path::new(file).parent().unwrap_or_else(|| Path::new(""));
+        match import.kind {
+            ImportSourceKind::External => vec![],
+            ImportSourceKind::Relative => vec![dir.join(&import.source)],
+            ImportSourceKind::Absolute => vec![PathBuf::from(&import.source)],
+            ImportSourceKind::Alias => self.options.path_aliases.candidates(&import.source),
+        }
+    }
+
+    /// Files that import `file_path`; their cross-file results (defeated memos, prop/state
+    /// clashes) depend on what it exports
+    pub fn consumers(&self, file_path: &str) -> Vec<String> {
+        let target = normalize_path(Path::new(file_path));
+        let mut out: Vec<String> = self
+            .imports
+            .iter()
+            .filter(|entry| entry.key() != file_path)
+            .filter(|entry| {
+                entry.value().iter().any(|import| {
+                    self.import_bases(entry.key(), import).iter().any(|base| module_path_candidates(base).any(|c| c == target))
+                })
+            })
+            .map(|entry| entry.key().clone())
+            .collect();
+        out.sort();
+        out
+    }
+
+    /// Resolved imports and re-exports between indexed files, with their cycles; external
+    /// and unresolved imports are left out. Paths are relative to the root, as in `to_json`.
+    pub fn import_graph(&self) -> ImportGraph {
+        let files: Vec<String> = self
+            .imports
+            .iter()
+            .map(|e| e.key().clone())
+            .chain(self.exports.iter().map(|e| e.key().clone()))
+            .chain(self.file_components.iter().map(|e| e.key().clone()))
+            .collect();
+        let by_path: HashMap<PathBuf, String> = files.iter().map(|f| (normalize_path(Path::new(f)), f.clone())).collect();
+        let mut edges: BTreeMap<String, Vec<String>> = files.into_iter().map(|f| (f, Vec::new())).collect();
+        for (file, targets) in edges.iter_mut() {
+            let imports = self.imports.get(file).map(|i| i.value().clone()).unwrap_or_default();
+            let reexports = self.exports.get(file).map(|e| e.value().iter().filter_map(|x| x.source.clone()).collect()).unwrap_or_else(Vec::new);
+            let reexports = reexports.into_iter().map(|source| ImportMeta {
+                kind: classify_import_source(&source, &self.options.path_aliases),
+                source,
+                specifiers: Vec::new(),
+                line: 0,
+            });
+            for import in imports.into_iter().chain(reexports).filter(|i| i.kind != ImportSourceKind::External) {
+                if let Some(target) = self.import_bases(file, &import).iter().find_map(|base| resolve_module_path(base, &by_path)) {
+                    targets.push(target.clone());
                 }
             }
-        });
+            targets.sort();
+            targets.dedup();
+        }
+        let cycles = import_cycles(&edges).into_iter().map(|cycle| cycle.iter().map(|f| self.portable_path(f)).collect()).collect();
+        let edges = edges
+            .into_iter()
+            .map(|(file, targets)| (self.portable_path(&file), targets.iter().map(|t| self.portable_path(t)).collect()))
+            .collect();
+        ImportGraph { edges, cycles }
+    }
 
-        Self {
-            components,
-            imports,
-            exports,
+    /// Imports resolving to an indexed file with at least `min_reexports` re-exports
+    /// (`export { a, b } from` counts 2, `export * from` 1). Sorted by file and line;
+    /// paths are relative to the root, as in `to_json`.
+    pub fn barrel_imports(&self, min_reexports: usize) -> Vec<BarrelImport> {
+        let barrels: HashMap<PathBuf, String> = self
+            .exports
+            .iter()
+            .filter(|e| e.value().iter().filter(|x| x.source.is_some()).count() >= min_reexports)
+            .map(|e| (normalize_path(Path::new(e.key())), e.key().clone()))
+            .collect();
+        let mut out = Vec::new();
+        if barrels.is_empty() {
+            return out;
+        }
+        for entry in self.imports.iter() {
+            let file = entry.key();
+            for import in entry.value().iter().filter(|i| i.kind != ImportSourceKind::External) {
+                let bases = self.import_bases(file, import);
+                let Some(barrel) = bases.iter().find_map(|base| resolve_module_path(base, &barrels)) else { continue };
+                for spec in &import.specifiers {
+                    out.push(BarrelImport {
+                        source: import.source.clone(),
+                        symbol: spec.imported.clone().unwrap_or_else(|| spec.local.clone()),
+                        barrel: self.portable_path(barrel),
+                        from_file: self.portable_path(file),
+                        line: import.line,
+                    });
+                }
+            }
+        }
+        out.sort_by(|a, b| (&a.from_file, a.line, &a.symbol).cmp(&(&b.from_file, b.line, &b.symbol)));
+        out
+    }
+
+    /// Exports imported under different local names in different places (`import Button`
+    /// here, `import Btn` there), sorted by source and export. Namespace imports are skipped.
+    /// Paths are relative to the root, as in `to_json`.
+    pub fn inconsistent_import_aliases(&self) -> Vec<InconsistentImportAlias> {
+        let by_path: HashMap<PathBuf, String> = self.hashes.iter().map(|e| (normalize_path(Path::new(e.key())), e.key().clone())).collect();
+        // (source, export) -> local name -> importing files
+        let mut seen: BTreeMap<(String, String), BTreeMap<String, Vec<String>>> = BTreeMap::new();
+        for entry in self.imports.iter() {
+            let file = entry.key();
+            for import in entry.value() {
+                let source = match import.kind {
+                    ImportSourceKind::External => import.source.clone(),
+                    _ => match self.import_bases(file, import).iter().find_map(|base| resolve_module_path(base, &by_path)) {
+                        Some(resolved) => self.portable_path(resolved),
+                        None => continue,
+                    },
+                };
+                let file = self.portable_path(file);
+                for spec in import.specifiers.iter().filter(|s| s.imported.as_deref() != Some("*")) {
+                    let export = spec.imported.clone().unwrap_or_else(|| spec.local.clone());
+                    let files = seen.entry((source.clone(), export)).or_default().entry(spec.local.clone()).or_default();
+                    if !files.contains(&file) {
+                        files.push(file.clone());
+                    }
+                }
+            }
         }
+        seen.into_iter()
+            .filter(|(_, aliases)| aliases.len() > 1)
+            .map(|((source, export), mut aliases)| {
+                aliases.values_mut().for_each(|files| files.sort());
+                InconsistentImportAlias { source, export, aliases }
+            })
+            .collect()
     }
 
     pub fn get_memo_boundary(&self, symbol: &str) -> Option<ComponentMeta> {
@@ -129,26 +1078,428 @@ impl MetadataGraph {
         self.components.get(file).map(|c| c.is_memoized).unwrap_or(false)
     }
 
+    /// Full snapshot; also resets the change tracking used by `to_json_delta`.
+    /// Files are keyed in path order and entries within a file in source order,
+    /// so two runs over the same tree produce identical output.
     pub fn to_json(&self) -> String {
+        self.to_json_with_limit(None)
+    }
+
+    /// `to_json` keeping at most `max_findings` findings, taken in path and source order
+    pub fn to_json_with_limit(&self, max_findings: Option<usize>) -> String {
+        self.dirty.clear();
+        self.removed.clear();
         let components_map = self
             .components
             .iter()
             .map(|e| (e.key().clone(), e.value().clone()))
             .collect();
-        let imports_map = self
-            .imports
+        let components_map = self.portable_components(components_map);
+        let imports_map = self.portable_keys(sorted_entries(&self.imports, None, |i| (i.line, 0)));
+        let exports_map = self.portable_keys(sorted_entries(&self.exports, None, |e| (e.line, 0)));
+        let mut findings_map = self.portable_keys(sorted_entries(&self.findings, None, |f| (f.line, f.column)));
+        let truncation = max_findings.and_then(|max| cap_findings(&mut findings_map, max));
+        let mut timeouts: Vec<ParseTimeout> = self.timeouts.iter().map(|e| e.value().clone()).collect();
+        timeouts.iter_mut().for_each(|t| t.path = self.portable_path(&t.path));
+        timeouts.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut diagnostics: Vec<AnalysisDiagnostic> = self.diagnostics.iter().flat_map(|e| e.value().clone()).collect();
+        diagnostics.iter_mut().for_each(|d| d.path = self.portable_path(&d.path));
+        diagnostics.sort_by(|a, b| a.path.cmp(&b.path));
+        let (components, memoized_components) = self.component_counts();
+        let component_counts = ComponentCounts { components, memoized_components };
+        serde_json::to_string(&GraphSnapshot { components: components_map, component_counts, imports: imports_map, exports: exports_map, findings: findings_map, timeouts, diagnostics, truncation }).unwrap_or_else(|_| "{}".into())
+    }
+
+    /// Findings of every enabled rule and the cross-file checks, paths as `to_json` emits them
+    /// `severities` maps a finding `type` to the severity it's reported with, overriding its default.
+    /// Cross-file findings are computed here, so `changed` filters them as `retain_changed` did the rest.
+    pub fn lint_report(&self, min_props: usize, severities: &HashMap<String, Severity>, changed: Option<&ChangedLines>) -> LintReport {
+        let mut all = sorted_entries(&self.findings, None, |f| (f.line, f.column));
+        for (file, finding) in self.prop_state_clashes() {
+            if changed.is_some_and(|c| !c.contains(&self.portable_path(&file), finding.line)) {
+                continue;
+            }
+            let findings = all.entry(file).or_default();
+            let at = findings.partition_point(|f| (f.line, f.column) <= (finding.line, finding.column));
+            findings.insert(at, finding);
+        }
+        let findings: BTreeMap<String, Vec<LintFinding>> = self
+            .portable_keys(all)
+            .into_iter()
+            .map(|(file, findings)| {
+                let findings = findings
+                    .into_iter()
+                    .map(|finding| {
+                        let severity = severities.get(finding.kind.type_name()).copied().unwrap_or_else(|| finding.kind.default_severity());
+                        LintFinding { finding, severity }
+                    })
+                    .filter(|f| f.severity != Severity::Off)
+                    .collect::<Vec<_>>();
+                (file, findings)
+            })
+            .filter(|(_, findings)| !findings.is_empty())
+            .collect();
+        let total_findings = findings.values().map(Vec::len).sum();
+        let count = |severity| findings.values().flatten().filter(|f| f.severity == severity).count();
+        let (errors, warnings, infos) = (count(Severity::Error), count(Severity::Warning), count(Severity::Info));
+        let defeated_memos = self
+            .defeated_memos()
+            .into_iter()
+            .map(|d| DefeatedMemo { child_file: self.portable_path(&d.child_file), file: self.portable_path(&d.file), ..d })
+            .collect();
+        let memo_priorities = self.memo_priorities(min_props);
+        let mut timeouts: Vec<ParseTimeout> = self.timeouts.iter().map(|e| ParseTimeout { path: self.portable_path(&e.path), ..e.value().clone() }).collect();
+        timeouts.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut diagnostics: Vec<AnalysisDiagnostic> = self
+            .diagnostics
             .iter()
-            .map(|e| (e.key().clone(), e.value().clone()))
+            .flat_map(|e| e.value().clone())
+            .map(|d| AnalysisDiagnostic { path: self.portable_path(&d.path), ..d })
             .collect();
-        let exports_map = self
-            .exports
+        diagnostics.sort_by(|a, b| a.path.cmp(&b.path));
+        LintReport { total_findings, errors, warnings, infos, findings, defeated_memos, memo_priorities, timeouts, diagnostics }
+    }
+
+    /// Drop findings outside the lines `changed` touched, so only new debt is reported
+    pub fn retain_changed(&self, changed: &ChangedLines) {
+        self.findings.iter_mut().for_each(|mut entry| {
+            let path = self.portable_path(entry.key());
+            entry.value_mut().retain(|f| changed.contains(&path, f.line));
+        });
+        self.findings.retain(|_, findings| !findings.is_empty());
+    }
+
+    /// `path` as emitted: relative to the project root when under it, always with `/` separators
+    fn portable_path(&self, path: &str) -> String {
+        let relative = self.root.as_deref().and_then(|root| Path::new(path).strip_prefix(root).ok());
+        match relative {
+            Some(rel) => rel.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"),
+            None => path.replace('\\', "/"),
+        }
+    }
+
+    fn portable_keys<T>(&self, map: BTreeMap<String, T>) -> BTreeMap<String, T> {
+        map.into_iter().map(|(file, v)| (self.portable_path(&file), v)).collect()
+    }
+
+    fn portable_components(&self, map: BTreeMap<String, ComponentMeta>) -> BTreeMap<String, ComponentMeta> {
+        map.into_iter()
+            .map(|(file, mut c)| {
+                c.file_path = self.portable_path(&c.file_path);
+                (self.portable_path(&file), c)
+            })
+            .collect()
+    }
+
+    /// Only the files updated or removed since the last serialization, then reset
+    pub fn to_json_delta(&self) -> String {
+        let mut changed: Vec<String> = self.dirty.iter().map(|f| f.key().clone()).collect();
+        let mut removed: Vec<String> = self.removed.iter().map(|f| f.key().clone()).collect();
+        changed.sort();
+        removed.sort();
+        self.dirty.clear();
+        self.removed.clear();
+
+        let components = self.portable_components(changed.iter().filter_map(|f| self.components.get(f).map(|c| (f.clone(), c.clone()))).collect());
+        let imports = self.portable_keys(sorted_entries(&self.imports, Some(&changed), |i| (i.line, 0)));
+        let exports = self.portable_keys(sorted_entries(&self.exports, Some(&changed), |e| (e.line, 0)));
+        let findings = self.portable_keys(sorted_entries(&self.findings, Some(&changed), |f| (f.line, f.column)));
+        let timeouts = changed
             .iter()
-            .map(|e| (e.key().clone(), e.value().clone()))
+            .filter_map(|f| self.timeouts.get(f).map(|t| ParseTimeout { path: self.portable_path(&t.path), ..t.clone() }))
             .collect();
-        serde_json::to_string(&GraphSnapshot { components: components_map, imports: imports_map, exports: exports_map }).unwrap_or_else(|_| "{}".into())
+        let diagnostics = changed
+            .iter()
+            .filter_map(|f| self.diagnostics.get(f).map(|d| d.clone()))
+            .flatten()
+            .map(|d| AnalysisDiagnostic { path: self.portable_path(&d.path), ..d })
+            .collect();
+        let changed = changed.iter().map(|f| self.portable_path(f)).collect();
+        let removed = removed.iter().map(|f| self.portable_path(f)).collect();
+        serde_json::to_string(&GraphDelta { changed, components, imports, exports, findings, timeouts, diagnostics, removed }).unwrap_or_else(|_| "{}".into())
+    }
+}
+
+/// Per-file entries (optionally only `files`) in path order, each list sorted by `position`
+fn sorted_entries<T: Clone>(
+    map: &DashMap<String, Vec<T>>,
+    files: Option<&[String]>,
+    position: impl Fn(&T) -> (usize, usize),
+) -> BTreeMap<String, Vec<T>> {
+    let sort = |(file, mut items): (String, Vec<T>)| {
+        items.sort_by_key(|item| position(item));
+        (file, items)
+    };
+    match files {
+        Some(files) => files.iter().filter_map(|f| map.get(f).map(|v| (f.clone(), v.clone()))).map(sort).collect(),
+        None => map.iter().map(|e| (e.key().clone(), e.value().clone())).map(sort).collect(),
+    }
+}
+
+/// Reads tried per file before it's reported unreadable
+#[cfg(not(target_arch = "wasm32"))]
+const READ_ATTEMPTS: usize = 3;
+
+/// `provider.read`, retried with a growing pause: network filesystems and concurrent git
+/// checkouts fail reads transiently. Non-UTF-8 content fails the same way every time and
+/// isn't retried. On failure, returns the last error and the number of attempts.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_with_retry(provider: &dyn FileProvider, path: &str) -> Result<String, (std::io::Error, usize)> {
+    let mut attempt = 1;
+    loop {
+        match provider.read(path) {
+            Ok(source) => return Ok(source),
+            Err(err) if err.kind() == std::io::ErrorKind::InvalidData || attempt == READ_ATTEMPTS => return Err((err, attempt)),
+            Err(err) => {
+                trace!(file = path, attempt, error = %err, "read failed, retrying");
+                std::thread::sleep(Duration::from_millis(10 << (2 * (attempt - 1))));
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Built-in rules plus the opt-in ones enabled in `options`
+fn rule_registry(options: &IndexOptions) -> RuleRegistry {
+    let mut rules = RuleRegistry::without(&options.disabled_rules);
+    let enabled = |name: &str| !options.disabled_rules.iter().any(|d| d == name);
+    if let Some(max_members) = options.max_union_members.filter(|_| enabled("LargeUnionProp")) {
+        rules.register(Box::new(LargeUnionPropRule { max_members }));
+    }
+    if let Some(min_depth) = options.min_ternary_depth {
+        rules.replace(Box::new(DeepJsxTernaryRule { min_depth }));
+    }
+    if let Some(max_props) = options.max_props {
+        rules.replace(Box::new(WidePropsRule { max_props }));
+    }
+    if !options.heavy_dependencies.is_empty() {
+        rules.replace(Box::new(HeavyDependencyRule::with_overrides(&options.heavy_dependencies)));
+    }
+    if options.check_regex && enabled("UnsafeRegex") {
+        rules.register(Box::new(UnsafeRegexRule));
+    }
+    rules
+}
+
+/// Copy the entry `from` has for `file` into `into`, replacing what's there
+fn absorb<V: Clone>(into: &DashMap<String, V>, from: &DashMap<String, V>, file: &str) {
+    if let Some(entry) = from.get(file) {
+        into.insert(file.to_string(), entry.value().clone());
+    }
+}
+
+/// Seconds since the epoch, the unit `IncrementalCache` stamps entries with
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Cached analyses depend on the opt-in rules and docs, so their settings are part of the key
+#[cfg(not(target_arch = "wasm32"))]
+fn cache_version(options: &IndexOptions) -> String {
+    let mut version = "0.15.0".to_string();
+    if let Some(max) = options.max_union_members {
+        version.push_str(&format!("+union{}", max));
+    }
+    if options.collect_docs {
+        version.push_str("+docs");
+    }
+    if options.check_regex {
+        version.push_str("+regex");
+    }
+    // the two modes lay entries out differently on disk
+    if options.dedup_cache {
+        version.push_str("+dedup");
+    }
+    if let Some(depth) = options.min_ternary_depth {
+        version.push_str(&format!("+ternary{}", depth));
+    }
+    if let Some(max) = options.max_props {
+        version.push_str(&format!("+props{}", max));
+    }
+    if !options.heavy_dependencies.is_empty() {
+        version.push_str(&format!("+heavy{:x}", content_hash(&format!("{:?}", options.heavy_dependencies))));
+    }
+    let mut disabled = options.disabled_rules.clone();
+    disabled.sort();
+    for rule in disabled {
+        version.push_str(&format!("-{}", rule));
+    }
+    version
+}
+
+/// Keep the first `max` findings across files; files left empty are dropped
+fn cap_findings(findings: &mut BTreeMap<String, Vec<Finding>>, max: usize) -> Option<Truncation> {
+    let total: usize = findings.values().map(Vec::len).sum();
+    if total <= max {
+        return None;
+    }
+    let mut left = max;
+    findings.retain(|_, items| {
+        items.truncate(left);
+        left -= items.len();
+        !items.is_empty()
+    });
+    Some(Truncation { truncated: true, total_estimated: total })
+}
+
+/// What a full index would cover, computed from the file walk alone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScopeEstimate {
+    pub file_count: usize,
+    pub total_bytes: u64,
+    /// File count per extension (without the dot)
+    pub by_extension: std::collections::BTreeMap<String, usize>,
+}
+
+/// Dry run of `index_project`: walk and stat files without parsing anything
+#[cfg(not(target_arch = "wasm32"))]
+pub fn estimate_scope(root: &str) -> ScopeEstimate {
+    let files = find_all_source_files(root);
+    let mut by_extension = std::collections::BTreeMap::new();
+    let mut total_bytes = 0;
+    for f in &files {
+        total_bytes += std::fs::metadata(f).map(|m| m.len()).unwrap_or(0);
+        let ext = std::path::Path::new(f).extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        *by_extension.entry(ext).or_insert(0) += 1;
+    }
+    ScopeEstimate { file_count: files.len(), total_bytes, by_extension }
+}
+
+/// Keep files matching at least one include glob; no globs keeps everything.
+/// Invalid globs are skipped with a warning rather than aborting the index.
+#[cfg(not(target_arch = "wasm32"))]
+fn filter_included(root: &str, files: Vec<String>, globs: &[String]) -> Vec<String> {
+    if globs.is_empty() {
+        return files;
+    }
+    let mut builder = globset::GlobSetBuilder::new();
+    for g in globs {
+        match globset::Glob::new(g) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(err) => warn!(glob = g.as_str(), %err, "ignoring invalid include glob"),
+        }
+    }
+    let Ok(set) = builder.build() else { return files };
+    files
+        .into_iter()
+        .filter(|f| {
+            let path = std::path::Path::new(f);
+            set.is_match(path.strip_prefix(root).unwrap_or(path))
+        })
+        .collect()
+}
+
+/// Lexically resolve `.` and `..` so import targets compare equal to walked paths
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for c in path.components() {
+        match c {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    out.push("..");
+                }
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// Files an import path may refer to, in lookup order: the path itself, then with
+/// each source extension, then as a directory index (`./ui` -> `./ui/index.ts`)
+fn module_path_candidates(base: &Path) -> impl Iterator<Item = PathBuf> {
+    const EXTENSIONS: [&str; 4] = ["tsx", "ts", "jsx", "js"];
+    let base = normalize_path(base);
+    let with_ext = EXTENSIONS.iter().map({
+        let base = base.clone();
+        move |ext| {
+            let mut with_ext = base.clone().into_os_string();
+            with_ext.push(".");
+            with_ext.push(ext);
+            PathBuf::from(with_ext)
+        }
+    });
+    let index = EXTENSIONS.iter().map({
+        let base = base.clone();
+        move |ext| base.join(format!("index.{}", ext))
+    });
+    std::iter::once(base).chain(with_ext).chain(index)
+}
+
+/// Strongly connected components (Tarjan) of more than one file, or of one importing
+/// itself; each sorted, and the list sorted by first file
+fn import_cycles(edges: &BTreeMap<String, Vec<String>>) -> Vec<Vec<String>> {
+    struct Tarjan<'a> {
+        edges: &'a BTreeMap<String, Vec<String>>,
+        index: HashMap<&'a str, usize>,
+        low: HashMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: std::collections::HashSet<&'a str>,
+        cycles: Vec<Vec<String>>,
+    }
+
+    impl<'a> Tarjan<'a> {
+        fn visit(&mut self, file: &'a str) {
+            let n = self.index.len();
+            self.index.insert(file, n);
+            self.low.insert(file, n);
+            self.stack.push(file);
+            self.on_stack.insert(file);
+            let edges = self.edges;
+            for target in edges.get(file).into_iter().flatten() {
+                let target = target.as_str();
+                if !self.index.contains_key(target) {
+                    self.visit(target);
+                    let low = self.low[file].min(self.low[target]);
+                    self.low.insert(file, low);
+                } else if self.on_stack.contains(target) {
+                    let low = self.low[file].min(self.index[target]);
+                    self.low.insert(file, low);
+                }
+            }
+            if self.low[file] == self.index[file] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(member);
+                    component.push(member.to_string());
+                    if member == file {
+                        break;
+                    }
+                }
+                let self_import = edges.get(file).is_some_and(|t| t.iter().any(|t| t == file));
+                if component.len() > 1 || self_import {
+                    component.sort();
+                    self.cycles.push(component);
+                }
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan { edges, index: HashMap::new(), low: HashMap::new(), stack: Vec::new(), on_stack: Default::default(), cycles: Vec::new() };
+    for file in edges.keys() {
+        if !tarjan.index.contains_key(file.as_str()) {
+            tarjan.visit(file);
+        }
     }
+    tarjan.cycles.sort();
+    tarjan.cycles
 }
 
+/// Indexed file an extensionless import path refers to (`./Button` -> `./Button.tsx`, `./ui/index.ts`)
+fn resolve_module_path<'a>(base: &Path, by_path: &'a HashMap<PathBuf, String>) -> Option<&'a String> {
+    module_path_candidates(base).find_map(|candidate| by_path.get(&candidate))
+}
+
+/// Fallback for targets the index doesn't cover: assets (`./logo.svg`), skipped
+/// directories, files without imports or exports
+fn module_exists_on_disk(base: &Path) -> bool {
+    module_path_candidates(base).any(|candidate| candidate.is_file())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 pub fn find_all_source_files(root: &str) -> Vec<String> {
     use walkdir::WalkDir;
     let mut out = Vec::new();
@@ -163,7 +1514,7 @@ pub fn find_all_source_files(root: &str) -> Vec<String> {
                 continue;
             }
             if let Some(ext) = p.extension() {
-                if ext == "ts" || ext == "tsx" || ext == "js" || ext == "jsx" {
+                if ext == "ts" || ext == "tsx" || ext == "js" || ext == "jsx" || ext == "vue" || ext == "astro" {
                     out.push(p.to_string_lossy().to_string());
                 }
             }
@@ -171,3 +1522,462 @@ pub fn find_all_source_files(root: &str) -> Vec<String> {
     }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn graph(files: &[(&str, &str)]) -> MetadataGraph {
+        let graph = MetadataGraph::empty(IndexOptions::default());
+        for (path, source) in files {
+            graph.update_file(path, source);
+        }
+        graph
+    }
+
+    fn component(graph: &MetadataGraph, file: &str) -> String {
+        graph.components.get(file).expect("component indexed").name.clone()
+    }
+
+    /// `graph` with `options`, emitting paths relative to `/p`
+    fn rooted(options: IndexOptions, files: &[(&str, &str)]) -> MetadataGraph {
+        let mut graph = MetadataGraph::empty(options);
+        graph.root = Some(PathBuf::from("/p"));
+        for (path, source) in files {
+            graph.update_file(path, source);
+        }
+        graph
+    }
+
+    /// Fails its first `failures` reads with `kind`, then returns the path
+    struct FlakyFs {
+        failures: usize,
+        kind: std::io::ErrorKind,
+        reads: AtomicUsize,
+    }
+
+    impl FileProvider for FlakyFs {
+        fn read(&self, path: &str) -> std::io::Result<String> {
+            if self.reads.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(std::io::Error::new(self.kind, "flaky"));
+            }
+            Ok(path.to_string())
+        }
+        fn list(&self, _root: &str) -> Vec<String> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn test_read_with_retry_outlasts_transient_failures() {
+        let fs = FlakyFs { failures: READ_ATTEMPTS - 1, kind: std::io::ErrorKind::Interrupted, reads: Default::default() };
+        assert_eq!(read_with_retry(&fs, "a.tsx").unwrap(), "a.tsx");
+        assert_eq!(fs.reads.load(Ordering::SeqCst), READ_ATTEMPTS);
+
+        let fs = FlakyFs { failures: READ_ATTEMPTS, kind: std::io::ErrorKind::Interrupted, reads: Default::default() };
+        let (err, attempts) = read_with_retry(&fs, "a.tsx").unwrap_err();
+        assert_eq!((err.kind(), attempts), (std::io::ErrorKind::Interrupted, READ_ATTEMPTS));
+    }
+
+    #[test]
+    fn test_read_with_retry_gives_up_on_invalid_data() {
+        // non-UTF-8 content reads the same every time
+        let fs = FlakyFs { failures: 1, kind: std::io::ErrorKind::InvalidData, reads: Default::default() };
+        let (err, attempts) = read_with_retry(&fs, "a.tsx").unwrap_err();
+        assert_eq!((err.kind(), attempts), (std::io::ErrorKind::InvalidData, 1));
+        assert_eq!(fs.reads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_merge_keeps_the_newer_analysis() {
+        let older = graph(&[("/p/a.tsx", "export function Old() { return <div />; }"), ("/p/b.tsx", "export function B() { return <div />; }")]);
+        let newer = graph(&[("/p/a.tsx", "export function New() { return <div />; }")]);
+        older.modified_at.insert("/p/a.tsx".into(), 100);
+        newer.modified_at.insert("/p/a.tsx".into(), 200);
+
+        // the newer graph comes first, so "last graph wins" would pick `Old`
+        let merged = MetadataGraph::merge(vec![newer, older]);
+        assert_eq!(component(&merged, "/p/a.tsx"), "New");
+        assert_eq!(component(&merged, "/p/b.tsx"), "B");
+        assert_eq!(*merged.modified_at.get("/p/a.tsx").unwrap(), 200);
+    }
+
+    #[test]
+    fn test_merge_ties_go_to_the_later_graph() {
+        let first = graph(&[("/p/a.tsx", "export function First() { return <div />; }")]);
+        let second = graph(&[("/p/a.tsx", "export function Second() { return <div />; }")]);
+        first.modified_at.insert("/p/a.tsx".into(), 100);
+        second.modified_at.insert("/p/a.tsx".into(), 100);
+
+        let merged = MetadataGraph::merge(vec![first, second]);
+        assert_eq!(component(&merged, "/p/a.tsx"), "Second");
+        assert_eq!(merged.file_components.get("/p/a.tsx").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_reports_use_root_relative_paths() {
+        let mut graph = MetadataGraph::empty(IndexOptions::default());
+        graph.root = Some(PathBuf::from("/p"));
+        let source = "import { useState } from 'react';\nimport { x } from './missing';\nexport function Wide({ a, b }) {\n  const [v] = useState(compute());\n  return <div>{v}</div>;\n}\n";
+        graph.update_file("/p/src/a.tsx", source);
+
+        assert_eq!(graph.memo_priorities(5)[0].file, "src/a.tsx");
+        assert_eq!(graph.unresolved_imports()[0].from_file, "src/a.tsx");
+        assert_eq!(graph.stats(10).top_files[0].file, "src/a.tsx");
+    }
+
+    #[test]
+    fn test_prop_state_clash_is_a_lint_finding() {
+        let graph = graph(&[
+            ("/p/Child.tsx", "import { useState } from 'react';\nexport function Child() {\n  const [open, setOpen] = useState(false);\n  return <div onClick={() => setOpen(!open)} />;\n}\n"),
+            ("/p/Parent.tsx", "import { Child } from './Child';\nexport function Parent() {\n  return <Child open={true} label=\"x\" />;\n}\n"),
+        ]);
+        let report = graph.lint_report(5, &HashMap::new(), None);
+        let clashes: Vec<&LintFinding> = report.findings.values().flatten().filter(|f| f.finding.kind.type_name() == "PropStateNameClash").collect();
+        assert_eq!(clashes.len(), 1);
+        assert!(matches!(&clashes[0].finding.kind, FindingKind::PropStateNameClash { child, name } if child == "Child" && name == "open"));
+        assert_eq!((clashes[0].finding.line, clashes[0].finding.column), (3, 17));
+        assert!(report.findings["/p/Parent.tsx"].iter().any(|f| f.finding.kind.type_name() == "PropStateNameClash"));
+
+        let off = HashMap::from([("PropStateNameClash".to_string(), Severity::Off)]);
+        assert!(graph.lint_report(5, &off, None).findings.values().flatten().all(|f| f.finding.kind.type_name() != "PropStateNameClash"));
+    }
+
+    #[test]
+    fn test_diff_filters_prop_state_clashes() {
+        let graph = rooted(IndexOptions::default(), &[
+            ("/p/Child.tsx", "import { useState } from 'react';\nexport function Child() {\n  const [open, setOpen] = useState(false);\n  return <div onClick={() => setOpen(!open)} />;\n}\n"),
+            ("/p/Parent.tsx", "import { Child } from './Child';\nexport function Parent() {\n  return <Child open={true} />;\n}\nexport function Other() {\n  return <Child open={false} />;\n}\n"),
+        ]);
+        let clash_lines = |changed: &ChangedLines| -> Vec<usize> {
+            graph.lint_report(5, &HashMap::new(), Some(changed)).findings.values().flatten().map(|f| f.finding.line).collect()
+        };
+        // only the clash on a touched line of the parent survives
+        let touched = ChangedLines::parse("+++ b/Parent.tsx\n@@ -5,3 +5,3 @@\n");
+        assert_eq!(clash_lines(&touched), vec![6]);
+        assert!(clash_lines(&ChangedLines::parse("+++ b/Child.tsx\n@@ -1,5 +1,5 @@\n")).is_empty());
+    }
+
+    #[test]
+    fn test_barrel_imports_resolve_like_other_imports() {
+        let aliases = PathAliases { base_dir: "/p".into(), paths: HashMap::from([("@/*".to_string(), vec!["src/*".to_string()])]) };
+        let graph = rooted(IndexOptions { path_aliases: aliases, ..Default::default() }, &[
+            ("/p/src/ui/index.ts", "export { Button } from './Button';\nexport * from './Card';\n"),
+            ("/p/src/App.tsx", "import { Button } from './ui';\nimport { Card } from '@/ui';\nimport { ui } from 'ui';\nexport function App() { return <Button />; }\n"),
+        ]);
+        // a package that happens to share the barrel's name isn't it
+        let found: Vec<(String, String, String, usize)> =
+            graph.barrel_imports(2).into_iter().map(|b| (b.source, b.symbol, b.barrel, b.line)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("./ui".to_string(), "Button".to_string(), "src/ui/index.ts".to_string(), 1),
+                ("@/ui".to_string(), "Card".to_string(), "src/ui/index.ts".to_string(), 2),
+            ]
+        );
+        assert!(graph.barrel_imports(3).is_empty());
+    }
+
+    #[test]
+    fn test_partial_run_against_a_warm_cache() {
+        let dir = std::env::temp_dir().join("perf_linter_since_project");
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        let root = dir.to_string_lossy().to_string();
+        let options = IndexOptions { cache_dir: Some(dir.join(".cache")), ..Default::default() };
+        let noisy = "export function A() {\n  return <div style={{ margin: 0 }} />;\n}\n";
+        fs::write(dir.join("A.tsx"), noisy).unwrap();
+        fs::write(dir.join("B.tsx"), "export function B() { return <div />; }\n").unwrap();
+        MetadataGraph::index_project_with_options(&root, &options);
+
+        fs::write(dir.join("B.tsx"), "export function Bee() { return <div style={{ margin: 0 }} />; }\n").unwrap();
+        fs::write(dir.join("C.tsx"), "export function C() { return <div />; }\n").unwrap();
+        let partial = MetadataGraph::index_project_since(&root, &options, &ChangedFiles::from_list(&root, "B.tsx\nC.tsx\n"));
+
+        let a = dir.join("A.tsx").to_string_lossy().to_string();
+        let b = dir.join("B.tsx").to_string_lossy().to_string();
+        let c = dir.join("C.tsx").to_string_lossy().to_string();
+        // unchanged files come from the cache and keep their components, but not their findings
+        assert_eq!(component(&partial, &a), "A");
+        assert!(partial.findings.get(&a).is_none_or(|f| f.is_empty()));
+        assert_eq!(component(&partial, &b), "Bee");
+        assert!(!partial.findings.get(&b).expect("changed file analyzed").is_empty());
+        assert_eq!(component(&partial, &c), "C");
+        // the cached file wasn't read, but its metrics still count its lines
+        let loc: Vec<(String, usize)> = partial.file_metrics().into_iter().map(|(file, m)| (file, m.loc)).collect();
+        assert_eq!(loc, vec![("A.tsx".to_string(), 3), ("B.tsx".to_string(), 1), ("C.tsx".to_string(), 1)]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_defeated_memos_leave_static_styles_to_their_rule() {
+        let graph = graph(&[
+            ("/p/Child.tsx", "import { memo } from 'react';\nexport const Child = memo((props) => <div />);\n"),
+            ("/p/Parent.tsx", "import { Child } from './Child';\nexport function Parent({ n }) {\n  return <Child style={{ margin: 0 }} opts={{ dense: true }} onPick={() => n} />;\n}\n"),
+        ]);
+        let memos = graph.defeated_memos();
+        let defeated: Vec<(&str, bool)> = memos.iter().map(|d| (d.prop.as_str(), d.is_static)).collect();
+        assert_eq!(defeated, vec![("onPick", false), ("opts", true)]);
+        let findings = graph.findings.get("/p/Parent.tsx").expect("parent has findings");
+        assert_eq!(findings.iter().filter(|f| f.kind.type_name() == "StaticInlineStyle").count(), 1);
+    }
+
+    #[test]
+    fn test_memo_priorities_rank_wide_components_first() {
+        let graph = rooted(IndexOptions::default(), &[
+            ("/p/Narrow.tsx", "export function Narrow({ a }) { return <div>{a}</div>; }\n"),
+            ("/p/Wide.tsx", "export function Wide({ a, b, c, d }) { return <div>{a}{b}{c}{d}</div>; }\n"),
+            ("/p/Memo.tsx", "import { memo } from 'react';\nexport const Memo = memo(({ a, b, c, d, e }) => <div>{a}{b}{c}{d}{e}</div>);\n"),
+            ("/p/Bare.tsx", "export function Bare() { return <div />; }\n"),
+            ("/p/pair.tsx", "export function Second({ a }) { return <i>{a}</i>; }\n\nexport function First({ a, b, c }) { return <b>{a}{b}{c}</b>; }\n"),
+        ]);
+        let ranked: Vec<(String, String, usize, usize, usize)> =
+            graph.memo_priorities(3).into_iter().map(|p| (p.component, p.file, p.line, p.prop_count, p.score)).collect();
+        // memoized and prop-less components are left out; equal scores go by file, then line
+        assert_eq!(
+            ranked,
+            vec![
+                ("Wide".to_string(), "Wide.tsx".to_string(), 1, 4, 8),
+                ("First".to_string(), "pair.tsx".to_string(), 3, 3, 3),
+                ("Narrow".to_string(), "Narrow.tsx".to_string(), 1, 1, 1),
+                ("Second".to_string(), "pair.tsx".to_string(), 1, 1, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_memoized_share_counts_every_component() {
+        let graph = graph(&[
+            ("/p/a.tsx", "import { memo } from 'react';\nfunction A() { return <div />; }\nexport const MemoA = memo(A);\nexport function B() { return <div />; }\n"),
+            ("/p/c.tsx", "export function C() { return <div />; }\n"),
+        ]);
+        // `a.tsx` holds two components but `components` in the JSON shows only one of them
+        let stats = graph.stats(10);
+        assert_eq!((stats.components, stats.memoized_components), (3, 1));
+        assert!((stats.memoized_ratio - 1.0 / 3.0).abs() < 1e-9);
+
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+        assert_eq!(json["component_counts"], serde_json::json!({ "components": 3, "memoized_components": 1 }));
+        assert_eq!(json["components"].as_object().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_non_component_dirs_require_jsx() {
+        let options = IndexOptions { non_component_dirs: vec!["utils/".into(), "lib/helpers".into()], ..Default::default() };
+        let format = "export function FormatDate(d) { return d.toISOString(); }\n";
+        let graph = rooted(options, &[
+            ("/p/utils/format.ts", format),
+            ("/p/src/utils/deep/format.ts", format),
+            ("/p/lib/helpers/format.ts", format),
+            ("/p/utils/Badge.tsx", "export function Badge() { return <span />; }\n"),
+            ("/p/src/format.ts", format),
+            ("/p/utilsx/format.ts", format),
+            ("/p/src/lib/helpers/format.ts", format),
+        ]);
+        // a bare name matches any directory segment; a path only from the root
+        for skipped in ["/p/utils/format.ts", "/p/src/utils/deep/format.ts", "/p/lib/helpers/format.ts"] {
+            assert!(graph.file_components.get(skipped).is_none(), "{}", skipped);
+        }
+        assert_eq!(component(&graph, "/p/utils/Badge.tsx"), "Badge");
+        for kept in ["/p/src/format.ts", "/p/utilsx/format.ts", "/p/src/lib/helpers/format.ts"] {
+            assert_eq!(component(&graph, kept), "FormatDate", "{}", kept);
+        }
+    }
+
+    #[test]
+    fn test_updates_mark_consumers_changed() {
+        let graph = graph(&[
+            ("/p/Button.tsx", "export function Button() { return <button />; }\n"),
+            ("/p/App.tsx", "import { Button } from './Button';\nexport function App() { return <Button />; }\n"),
+            ("/p/pages/Home.tsx", "import { Button } from '../Button';\nexport function Home() { return <Button />; }\n"),
+            ("/p/Other.tsx", "import { useState } from 'react';\nexport function Other() { return <div />; }\n"),
+        ]);
+        // external imports never lead to a file
+        assert_eq!(graph.consumers("/p/Button.tsx"), vec!["/p/App.tsx", "/p/pages/Home.tsx"]);
+        assert!(graph.consumers("/p/App.tsx").is_empty());
+
+        graph.dirty.clear();
+        let edited = "export function Button() { return <button type=\"button\" />; }\n";
+        graph.update_file("/p/Button.tsx", edited);
+        let mut dirty: Vec<String> = graph.dirty.iter().map(|f| f.key().clone()).collect();
+        dirty.sort();
+        assert_eq!(dirty, vec!["/p/App.tsx", "/p/Button.tsx", "/p/pages/Home.tsx"]);
+
+        // same content: nothing to re-emit
+        graph.dirty.clear();
+        graph.update_file("/p/Button.tsx", edited);
+        assert!(graph.dirty.is_empty());
+
+        graph.remove_file("/p/Button.tsx");
+        assert!(graph.removed.contains("/p/Button.tsx"));
+        let mut dirty: Vec<String> = graph.dirty.iter().map(|f| f.key().clone()).collect();
+        dirty.sort();
+        assert_eq!(dirty, vec!["/p/App.tsx", "/p/pages/Home.tsx"]);
+    }
+
+    #[test]
+    fn test_inconsistent_import_aliases() {
+        let graph = rooted(IndexOptions::default(), &[
+            ("/p/Button.tsx", "export default function Button() { return <button />; }\n"),
+            ("/p/a.tsx", "import Button from './Button';\nimport { useState } from 'react';\nexport function A() { return <Button />; }\n"),
+            ("/p/pages/b.tsx", "import Btn from '../Button';\nimport { useState } from 'react';\nexport function B() { return <Btn />; }\n"),
+            ("/p/c.tsx", "import { useState as useLocal } from 'react';\nimport * as R from 'react';\nexport function C() { return <div />; }\n"),
+            ("/p/d.tsx", "import * as Everything from 'react';\nimport Missing from './Missing';\nimport Gone from './Missing';\nexport function D() { return <div />; }\n"),
+        ]);
+        // different relative paths to one file are one source; namespaces and unresolved paths are skipped
+        let found: Vec<(String, String, BTreeMap<String, Vec<String>>)> =
+            graph.inconsistent_import_aliases().into_iter().map(|a| (a.source, a.export, a.aliases)).collect();
+        let aliases = |pairs: &[(&str, &[&str])]| -> BTreeMap<String, Vec<String>> {
+            pairs.iter().map(|(name, files)| (name.to_string(), files.iter().map(|f| f.to_string()).collect())).collect()
+        };
+        assert_eq!(
+            found,
+            vec![
+                ("Button.tsx".to_string(), "default".to_string(), aliases(&[("Btn", &["pages/b.tsx"]), ("Button", &["a.tsx"])])),
+                ("react".to_string(), "useState".to_string(), aliases(&[("useLocal", &["c.tsx"]), ("useState", &["a.tsx", "pages/b.tsx"])])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_graph_cycles() {
+        let graph = rooted(IndexOptions::default(), &[
+            ("/p/a.tsx", "import { b } from './b';\nexport const a = 1;\n"),
+            ("/p/b.tsx", "export { a } from './a';\nexport const b = 2;\n"),
+            ("/p/c.tsx", "import { a } from './a';\nimport React from 'react';\nimport { x } from './missing';\nexport const c = a;\n"),
+        ]);
+        // the re-export closes the cycle; external and unresolved imports leave no edge
+        let imports = graph.import_graph();
+        let json = serde_json::to_value(&imports).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "edges": { "a.tsx": ["b.tsx"], "b.tsx": ["a.tsx"], "c.tsx": ["a.tsx"] },
+                "cycles": [["a.tsx", "b.tsx"]],
+            })
+        );
+
+        assert_eq!(
+            imports.to_dot(true),
+            "digraph imports {
+  node [shape=box];
+  \"a.tsx\" [color=red];
+  \"b.tsx\" [color=red];
+  \"c.tsx\";
+  \"a.tsx\" -> \"b.tsx\" [color=red];
+  \"b.tsx\" -> \"a.tsx\" [color=red];
+  \"c.tsx\" -> \"a.tsx\";
+}
+"
+        );
+        assert!(!imports.to_dot(false).contains("red"));
+    }
+
+    #[test]
+    fn test_delta_holds_only_updated_and_removed_files() {
+        let eager = "import { useState } from 'react';\nexport function A() {\n  const [v] = useState(compute());\n  return <div>{v}</div>;\n}\n";
+        let graph = rooted(IndexOptions::default(), &[("/p/src/a.tsx", eager), ("/p/src/b.tsx", eager), ("/p/src/c.tsx", eager)]);
+        graph.to_json();
+
+        graph.update_file("/p/src/b.tsx", &eager.replace("function A", "function B"));
+        graph.remove_file("/p/src/c.tsx");
+        let delta: serde_json::Value = serde_json::from_str(&graph.to_json_delta()).unwrap();
+        assert_eq!(delta["changed"], serde_json::json!(["src/b.tsx"]));
+        assert_eq!(delta["removed"], serde_json::json!(["src/c.tsx"]));
+        assert_eq!(delta["components"]["src/b.tsx"]["name"], "B");
+        for key in ["components", "imports", "findings"] {
+            let files: Vec<&String> = delta[key].as_object().unwrap().keys().collect();
+            assert_eq!(files, ["src/b.tsx"], "{}", key);
+        }
+
+        // serializing resets the tracking
+        let again: serde_json::Value = serde_json::from_str(&graph.to_json_delta()).unwrap();
+        assert_eq!((again["changed"].as_array().unwrap().len(), again["removed"].as_array().unwrap().len()), (0, 0));
+    }
+
+    #[test]
+    fn test_stats_counts_rules_and_ranks_files() {
+        let file = |name: &str, eager: usize| {
+            let body = "  const [v] = useState(compute());\n".repeat(eager);
+            format!("import {{ useState }} from 'react';\nexport function {}() {{\n{}  return <div style={{{{ margin: 0 }}}} />;\n}}\n", name, body)
+        };
+        let (a, b, c) = (file("A", 2), file("B", 2), file("C", 3));
+        let graph = rooted(IndexOptions::default(), &[("/p/b.tsx", &b), ("/p/a.tsx", &a), ("/p/c.tsx", &c)]);
+        let stats = graph.stats(2);
+        assert_eq!(stats.total_findings, 10);
+        assert_eq!(stats.by_rule, BTreeMap::from([("EagerStateInit".to_string(), 7), ("StaticInlineStyle".to_string(), 3)]));
+        // most findings first, ties by path, cut at `top_n`
+        let top: Vec<(&str, usize)> = stats.top_files.iter().map(|f| (f.file.as_str(), f.findings)).collect();
+        assert_eq!(top, [("c.tsx", 4), ("a.tsx", 3)]);
+        assert_eq!((stats.components, stats.memoized_components), (3, 0));
+
+        let utils = rooted(IndexOptions::default(), &[("/p/format.ts", "export const format = (d) => d.toISOString();\n")]);
+        let stats = utils.stats(2);
+        assert_eq!((stats.total_findings, stats.components, stats.memoized_ratio), (0, 0, 0.0));
+        assert!(stats.top_files.iter().all(|f| f.findings == 0));
+    }
+
+    #[test]
+    fn test_unresolved_imports_report_typos_and_missing_aliases() {
+        let aliases = PathAliases { base_dir: "/p".into(), paths: HashMap::from([("@/*".to_string(), vec!["src/*".to_string()])]) };
+        let graph = rooted(IndexOptions { path_aliases: aliases, ..Default::default() }, &[
+            ("/p/src/Button.tsx", "export function Button() { return <button />; }\n"),
+            (
+                "/p/src/App.tsx",
+                "import React from 'react';\nimport { Button } from './Buton';\nimport { Card } from '@/Card';\nimport { Button as B } from '@/Button';\nimport { Button as C } from './Button';\n",
+            ),
+        ]);
+        let unresolved: Vec<(String, ImportSourceKind, String, usize)> =
+            graph.unresolved_imports().into_iter().map(|u| (u.source, u.kind, u.from_file, u.line)).collect();
+        assert_eq!(
+            unresolved,
+            vec![
+                ("./Buton".to_string(), ImportSourceKind::Relative, "src/App.tsx".to_string(), 2),
+                ("@/Card".to_string(), ImportSourceKind::Alias, "src/App.tsx".to_string(), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_max_findings_keeps_the_first_in_path_and_source_order() {
+        let eager = "import { useState } from 'react';\nexport function A() {\n  const [a] = useState(compute());\n  const [b] = useState(compute());\n  return <div>{a}{b}</div>;\n}\n";
+        let graph = rooted(IndexOptions::default(), &[("/p/b.tsx", eager), ("/p/a.tsx", eager), ("/p/c.tsx", eager)]);
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json_with_limit(Some(3))).unwrap();
+        let kept: Vec<(String, u64)> = json["findings"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .flat_map(|(file, findings)| findings.as_array().unwrap().iter().map(move |f| (file.clone(), f["line"].as_u64().unwrap())))
+            .collect();
+        assert_eq!(kept, [("a.tsx".to_string(), 3), ("a.tsx".to_string(), 4), ("b.tsx".to_string(), 3)]);
+        assert_eq!((&json["truncated"], &json["total_estimated"]), (&serde_json::json!(true), &serde_json::json!(6)));
+
+        // under the cap nothing is cut and the snapshot carries no truncation fields
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json_with_limit(Some(6))).unwrap();
+        assert_eq!(json["findings"].as_object().unwrap().values().map(|f| f.as_array().unwrap().len()).sum::<usize>(), 6);
+        assert!(json.get("truncated").is_none() && json.get("total_estimated").is_none());
+    }
+
+    #[test]
+    fn test_file_metrics_per_file() {
+        let graph = rooted(IndexOptions::default(), &[
+            (
+                "/p/src/List.tsx",
+                "import { memo, useState } from 'react';\nfunction Row() { return <li />; }\nexport const MemoRow = memo(Row);\n\nexport function List() {\n  const [v] = useState(compute());\n  return <ul style={{ margin: 0 }}>{v}</ul>;\n}\n",
+            ),
+            ("/p/src/format.ts", "// dates\nexport const format = (d) => d.toISOString();\n"),
+        ]);
+        let metrics = graph.file_metrics();
+        assert_eq!(metrics.keys().collect::<Vec<_>>(), ["src/List.tsx", "src/format.ts"]);
+
+        let list = &metrics["src/List.tsx"];
+        assert_eq!((list.loc, list.component_count, list.memoized_count), (8, 2, 1));
+        assert_eq!(list.finding_count_by_rule, BTreeMap::from([("EagerStateInit".to_string(), 1), ("StaticInlineStyle".to_string(), 1)]));
+
+        // files without components still count their lines
+        let format = &metrics["src/format.ts"];
+        assert_eq!((format.loc, format.component_count, format.memoized_count), (2, 0, 0));
+        assert!(format.finding_count_by_rule.is_empty());
+    }
+}
@@ -0,0 +1,23 @@
+use std::io;
+
+/// Where indexing lists and reads source files from. Editors supply one that
+/// serves unsaved buffers; tests supply an in-memory tree.
+pub trait FileProvider: Send + Sync {
+    fn read(&self, path: &str) -> io::Result<String>;
+    /// Source files under `root`, in any order
+    fn list(&self, root: &str) -> Vec<String>;
+}
+
+/// The real filesystem, walked the way `find_all_source_files` does
+#[cfg(not(target_arch = "wasm32"))]
+pub struct RealFs;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileProvider for RealFs {
+    fn read(&self, path: &str) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+    fn list(&self, root: &str) -> Vec<String> {
+        super::metadata::find_all_source_files(root)
+    }
+}
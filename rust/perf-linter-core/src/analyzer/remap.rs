@@ -0,0 +1,72 @@
+use super::findings::{Finding, OriginalLocation};
+use sourcemap::SourceMap;
+use std::path::Path;
+
+/// Load the `<file>.map` sitting next to an analyzed file, if there is a valid one
+pub fn load_adjacent_sourcemap(file_path: &str) -> Option<SourceMap> {
+    let raw = std::fs::read(format!("{}.map", file_path)).ok()?;
+    SourceMap::from_slice(&raw).ok()
+}
+
+/// 0-based UTF-16 column, as sourcemaps count them, of the 1-based byte `column` on
+/// 1-based `line` of `source`; the byte column itself when `source` has no such line
+fn utf16_column(source: &str, line: usize, column: usize) -> u32 {
+    let byte = column.saturating_sub(1);
+    let Some(text) = source.split('\n').nth(line.saturating_sub(1)) else { return byte as u32 };
+    let mut end = byte.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text[..end].encode_utf16().count() as u32
+}
+
+/// Attach original positions to findings of `source` (the analyzed file's text, whose byte
+/// columns are converted for the lookup). Positions the map doesn't cover are left
+/// untouched rather than snapped to the nearest mapping on another line.
+pub fn remap_findings(file_path: &str, source: &str, map: &SourceMap, findings: &mut [Finding]) {
+    let map_dir = Path::new(file_path).parent().unwrap_or_else(|| Path::new(""));
+    for f in findings.iter_mut() {
+        let (line, column) = (f.line.saturating_sub(1) as u32, utf16_column(source, f.line, f.column));
+        let Some(token) = map.lookup_token(line, column) else { continue };
+        if token.get_dst_line() != line {
+            continue;
+        }
+        let Some(source) = token.get_source() else { continue };
+        // plain relative sources are relative to the map; URLs like webpack:// stay as-is
+        let file = if source.contains("://") || Path::new(source).is_absolute() {
+            source.to_string()
+        } else {
+            map_dir.join(source).to_string_lossy().to_string()
+        };
+        f.original = Some(OriginalLocation {
+            file,
+            line: token.get_src_line() as usize + 1,
+            column: token.get_src_col() as usize + 1,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::findings::FindingKind;
+    use sourcemap::SourceMapBuilder;
+
+    #[test]
+    fn test_columns_after_non_ascii_text() {
+        // `é` is 2 bytes but 1 UTF-16 unit, `😀` 4 bytes but 2 units
+        let source = "const s = \"é😀\"; f();\n";
+        let byte_column = source.find("f()").unwrap() + 1;
+        assert_eq!(utf16_column(source, 1, byte_column), 17);
+
+        let mut builder = SourceMapBuilder::new(None);
+        builder.add(0, 0, 0, 0, Some("src/a.ts"), None, false);
+        builder.add(0, 17, 4, 2, Some("src/a.ts"), None, false);
+        builder.add(0, 19, 9, 0, Some("src/a.ts"), None, false);
+        let map = builder.into_sourcemap();
+        let mut findings = vec![Finding { kind: FindingKind::ConditionalRemount, line: 1, column: byte_column, end_line: None, original: None }];
+        remap_findings("/out/a.js", source, &map, &mut findings);
+        let original = findings[0].original.as_ref().expect("mapped");
+        assert_eq!((original.line, original.column), (5, 3));
+    }
+}
@@ -0,0 +1,4241 @@
+use std::collections::{BTreeMap, HashMap};
+use swc_common::{Span, Spanned};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+use super::extract::{classify_import_source, is_component_element, prop_count, is_component_name, is_identifier_react_forward_ref, is_identifier_react_memo, jsx_element_name, PathAliases};
+use super::findings::{Finding, FindingKind, Severity};
+use serde::Serialize;
+use crate::parser::LineIndex;
+use crate::redos::analyze_pattern;
+
+/// A parsed file plus what extraction learned about it, shared by every rule
+pub struct FileContext<'a> {
+    pub module: &'a Module,
+    pub lines: &'a LineIndex,
+    /// Spans of component bodies (blocks or arrow expressions): the render path
+    pub render_bodies: &'a [Span],
+    /// Local symbols exported by name, and the one exported as default
+    pub named_export_locals: &'a [(String, Span)],
+    pub default_export_local: Option<&'a (String, Span)>,
+}
+
+/// One independent detection over a file; rules are added or switched off
+/// without touching metadata extraction
+pub trait Rule: Send + Sync {
+    /// Stable identifier used to disable the rule
+    fn name(&self) -> &'static str;
+    /// One line on what the rule reports, for `RuleRegistry::catalog`
+    fn description(&self) -> &'static str {
+        ""
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Performance
+    }
+    /// Strongest severity the rule's findings get when the config doesn't override it;
+    /// `FindingKind::default_severity` may put individual findings below it
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding>;
+}
+
+/// What kind of problem a rule looks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleCategory {
+    /// Wasted renders, allocations or work on the render path
+    Performance,
+    /// Code that doesn't do what it looks like it does
+    Correctness,
+    /// Bundle size and code splitting
+    Bundle,
+    Security,
+    /// Readability and API shape
+    Maintainability,
+}
+
+/// A registered rule as listed by the `rules` subcommand and `list_rules`
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleInfo {
+    /// `Rule::name`, as accepted by `disabledRules`
+    pub id: &'static str,
+    /// `id` as words, e.g. `Missing display name`
+    pub name: String,
+    pub default_severity: Severity,
+    pub description: &'static str,
+    pub category: RuleCategory,
+    /// False for the opt-in rules, which need a flag or config setting to run
+    pub enabled_by_default: bool,
+}
+
+/// Ordered set of rules `extract_all` runs on every file
+pub struct RuleRegistry {
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl Default for RuleRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl RuleRegistry {
+    pub fn empty() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Rules on by default
+    pub fn builtin() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Box::new(UnstableChildrenRule));
+        registry.register(Box::new(HookAfterEarlyReturnRule));
+        registry.register(Box::new(ExpensiveAllocationRule));
+        registry.register(Box::new(QuadraticLookupRule));
+        registry.register(Box::new(AmbiguousExportRule));
+        registry.register(Box::new(ConditionalRemountRule));
+        registry.register(Box::new(ChildrenManipulationRule));
+        registry.register(Box::new(AsyncEffectCallbackRule));
+        registry.register(Box::new(EagerStateInitRule));
+        registry.register(Box::new(CodeSplitPointRule));
+        registry.register(Box::new(SetStateInLoopRule));
+        registry.register(Box::new(StaticInlineStyleRule));
+        registry.register(Box::new(RestSpreadForwardingRule));
+        registry.register(Box::new(RefPropWithoutForwardRefRule));
+        registry.register(Box::new(ComparatorMissingPropRule));
+        registry.register(Box::new(MisusedDomPropRule));
+        registry.register(Box::new(FragmentInMapRule));
+        registry.register(Box::new(JsonInRenderRule));
+        registry.register(Box::new(DeepJsxTernaryRule::default()));
+        registry.register(Box::new(NonFunctionHandlerRule));
+        registry.register(Box::new(InconsistentPropTypeRule));
+        registry.register(Box::new(UnusedMemoRule));
+        registry.register(Box::new(LiteralInDepsRule));
+        registry.register(Box::new(HeavyDependencyRule::default()));
+        registry.register(Box::new(DirectMutationRule));
+        registry.register(Box::new(PotentialRenderLoopRule));
+        registry.register(Box::new(StyleClassOverlapRule));
+        registry.register(Box::new(CollectionCloneInRenderRule));
+        registry.register(Box::new(UnusedPropRule));
+        registry.register(Box::new(UnstableHookArgRule));
+        registry.register(Box::new(JsxIifeRule));
+        registry.register(Box::new(RefInDepsRule));
+        registry.register(Box::new(WidePropsRule::default()));
+        registry.register(Box::new(MissingDisplayNameRule));
+        registry.register(Box::new(SpreadClobberRule));
+        registry.register(Box::new(DynamicClassNameComputationRule));
+        registry.register(Box::new(ShadowedBindingRule));
+        registry
+    }
+
+    /// `builtin` plus the opt-in rules (`LargeUnionProp`, `UnsafeRegex`) at their default
+    /// settings: every rule name `disabledRules` accepts
+    pub fn all() -> Self {
+        let mut registry = Self::builtin();
+        registry.register(Box::new(LargeUnionPropRule::default()));
+        registry.register(Box::new(UnsafeRegexRule));
+        registry
+    }
+
+    /// Built-in rules minus the ones named in `disabled`
+    pub fn without(disabled: &[String]) -> Self {
+        let mut registry = Self::builtin();
+        registry.rules.retain(|r| !disabled.iter().any(|d| d == r.name()));
+        registry
+    }
+
+    pub fn register(&mut self, rule: Box<dyn Rule>) {
+        self.rules.push(rule);
+    }
+
+    /// Swap the rule with the same name for `rule` (e.g. with other settings);
+    /// nothing happens when that rule isn't registered
+    pub fn replace(&mut self, rule: Box<dyn Rule>) {
+        if let Some(slot) = self.rules.iter_mut().find(|r| r.name() == rule.name()) {
+            *slot = rule;
+        }
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.rules.iter().map(|r| r.name())
+    }
+
+    /// Metadata of every registered rule, in registration order; `all().catalog()` lists
+    /// the opt-in rules too
+    pub fn catalog(&self) -> Vec<RuleInfo> {
+        let defaults: Vec<&str> = Self::builtin().names().collect();
+        self.rules
+            .iter()
+            .map(|r| RuleInfo {
+                id: r.name(),
+                name: title_words(r.name()),
+                default_severity: r.default_severity(),
+                description: r.description(),
+                category: r.category(),
+                enabled_by_default: defaults.contains(&r.name()),
+            })
+            .collect()
+    }
+
+    /// Findings of every registered rule, in registration order
+    pub fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        self.rules.iter().flat_map(|r| r.check(ctx)).collect()
+    }
+}
+
+/// `MissingDisplayName` -> `Missing display name`
+fn title_words(name: &str) -> String {
+    let mut words = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if i > 0 && c.is_ascii_uppercase() {
+            words.push(' ');
+            words.push(c.to_ascii_lowercase());
+        } else {
+            words.push(c);
+        }
+    }
+    words
+}
+
+fn finding_at(lines: &LineIndex, span: Span, kind: FindingKind) -> Finding {
+    let (line, column) = lines.line_col(span.lo);
+    let end_line = Some(lines.line(span.hi)).filter(|&end| end > line);
+    Finding { kind, line, column, end_line, original: None }
+}
+
+/// A component body the extractor recorded
+enum RenderBody<'a> {
+    Block(&'a BlockStmt),
+    Expr(&'a Expr),
+}
+
+/// Finds the nodes behind `FileContext::render_bodies` again
+struct RenderBodies<'s, F> {
+    spans: &'s [Span],
+    on_body: F,
+}
+
+impl<F: FnMut(RenderBody<'_>)> Visit for RenderBodies<'_, F> {
+    fn visit_block_stmt(&mut self, b: &BlockStmt) {
+        if self.spans.contains(&b.span) {
+            (self.on_body)(RenderBody::Block(b));
+        }
+        b.visit_children_with(self);
+    }
+    fn visit_expr(&mut self, e: &Expr) {
+        if self.spans.contains(&e.span()) {
+            (self.on_body)(RenderBody::Expr(e));
+        }
+        e.visit_children_with(self);
+    }
+}
+
+fn for_each_render_body(ctx: &FileContext, on_body: impl FnMut(RenderBody<'_>)) {
+    if ctx.render_bodies.is_empty() {
+        return;
+    }
+    let mut bodies = RenderBodies { spans: ctx.render_bodies, on_body };
+    ctx.module.visit_with(&mut bodies);
+}
+
+/// Values that get a new identity on every render (elements, arrays, render functions)
+fn is_fresh_children_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::JSXElement(_) | Expr::JSXFragment(_) | Expr::Array(_) | Expr::Arrow(_) | Expr::Fn(_) => true,
+        Expr::Paren(p) => is_fresh_children_expr(&p.expr),
+        _ => false,
+    }
+}
+
+/// `children={...}` or nested children that are freshly created; static text is fine
+fn has_unstable_children(el: &JSXElement) -> bool {
+    let via_attr = el.opening.attrs.iter().any(|a| match a {
+        JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(n), value: Some(v), .. }) if n.sym.as_ref() == "children" => match v {
+            JSXAttrValue::JSXElement(_) | JSXAttrValue::JSXFragment(_) => true,
+            JSXAttrValue::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(e), .. }) => is_fresh_children_expr(e),
+            _ => false,
+        },
+        _ => false,
+    });
+    via_attr || el.children.iter().any(|c| match c {
+        JSXElementChild::JSXElement(_) | JSXElementChild::JSXFragment(_) => true,
+        JSXElementChild::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(e), .. }) => is_fresh_children_expr(e),
+        _ => false,
+    })
+}
+
+/// `FindingKind::UnstableChildren`
+pub struct UnstableChildrenRule;
+
+struct UnstableChildrenScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl Visit for UnstableChildrenScan<'_> {
+    fn visit_jsx_element(&mut self, el: &JSXElement) {
+        // Só componentes podem ser memoizados: nomes maiúsculos ou membros (`Menu.Item`); tags DOM e XML (`svg:rect`) não importam aqui
+        if let Some(name) = jsx_element_name(&el.opening.name) {
+            if is_component_element(&name) && has_unstable_children(el) {
+                self.findings.push(finding_at(self.lines, el.span, FindingKind::UnstableChildren { component: name }));
+            }
+        }
+        el.visit_children_with(self);
+    }
+}
+
+impl Rule for UnstableChildrenRule {
+    fn name(&self) -> &'static str {
+        "UnstableChildren"
+    }
+    fn description(&self) -> &'static str {
+        "`children` built fresh on every render (inline JSX, arrays, render functions), which defeats memoized children"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = UnstableChildrenScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+fn has_key_attr(el: &JSXElement) -> bool {
+    el.opening.attrs.iter().any(|a| matches!(a, JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(n), .. }) if n.sym.as_ref() == "key"))
+}
+
+/// Element a conditional branch renders: `<A/>`, `(<A/>)` or `cond && <A/>`
+fn branch_element(expr: &Expr) -> Option<&JSXElement> {
+    match expr {
+        Expr::JSXElement(el) => Some(el),
+        Expr::Paren(p) => branch_element(&p.expr),
+        Expr::Bin(BinExpr { op: BinaryOp::LogicalAnd, right, .. }) => branch_element(right),
+        _ => None,
+    }
+}
+
+/// Two unkeyed branches rendering different element types in the same slot;
+/// anything we can't name (fragments, calls, variables) is left alone
+fn conditional_remount(left: &Expr, right: &Expr) -> bool {
+    let (Some(a), Some(b)) = (branch_element(left), branch_element(right)) else { return false };
+    if has_key_attr(a) || has_key_attr(b) {
+        return false;
+    }
+    match (jsx_element_name(&a.opening.name), jsx_element_name(&b.opening.name)) {
+        (Some(x), Some(y)) => x != y,
+        _ => false,
+    }
+}
+
+/// `FindingKind::ConditionalRemount`
+pub struct ConditionalRemountRule;
+
+struct ConditionalRemountScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl Visit for ConditionalRemountScan<'_> {
+    fn visit_jsx_expr_container(&mut self, c: &JSXExprContainer) {
+        // `{cond ? <A/> : <B/>}` e `{cond && <A/> || <B/>}`
+        if let JSXExpr::Expr(e) = &c.expr {
+            let remount = match &**e {
+                Expr::Cond(cond) => conditional_remount(&cond.cons, &cond.alt),
+                Expr::Bin(BinExpr { op: BinaryOp::LogicalOr, left, right, .. }) => conditional_remount(left, right),
+                _ => false,
+            };
+            if remount {
+                self.findings.push(finding_at(self.lines, c.span, FindingKind::ConditionalRemount));
+            }
+        }
+        c.visit_children_with(self);
+    }
+}
+
+impl Rule for ConditionalRemountRule {
+    fn name(&self) -> &'static str {
+        "ConditionalRemount"
+    }
+    fn description(&self) -> &'static str {
+        "Ternary swapping element types without keys: the subtree remounts and loses its state"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = ConditionalRemountScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// `useX(...)` or `React.useX(...)`: any identifier matching `use[A-Z]`
+pub(crate) fn hook_name(callee: &Expr) -> Option<String> {
+    let name = match callee {
+        Expr::Ident(i) => i.sym.to_string(),
+        Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(p), .. }) if matches!(&**obj, Expr::Ident(o) if o.sym.as_ref() == "React") => p.sym.to_string(),
+        _ => return None,
+    };
+    let is_hook = name.strip_prefix("use").and_then(|rest| rest.chars().next()).map(|c| c.is_uppercase()).unwrap_or(false);
+    if is_hook { Some(name) } else { None }
+}
+
+/// Hooks whose callback may only return a cleanup function
+const EFFECT_HOOKS: &[&str] = &["useEffect", "useLayoutEffect", "useInsertionEffect"];
+
+/// `async () => ...` or `async function () {}`, possibly parenthesized
+fn is_async_fn(expr: &Expr) -> bool {
+    match expr {
+        Expr::Arrow(a) => a.is_async,
+        Expr::Fn(f) => f.function.is_async,
+        Expr::Paren(p) => is_async_fn(&p.expr),
+        _ => false,
+    }
+}
+
+/// Hook calls made directly in a statement (nested functions are not this render's hooks)
+#[derive(Default)]
+struct HookCalls {
+    calls: Vec<(String, Span)>,
+}
+
+impl Visit for HookCalls {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(e) = &call.callee {
+            if let Some(name) = hook_name(e) { self.calls.push((name, call.span)); }
+        }
+        call.visit_children_with(self);
+    }
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+}
+
+/// Whether a statement can return from the enclosing component
+#[derive(Default)]
+struct ContainsReturn(bool);
+
+impl Visit for ContainsReturn {
+    fn visit_return_stmt(&mut self, _: &ReturnStmt) { self.0 = true; }
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+}
+
+/// Hooks reached only when an earlier conditional return did not fire
+fn hooks_after_early_return(body: &BlockStmt, lines: &LineIndex) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut returned_early = false;
+    for stmt in &body.stmts {
+        if let Stmt::Return(_) = stmt { break; }
+        if returned_early {
+            let mut hooks = HookCalls::default();
+            stmt.visit_with(&mut hooks);
+            for (hook, span) in hooks.calls {
+                findings.push(finding_at(lines, span, FindingKind::HookAfterEarlyReturn { hook }));
+            }
+        }
+        let mut ret = ContainsReturn::default();
+        stmt.visit_with(&mut ret);
+        returned_early |= ret.0;
+    }
+    findings
+}
+
+/// `FindingKind::HookAfterEarlyReturn`, for block-bodied components
+pub struct HookAfterEarlyReturnRule;
+
+impl Rule for HookAfterEarlyReturnRule {
+    fn name(&self) -> &'static str {
+        "HookAfterEarlyReturn"
+    }
+    fn description(&self) -> &'static str {
+        "Hook called after a conditional `return`, so it doesn't run on every render"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for_each_render_body(ctx, |body| {
+            if let RenderBody::Block(b) = body {
+                findings.extend(hooks_after_early_return(b, ctx.lines));
+            }
+        });
+        findings
+    }
+}
+
+/// `FindingKind::AsyncEffectCallback`
+pub struct AsyncEffectCallbackRule;
+
+struct AsyncEffectScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl Visit for AsyncEffectScan<'_> {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            if let Some(hook) = hook_name(callee).filter(|h| EFFECT_HOOKS.contains(&h.as_str())) {
+                if call.args.first().map(|a| is_async_fn(&a.expr)).unwrap_or(false) {
+                    self.findings.push(finding_at(self.lines, call.span, FindingKind::AsyncEffectCallback { hook }));
+                }
+            }
+        }
+        call.visit_children_with(self);
+    }
+}
+
+impl Rule for AsyncEffectCallbackRule {
+    fn name(&self) -> &'static str {
+        "AsyncEffectCallback"
+    }
+    fn description(&self) -> &'static str {
+        "Async effect callback: the effect returns a Promise instead of a cleanup function"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = AsyncEffectScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// Hooks taking a dependency array, with its argument position
+const DEPS_HOOKS: &[(&str, usize)] = &[
+    ("useEffect", 1),
+    ("useLayoutEffect", 1),
+    ("useInsertionEffect", 1),
+    ("useMemo", 1),
+    ("useCallback", 1),
+    ("useImperativeHandle", 2),
+];
+
+/// Object, array and function literals: a new identity on every render
+fn is_fresh_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Object(_) | Expr::Array(_) | Expr::Arrow(_) | Expr::Fn(_) => true,
+        Expr::Paren(p) => is_fresh_literal(&p.expr),
+        _ => false,
+    }
+}
+
+struct LiteralDepsScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl Visit for LiteralDepsScan<'_> {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            if let Some((hook, index)) = hook_name(callee).and_then(|h| DEPS_HOOKS.iter().find(|(name, _)| *name == h)) {
+                if let Some(Expr::Array(deps)) = call.args.get(*index).map(|a| &*a.expr) {
+                    for dep in deps.elems.iter().flatten().filter(|d| d.spread.is_none() && is_fresh_literal(&d.expr)) {
+                        self.findings.push(finding_at(self.lines, dep.expr.span(), FindingKind::LiteralInDeps { hook: hook.to_string() }));
+                    }
+                }
+            }
+        }
+        call.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::LiteralInDeps`
+pub struct LiteralInDepsRule;
+
+impl Rule for LiteralInDepsRule {
+    fn name(&self) -> &'static str {
+        "LiteralInDeps"
+    }
+    fn description(&self) -> &'static str {
+        "Object, array or function literal in a deps array: it changes every render"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = LiteralDepsScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// React's own hooks: their arguments are callbacks, initial values or deps, and only deps
+/// compare identities (`LiteralInDeps`)
+const REACT_HOOKS: &[&str] = &[
+    "useState", "useReducer", "useRef", "useContext", "useEffect", "useLayoutEffect", "useInsertionEffect", "useMemo",
+    "useCallback", "useImperativeHandle", "useDebugValue", "useDeferredValue", "useTransition", "useId",
+    "useSyncExternalStore", "useOptimistic", "useActionState", "useFormStatus",
+];
+
+struct HookArgScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl Visit for HookArgScan<'_> {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            if let Some(hook) = hook_name(callee).filter(|h| !REACT_HOOKS.contains(&h.as_str())) {
+                for arg in call.args.iter().filter(|a| a.spread.is_none() && is_fresh_literal(&a.expr)) {
+                    self.findings.push(finding_at(self.lines, arg.expr.span(), FindingKind::UnstableHookArg { hook: hook.clone() }));
+                }
+            }
+        }
+        call.visit_children_with(self);
+    }
+    fn visit_fn_decl(&mut self, f: &FnDecl) {
+        // `function useThing(config = {})`: a fresh default whenever the caller omits it
+        if hook_name(&Expr::Ident(f.ident.clone())).is_some() {
+            for param in &f.function.params {
+                if let Pat::Assign(AssignPat { right, .. }) = &param.pat {
+                    if is_fresh_literal(right) {
+                        self.findings.push(finding_at(self.lines, right.span(), FindingKind::UnstableHookArg { hook: f.ident.sym.to_string() }));
+                    }
+                }
+            }
+        }
+        f.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::UnstableHookArg`: object, array and function literals passed to a custom
+/// hook, or defaulted in a `function use*(..)` signature
+pub struct UnstableHookArgRule;
+
+impl Rule for UnstableHookArgRule {
+    fn name(&self) -> &'static str {
+        "UnstableHookArg"
+    }
+    fn description(&self) -> &'static str {
+        "Literal argument to a custom hook: new on every call, so anything keyed on it re-runs"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = HookArgScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// `f()`, possibly parenthesized; a function passed instead runs only on mount
+fn is_call(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call(_) => true,
+        Expr::Paren(p) => is_call(&p.expr),
+        _ => false,
+    }
+}
+
+/// `FindingKind::EagerStateInit`
+pub struct EagerStateInitRule;
+
+struct EagerStateInitScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl Visit for EagerStateInitScan<'_> {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            if hook_name(callee).as_deref() == Some("useState") {
+                let eager = call.args.first().map(|a| is_call(&a.expr)).unwrap_or(false);
+                if eager {
+                    self.findings.push(finding_at(self.lines, call.span, FindingKind::EagerStateInit));
+                }
+            }
+        }
+        call.visit_children_with(self);
+    }
+}
+
+impl Rule for EagerStateInitRule {
+    fn name(&self) -> &'static str {
+        "EagerStateInit"
+    }
+    fn description(&self) -> &'static str {
+        "`useState(compute())` runs `compute` every render; pass a function to run it once"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = EagerStateInitScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// `React.Children.map`, `Children.toArray`, `cloneElement` or `React.cloneElement`,
+/// reported as `Children.<method>` / `cloneElement`
+fn children_manipulation(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(id) if id.sym.as_ref() == "cloneElement" => Some("cloneElement".into()),
+        Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(p), .. }) => {
+            let method = p.sym.as_ref();
+            match &**obj {
+                Expr::Ident(o) if o.sym.as_ref() == "React" && method == "cloneElement" => Some("cloneElement".into()),
+                Expr::Ident(o) if o.sym.as_ref() == "Children" => Some(format!("Children.{}", method)),
+                Expr::Member(MemberExpr { obj: root, prop: MemberProp::Ident(mid), .. }) => match &**root {
+                    Expr::Ident(r) if r.sym.as_ref() == "React" && mid.sym.as_ref() == "Children" => Some(format!("Children.{}", method)),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// `FindingKind::ChildrenManipulation`
+pub struct ChildrenManipulationRule;
+
+struct ChildrenManipulationScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl Visit for ChildrenManipulationScan<'_> {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            if let Some(method) = children_manipulation(callee) {
+                self.findings.push(finding_at(self.lines, call.span, FindingKind::ChildrenManipulation { method }));
+            }
+        }
+        call.visit_children_with(self);
+    }
+}
+
+impl Rule for ChildrenManipulationRule {
+    fn name(&self) -> &'static str {
+        "ChildrenManipulation"
+    }
+    fn description(&self) -> &'static str {
+        "`React.Children.*` or `cloneElement`: new child elements every render"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = ChildrenManipulationScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// Constructors that are costly or identity-unstable when called on every render
+const EXPENSIVE_CTORS: &[&str] = &["RegExp", "Function", "Map", "Set", "WeakMap"];
+
+/// Array callbacks that run once per element, synchronously during render
+const ITERATION_METHODS: &[&str] = &["map", "forEach", "filter", "flatMap", "reduce", "some", "every"];
+
+/// Linear searches that turn an enclosing loop into O(n²)
+const LINEAR_LOOKUPS: &[&str] = &["includes", "indexOf", "find"];
+
+/// Name a collection is referred to by: `items` or the last segment of `props.items`
+fn collection_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(i) => Some(i.sym.to_string()),
+        Expr::Member(MemberExpr { prop: MemberProp::Ident(p), .. }) => Some(p.sym.to_string()),
+        Expr::Paren(p) => collection_name(&p.expr),
+        _ => None,
+    }
+}
+
+/// Without types, a plural or list-like name is the best hint that `x.includes`
+/// is an array search rather than a substring check
+fn is_plausible_collection(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    (lower.ends_with('s') && !lower.ends_with("ss") && !lower.ends_with("status"))
+        || ["list", "array", "arr", "ids", "items", "keys", "values"].iter().any(|hint| lower.ends_with(hint))
+}
+
+/// A loop on the render path and the lookups performed inside it
+struct LoopFrame {
+    span: Span,
+    collections: Vec<String>,
+    lookups: Vec<String>,
+}
+
+/// Walks a component's render path: the body itself, but not the nested
+/// functions (handlers, hook callbacks) that run outside of render
+struct RenderScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+    loops: Vec<LoopFrame>,
+}
+
+impl RenderScan<'_> {
+    fn enter_loop(&mut self, span: Span, collection: Option<String>) {
+        self.loops.push(LoopFrame { span, collections: collection.into_iter().collect(), lookups: Vec::new() });
+    }
+
+    fn exit_loop(&mut self) {
+        let Some(frame) = self.loops.pop() else { return };
+        if !frame.lookups.is_empty() {
+            self.findings.push(finding_at(self.lines, frame.span, FindingKind::QuadraticLookup { methods: frame.lookups }));
+        }
+    }
+
+    /// `.includes/.indexOf/.find` on something that looks like an array, inside a loop
+    fn record_lookup(&mut self, call: &CallExpr, receiver: &Expr, method: &str) {
+        if self.loops.is_empty() || !LINEAR_LOOKUPS.contains(&method) {
+            return;
+        }
+        let searches_string = matches!(call.args.first().map(|a| &*a.expr), Some(Expr::Lit(Lit::Str(_))) | Some(Expr::Tpl(_)));
+        let Some(name) = collection_name(receiver) else { return };
+        let iterated = self.loops.iter().any(|f| f.collections.contains(&name));
+        if searches_string && !iterated {
+            return;
+        }
+        if iterated || is_plausible_collection(&name) {
+            let frame = self.loops.last_mut().expect("checked above");
+            if !frame.lookups.iter().any(|m| m == method) {
+                frame.lookups.push(method.to_string());
+            }
+        }
+    }
+
+    /// Callback bodies of `.map` & co. are render code, unlike other nested functions
+    fn visit_iteration_callback(&mut self, arg: &Expr) {
+        match arg {
+            Expr::Arrow(a) => a.body.visit_with(self),
+            Expr::Fn(f) => f.function.body.visit_with(self),
+            other => other.visit_with(self),
+        }
+    }
+}
+
+impl Visit for RenderScan<'_> {
+    fn visit_new_expr(&mut self, n: &NewExpr) {
+        if let Expr::Ident(ctor) = &*n.callee {
+            if EXPENSIVE_CTORS.contains(&ctor.sym.as_ref()) {
+                self.findings.push(finding_at(self.lines, n.span, FindingKind::ExpensiveAllocation { ctor: ctor.sym.to_string() }));
+            }
+        }
+        n.visit_children_with(self);
+    }
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        let Callee::Expr(callee) = &call.callee else { return call.visit_children_with(self) };
+        let Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(method), .. }) = &**callee else {
+            return call.visit_children_with(self);
+        };
+        self.record_lookup(call, obj, method.sym.as_ref());
+        if !ITERATION_METHODS.contains(&method.sym.as_ref()) {
+            return call.visit_children_with(self);
+        }
+        obj.visit_with(self);
+        self.enter_loop(call.span, collection_name(obj));
+        for arg in &call.args {
+            self.visit_iteration_callback(&arg.expr);
+        }
+        self.exit_loop();
+    }
+    fn visit_for_stmt(&mut self, f: &ForStmt) {
+        self.enter_loop(f.span, None);
+        f.visit_children_with(self);
+        self.exit_loop();
+    }
+    fn visit_for_of_stmt(&mut self, f: &ForOfStmt) {
+        f.right.visit_with(self);
+        self.enter_loop(f.span, collection_name(&f.right));
+        f.body.visit_with(self);
+        self.exit_loop();
+    }
+    fn visit_for_in_stmt(&mut self, f: &ForInStmt) {
+        f.right.visit_with(self);
+        self.enter_loop(f.span, None);
+        f.body.visit_with(self);
+        self.exit_loop();
+    }
+    fn visit_while_stmt(&mut self, w: &WhileStmt) {
+        self.enter_loop(w.span, None);
+        w.visit_children_with(self);
+        self.exit_loop();
+    }
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+}
+
+fn scan_render<N>(node: &N, lines: &LineIndex) -> Vec<Finding>
+where
+    N: for<'a> VisitWith<RenderScan<'a>>,
+{
+    let mut scan = RenderScan { lines, findings: Vec::new(), loops: Vec::new() };
+    node.visit_with(&mut scan);
+    scan.findings
+}
+
+/// `scan_render` over every render body of the file
+fn render_cost(ctx: &FileContext) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for_each_render_body(ctx, |body| match body {
+        RenderBody::Block(b) => findings.extend(scan_render(b, ctx.lines)),
+        RenderBody::Expr(e) => findings.extend(scan_render(e, ctx.lines)),
+    });
+    findings
+}
+
+/// `FindingKind::ExpensiveAllocation`; shares `scan_render` with `QuadraticLookupRule`
+pub struct ExpensiveAllocationRule;
+
+impl Rule for ExpensiveAllocationRule {
+    fn name(&self) -> &'static str {
+        "ExpensiveAllocation"
+    }
+    fn description(&self) -> &'static str {
+        "`new RegExp/Function/Map/Set/WeakMap` on the render path; hoist it or wrap it in `useMemo`"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        render_cost(ctx).into_iter().filter(|f| matches!(f.kind, FindingKind::ExpensiveAllocation { .. })).collect()
+    }
+}
+
+/// `FindingKind::QuadraticLookup`
+pub struct QuadraticLookupRule;
+
+impl Rule for QuadraticLookupRule {
+    fn name(&self) -> &'static str {
+        "QuadraticLookup"
+    }
+    fn description(&self) -> &'static str {
+        "Linear `includes/indexOf/find` inside a render loop; index the collection in a `Set`/`Map`"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        render_cost(ctx).into_iter().filter(|f| matches!(f.kind, FindingKind::QuadraticLookup { .. })).collect()
+    }
+}
+
+/// `FindingKind::AmbiguousExport`: same local symbol exported both as default and by name
+pub struct AmbiguousExportRule;
+
+impl Rule for AmbiguousExportRule {
+    fn name(&self) -> &'static str {
+        "AmbiguousExport"
+    }
+    fn description(&self) -> &'static str {
+        "Symbol exported both as default and by name; bundlers may duplicate the module"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Bundle
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let Some((name, default_span)) = ctx.default_export_local else { return Vec::new() };
+        let mut spans: Vec<Span> = ctx.named_export_locals.iter().filter(|(n, _)| n == name).map(|(_, sp)| *sp).collect();
+        if spans.is_empty() {
+            return Vec::new();
+        }
+        spans.push(*default_span);
+        spans.sort_by_key(|sp| sp.lo);
+        let lines = spans.iter().map(|sp| ctx.lines.line(sp.lo)).collect();
+        vec![finding_at(ctx.lines, spans[0], FindingKind::AmbiguousExport { name: name.clone(), lines })]
+    }
+}
+
+/// Members of the interfaces and object type aliases declared at the top of the module
+fn local_type_members(module: &Module) -> HashMap<String, &[TsTypeElement]> {
+    module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::Stmt(Stmt::Decl(d)) => Some(d),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(e)) => Some(&e.decl),
+            _ => None,
+        })
+        .filter_map(|decl| match decl {
+            Decl::TsInterface(i) => Some((i.id.sym.to_string(), &i.body.body[..])),
+            Decl::TsTypeAlias(a) => match &*a.type_ann {
+                TsType::TsTypeLit(lit) => Some((a.id.sym.to_string(), &lit.members[..])),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Members of the type annotating a props parameter: inline or a local interface/alias
+fn props_type_members<'a>(props: &'a Pat, locals: &HashMap<String, &'a [TsTypeElement]>) -> Option<&'a [TsTypeElement]> {
+    let ann = match props {
+        Pat::Object(o) => o.type_ann.as_deref(),
+        Pat::Ident(i) => i.type_ann.as_deref(),
+        _ => None,
+    };
+    match ann.map(|a| &*a.type_ann) {
+        Some(TsType::TsTypeLit(lit)) => Some(&lit.members[..]),
+        Some(TsType::TsTypeRef(TsTypeRef { type_name: TsEntityName::Ident(id), .. })) => locals.get(id.sym.as_ref()).copied(),
+        _ => None,
+    }
+}
+
+/// A top-level component with a props parameter
+struct ComponentFn<'a> {
+    name: String,
+    props: &'a Pat,
+    body: RenderBody<'a>,
+}
+
+/// `function C(p) {}`, `const C = (p) => ..`, `const C = memo((p) => ..)`, `export default function C(p) {}`
+fn component_fns(module: &Module) -> Vec<ComponentFn<'_>> {
+    fn from_function(name: String, f: &Function) -> Option<ComponentFn<'_>> {
+        Some(ComponentFn { name, props: &f.params.first()?.pat, body: RenderBody::Block(f.body.as_ref()?) })
+    }
+    fn from_expr(name: String, expr: &Expr) -> Option<ComponentFn<'_>> {
+        match expr {
+            Expr::Arrow(a) => {
+                let body = match &*a.body {
+                    BlockStmtOrExpr::BlockStmt(b) => RenderBody::Block(b),
+                    BlockStmtOrExpr::Expr(e) => RenderBody::Expr(e),
+                };
+                Some(ComponentFn { name, props: a.params.first()?, body })
+            }
+            Expr::Fn(f) => from_function(name, &f.function),
+            Expr::Paren(p) => from_expr(name, &p.expr),
+            Expr::Call(CallExpr { callee: Callee::Expr(callee), args, .. }) if is_identifier_react_memo(callee) => {
+                args.first().and_then(|a| from_expr(name, &a.expr))
+            }
+            _ => None,
+        }
+    }
+    let mut out = Vec::new();
+    for item in &module.body {
+        let decl = match item {
+            ModuleItem::Stmt(Stmt::Decl(d)) => d,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(e)) => &e.decl,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(ExportDefaultDecl { decl: DefaultDecl::Fn(f), .. })) => {
+                out.extend(f.ident.as_ref().and_then(|id| from_function(id.sym.to_string(), &f.function)));
+                continue;
+            }
+            _ => continue,
+        };
+        match decl {
+            Decl::Fn(f) if is_component_name(f.ident.sym.as_ref()) => out.extend(from_function(f.ident.sym.to_string(), &f.function)),
+            Decl::Var(v) => {
+                for d in &v.decls {
+                    let (Some(id), Some(init)) = (d.name.as_ident(), &d.init) else { continue };
+                    if is_component_name(id.sym.as_ref()) {
+                        out.extend(from_expr(id.sym.to_string(), init));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// `FindingKind::LargeUnionProp`: opt-in, since it costs type-check time rather than renders
+pub struct LargeUnionPropRule {
+    /// Unions with more members than this are reported
+    pub max_members: usize,
+}
+
+impl Default for LargeUnionPropRule {
+    fn default() -> Self {
+        Self { max_members: 8 }
+    }
+}
+
+impl Rule for LargeUnionPropRule {
+    fn name(&self) -> &'static str {
+        "LargeUnionProp"
+    }
+    fn description(&self) -> &'static str {
+        "Prop typed as a union wider than the configured limit; slows down type checking"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Maintainability
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let locals = local_type_members(ctx.module);
+        let mut findings = Vec::new();
+        for ComponentFn { name: component, props, .. } in component_fns(ctx.module) {
+            let Some(members) = props_type_members(props, &locals) else { continue };
+            for member in members {
+                let TsTypeElement::TsPropertySignature(sig) = member else { continue };
+                let (Expr::Ident(key), Some(ty)) = (&*sig.key, &sig.type_ann) else { continue };
+                if let TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(union)) = &*ty.type_ann {
+                    if union.types.len() > self.max_members {
+                        findings.push(finding_at(
+                            ctx.lines,
+                            sig.span,
+                            FindingKind::LargeUnionProp { component: component.clone(), prop: key.sym.to_string(), members: union.types.len() },
+                        ));
+                    }
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// `lazy(...)` or `React.lazy(...)`
+fn is_react_lazy(expr: &Expr) -> bool {
+    match expr {
+        Expr::Ident(id) => id.sym.as_ref() == "lazy",
+        Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(p), .. }) => {
+            matches!(&**obj, Expr::Ident(o) if o.sym.as_ref() == "React") && p.sym.as_ref() == "lazy"
+        }
+        _ => false,
+    }
+}
+
+/// `FindingKind::CodeSplitPoint`
+pub struct CodeSplitPointRule;
+
+struct CodeSplitScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+    /// Depth of enclosing `React.lazy(...)` calls
+    lazy_depth: usize,
+}
+
+impl Visit for CodeSplitScan<'_> {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        match &call.callee {
+            Callee::Import(_) => {
+                if let Some(Expr::Lit(Lit::Str(src))) = call.args.first().map(|a| &*a.expr) {
+                    let module = src.value.to_string();
+                    // re-classified against tsconfig aliases when the file is stored
+                    let kind = classify_import_source(&module, &PathAliases::default());
+                    self.findings.push(finding_at(self.lines, call.span, FindingKind::CodeSplitPoint { module, kind, lazy: self.lazy_depth > 0 }));
+                }
+                call.visit_children_with(self);
+            }
+            Callee::Expr(callee) if is_react_lazy(callee) => {
+                self.lazy_depth += 1;
+                call.visit_children_with(self);
+                self.lazy_depth -= 1;
+            }
+            _ => call.visit_children_with(self),
+        }
+    }
+}
+
+impl Rule for CodeSplitPointRule {
+    fn name(&self) -> &'static str {
+        "CodeSplitPoint"
+    }
+    fn description(&self) -> &'static str {
+        "Dynamic `import()`, lazy or not: a code-split boundary"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Bundle
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = CodeSplitScan { lines: ctx.lines, findings: Vec::new(), lazy_depth: 0 };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// Setters a component gets from `const [x, setX] = useState(...)` (or `useReducer`'s dispatch)
+#[derive(Default)]
+struct StateSetters(Vec<String>);
+
+impl Visit for StateSetters {
+    fn visit_var_declarator(&mut self, d: &VarDeclarator) {
+        if let (Pat::Array(arr), Some(init)) = (&d.name, &d.init) {
+            let is_state_hook = match &**init {
+                Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }) => {
+                    matches!(hook_name(callee).as_deref(), Some("useState") | Some("useReducer"))
+                }
+                _ => false,
+            };
+            if let Some(Some(Pat::Ident(setter))) = arr.elems.get(1).filter(|_| is_state_hook) {
+                self.0.push(setter.sym.to_string());
+            }
+        }
+        d.visit_children_with(self);
+    }
+}
+
+/// Globals named like setters that aren't
+const NON_SETTERS: &[&str] = &["setTimeout", "setInterval", "setImmediate"];
+
+/// `setX` by naming convention, when the setter doesn't come from a local `useState`
+/// (e.g. one passed down as a prop)
+fn is_setter_name(name: &str) -> bool {
+    !NON_SETTERS.contains(&name) && name.strip_prefix("set").and_then(|rest| rest.chars().next()).map(|c| c.is_uppercase()).unwrap_or(false)
+}
+
+/// Setter calls inside loops of one handler body; nested functions other than
+/// iteration callbacks run at some other time and are left to their own scan
+struct SetterLoopScan<'a> {
+    lines: &'a LineIndex,
+    setters: &'a [String],
+    findings: Vec<Finding>,
+    loop_depth: usize,
+}
+
+impl SetterLoopScan<'_> {
+    fn in_loop(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.loop_depth += 1;
+        visit(self);
+        self.loop_depth -= 1;
+    }
+}
+
+impl Visit for SetterLoopScan<'_> {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        match &call.callee {
+            Callee::Expr(callee) => match &**callee {
+                Expr::Ident(id) if self.loop_depth > 0 && (self.setters.iter().any(|s| s == id.sym.as_ref()) || is_setter_name(id.sym.as_ref())) => {
+                    self.findings.push(finding_at(self.lines, call.span, FindingKind::SetStateInLoop { setter: id.sym.to_string() }));
+                    call.visit_children_with(self);
+                }
+                Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(method), .. }) if ITERATION_METHODS.contains(&method.sym.as_ref()) => {
+                    obj.visit_with(self);
+                    self.in_loop(|scan| {
+                        for arg in &call.args {
+                            match &*arg.expr {
+                                Expr::Arrow(a) => a.body.visit_with(scan),
+                                Expr::Fn(f) => f.function.body.visit_with(scan),
+                                other => other.visit_with(scan),
+                            }
+                        }
+                    });
+                }
+                _ => call.visit_children_with(self),
+            },
+            _ => call.visit_children_with(self),
+        }
+    }
+    fn visit_for_stmt(&mut self, f: &ForStmt) {
+        self.in_loop(|scan| f.visit_children_with(scan));
+    }
+    fn visit_for_of_stmt(&mut self, f: &ForOfStmt) {
+        f.right.visit_with(self);
+        self.in_loop(|scan| f.body.visit_with(scan));
+    }
+    fn visit_for_in_stmt(&mut self, f: &ForInStmt) {
+        f.right.visit_with(self);
+        self.in_loop(|scan| f.body.visit_with(scan));
+    }
+    fn visit_while_stmt(&mut self, w: &WhileStmt) {
+        self.in_loop(|scan| w.visit_children_with(scan));
+    }
+    fn visit_do_while_stmt(&mut self, w: &DoWhileStmt) {
+        self.in_loop(|scan| w.visit_children_with(scan));
+    }
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+}
+
+/// Event handlers inside a component: `const handleX = () => ..` (optionally in
+/// `useCallback`), nested function declarations, and inline `on*={() => ..}` props
+struct HandlerScan<'a> {
+    lines: &'a LineIndex,
+    setters: &'a [String],
+    findings: Vec<Finding>,
+}
+
+impl HandlerScan<'_> {
+    fn scan_handler(&mut self, handler: &Expr) {
+        match handler {
+            Expr::Arrow(a) => self.scan_body(&a.body),
+            Expr::Fn(f) => self.scan_body(&f.function.body),
+            Expr::Paren(p) => self.scan_handler(&p.expr),
+            Expr::Call(CallExpr { callee: Callee::Expr(callee), args, .. }) if hook_name(callee).as_deref() == Some("useCallback") => {
+                if let Some(first) = args.first() {
+                    self.scan_handler(&first.expr);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn scan_body<N>(&mut self, body: &N)
+    where
+        N: for<'b> VisitWith<SetterLoopScan<'b>>,
+    {
+        let mut scan = SetterLoopScan { lines: self.lines, setters: self.setters, findings: Vec::new(), loop_depth: 0 };
+        body.visit_with(&mut scan);
+        self.findings.extend(scan.findings);
+    }
+}
+
+impl Visit for HandlerScan<'_> {
+    fn visit_var_declarator(&mut self, d: &VarDeclarator) {
+        if let Some(init) = &d.init {
+            self.scan_handler(init);
+        }
+        d.visit_children_with(self);
+    }
+    fn visit_fn_decl(&mut self, f: &FnDecl) {
+        self.scan_body(&f.function.body);
+        f.visit_children_with(self);
+    }
+    fn visit_jsx_attr(&mut self, attr: &JSXAttr) {
+        let is_event = matches!(&attr.name, JSXAttrName::Ident(n) if n.sym.starts_with("on") && n.sym.chars().nth(2).map(|c| c.is_uppercase()).unwrap_or(false));
+        if let (true, Some(JSXAttrValue::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(e), .. }))) = (is_event, &attr.value) {
+            self.scan_handler(e);
+        }
+        attr.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::SetStateInLoop`
+pub struct SetStateInLoopRule;
+
+impl Rule for SetStateInLoopRule {
+    fn name(&self) -> &'static str {
+        "SetStateInLoop"
+    }
+    fn description(&self) -> &'static str {
+        "State setter called in a loop inside an event handler: one update per iteration"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for_each_render_body(ctx, |body| {
+            let RenderBody::Block(b) = body else { return };
+            let mut setters = StateSetters::default();
+            b.visit_with(&mut setters);
+            let mut handlers = HandlerScan { lines: ctx.lines, setters: &setters.0, findings: Vec::new() };
+            b.visit_with(&mut handlers);
+            findings.extend(handlers.findings);
+        });
+        findings
+    }
+}
+
+/// Value that is the same on every render: literals, `-1`, templates without expressions
+fn is_static_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(Lit::Str(_) | Lit::Num(_) | Lit::Bool(_) | Lit::Null(_)) => true,
+        Expr::Tpl(t) => t.exprs.is_empty(),
+        Expr::Unary(UnaryExpr { op: UnaryOp::Minus | UnaryOp::Plus, arg, .. }) => matches!(&**arg, Expr::Lit(Lit::Num(_))),
+        Expr::Paren(p) => is_static_literal(&p.expr),
+        _ => false,
+    }
+}
+
+/// `{ color: 'red', margin: 4 }`: plain keys and literal values only; spreads,
+/// computed keys and any variable make the style dynamic
+pub(crate) fn is_static_style(obj: &ObjectLit) -> bool {
+    obj.props.iter().all(|p| match p {
+        PropOrSpread::Prop(prop) => match &**prop {
+            Prop::KeyValue(kv) => !matches!(kv.key, PropName::Computed(_)) && is_static_literal(&kv.value),
+            _ => false,
+        },
+        PropOrSpread::Spread(_) => false,
+    })
+}
+
+/// `FindingKind::StaticInlineStyle`; dynamic styles are left to the inline-prop analysis,
+/// which marks its static ones `is_static` and leaves `style` out of `defeated_memos`
+pub struct StaticInlineStyleRule;
+
+struct StaticStyleScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl Visit for StaticStyleScan<'_> {
+    fn visit_jsx_opening_element(&mut self, el: &JSXOpeningElement) {
+        for attr in &el.attrs {
+            let JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(n), value: Some(JSXAttrValue::JSXExprContainer(c)), span, .. }) = attr else { continue };
+            let JSXExpr::Expr(value) = &c.expr else { continue };
+            if n.sym.as_ref() != "style" {
+                continue;
+            }
+            if let Expr::Object(obj) = &**value {
+                if !obj.props.is_empty() && is_static_style(obj) {
+                    let element = jsx_element_name(&el.name).unwrap_or_default();
+                    self.findings.push(finding_at(self.lines, *span, FindingKind::StaticInlineStyle { element }));
+                }
+            }
+        }
+        el.visit_children_with(self);
+    }
+}
+
+impl Rule for StaticInlineStyleRule {
+    fn name(&self) -> &'static str {
+        "StaticInlineStyle"
+    }
+    fn description(&self) -> &'static str {
+        "Inline `style` built only from literals; hoist it to a constant or a CSS class"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = StaticStyleScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// `{...rest}` spread onto a component element, for a rest binding of the props
+struct RestSpreadScan<'a> {
+    lines: &'a LineIndex,
+    component: &'a str,
+    rest: &'a str,
+    findings: Vec<Finding>,
+}
+
+impl Visit for RestSpreadScan<'_> {
+    fn visit_jsx_opening_element(&mut self, el: &JSXOpeningElement) {
+        if let Some(element) = jsx_element_name(&el.name).filter(|n| is_component_element(n)) {
+            for attr in &el.attrs {
+                let JSXAttrOrSpread::SpreadElement(spread) = attr else { continue };
+                if matches!(&*spread.expr, Expr::Ident(i) if i.sym.as_ref() == self.rest) {
+                    let kind = FindingKind::RestSpreadForwarding { component: self.component.to_string(), element: element.clone() };
+                    self.findings.push(finding_at(self.lines, spread.dot3_token, kind));
+                }
+            }
+        }
+        el.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::RestSpreadForwarding`
+pub struct RestSpreadForwardingRule;
+
+impl Rule for RestSpreadForwardingRule {
+    fn name(&self) -> &'static str {
+        "RestSpreadForwarding"
+    }
+    fn description(&self) -> &'static str {
+        "Rest props spread onto a child: an unknown-shaped, fresh object every render"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for c in component_fns(ctx.module) {
+            let Pat::Object(obj) = c.props else { continue };
+            let rest = obj.props.iter().find_map(|p| match p {
+                ObjectPatProp::Rest(RestPat { arg, .. }) => arg.as_ident().map(|id| id.sym.to_string()),
+                _ => None,
+            });
+            let Some(rest) = rest else { continue };
+            let mut scan = RestSpreadScan { lines: ctx.lines, component: &c.name, rest: &rest, findings: Vec::new() };
+            match c.body {
+                RenderBody::Block(b) => b.visit_with(&mut scan),
+                RenderBody::Expr(e) => e.visit_with(&mut scan),
+            }
+            findings.extend(scan.findings);
+        }
+        findings
+    }
+}
+
+/// Components passed by name to `forwardRef(Name)` anywhere in the module
+#[derive(Default)]
+struct ForwardRefTargets(Vec<String>);
+
+impl Visit for ForwardRefTargets {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            if is_identifier_react_forward_ref(callee) {
+                if let Some(Expr::Ident(id)) = call.args.first().map(|a| &*a.expr) {
+                    self.0.push(id.sym.to_string());
+                }
+            }
+        }
+        call.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::RefPropWithoutForwardRef`. Components defined inline in
+/// `forwardRef(..)` are never listed by `component_fns`; those wrapped by name are skipped.
+pub struct RefPropWithoutForwardRefRule;
+
+impl Rule for RefPropWithoutForwardRefRule {
+    fn name(&self) -> &'static str {
+        "RefPropWithoutForwardRef"
+    }
+    fn description(&self) -> &'static str {
+        "`ref` read from props of a component not wrapped in `forwardRef`: React never passes it"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut wrapped = ForwardRefTargets::default();
+        ctx.module.visit_with(&mut wrapped);
+        let mut findings = Vec::new();
+        for c in component_fns(ctx.module) {
+            let Pat::Object(obj) = c.props else { continue };
+            if wrapped.0.contains(&c.name) {
+                continue;
+            }
+            let ref_key = obj.props.iter().find_map(|p| match p {
+                ObjectPatProp::Assign(a) if a.key.sym.as_ref() == "ref" => Some(a.key.span),
+                ObjectPatProp::KeyValue(KeyValuePatProp { key: PropName::Ident(k), .. }) if k.sym.as_ref() == "ref" => Some(k.span),
+                _ => None,
+            });
+            if let Some(span) = ref_key {
+                findings.push(finding_at(ctx.lines, span, FindingKind::RefPropWithoutForwardRef { component: c.name }));
+            }
+        }
+        findings
+    }
+}
+
+/// Types whose values are compared by reference: functions, objects, arrays
+fn is_reference_type(ty: &TsType) -> bool {
+    match ty {
+        TsType::TsFnOrConstructorType(_) | TsType::TsTypeLit(_) | TsType::TsArrayType(_) | TsType::TsTupleType(_) => true,
+        TsType::TsTypeRef(TsTypeRef { type_name: TsEntityName::Ident(id), .. }) => {
+            matches!(id.sym.as_ref(), "Array" | "ReadonlyArray" | "Record" | "Map" | "Set")
+        }
+        TsType::TsParenthesizedType(p) => is_reference_type(&p.type_ann),
+        TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(u)) => u.types.iter().any(|t| is_reference_type(t)),
+        _ => false,
+    }
+}
+
+/// Props typed as (or defaulting to) a function, object or array
+fn reference_props(props: &Pat, locals: &HashMap<String, &[TsTypeElement]>) -> Vec<String> {
+    let mut out = Vec::new();
+    for member in props_type_members(props, locals).unwrap_or_default() {
+        match member {
+            TsTypeElement::TsPropertySignature(sig) => {
+                if let (Expr::Ident(key), Some(ty)) = (&*sig.key, &sig.type_ann) {
+                    if is_reference_type(&ty.type_ann) {
+                        out.push(key.sym.to_string());
+                    }
+                }
+            }
+            TsTypeElement::TsMethodSignature(sig) => {
+                if let Expr::Ident(key) = &*sig.key {
+                    out.push(key.sym.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Pat::Object(obj) = props {
+        for prop in &obj.props {
+            let ObjectPatProp::Assign(AssignPatProp { key, value: Some(value), .. }) = prop else { continue };
+            let name = key.sym.to_string();
+            if matches!(&**value, Expr::Object(_) | Expr::Array(_) | Expr::Arrow(_) | Expr::Fn(_)) && !out.contains(&name) {
+                out.push(name);
+            }
+        }
+    }
+    out
+}
+
+/// Prop names a `(prev, next) => ..` comparator reads; `whole` once a props object
+/// escapes (`shallowEqual(prev, next)`, `prev[key]`), which may compare anything
+#[derive(Default)]
+struct ComparatorRefs {
+    params: Vec<String>,
+    props: Vec<String>,
+    whole: bool,
+}
+
+impl Visit for ComparatorRefs {
+    fn visit_member_expr(&mut self, m: &MemberExpr) {
+        if matches!(&*m.obj, Expr::Ident(o) if self.params.contains(&o.sym.to_string())) {
+            match &m.prop {
+                MemberProp::Ident(p) => self.props.push(p.sym.to_string()),
+                _ => self.whole = true,
+            }
+            return;
+        }
+        m.obj.visit_with(self);
+        if let MemberProp::Computed(c) = &m.prop {
+            c.visit_with(self);
+        }
+    }
+    fn visit_ident(&mut self, id: &Ident) {
+        if self.params.contains(&id.sym.to_string()) {
+            self.whole = true;
+        }
+    }
+}
+
+/// What a comparator checks, or None when it isn't an inline function
+fn comparator_refs(cmp: &Expr) -> Option<ComparatorRefs> {
+    let mut refs = ComparatorRefs::default();
+    let params: Vec<&Pat> = match cmp {
+        Expr::Arrow(a) => a.params.iter().collect(),
+        Expr::Fn(f) => f.function.params.iter().map(|p| &p.pat).collect(),
+        _ => return None,
+    };
+    for param in params {
+        match param {
+            Pat::Ident(id) => refs.params.push(id.sym.to_string()),
+            Pat::Object(obj) => {
+                for prop in &obj.props {
+                    match prop {
+                        ObjectPatProp::Assign(a) => refs.props.push(a.key.sym.to_string()),
+                        ObjectPatProp::KeyValue(KeyValuePatProp { key: PropName::Ident(k), .. }) => refs.props.push(k.sym.to_string()),
+                        _ => refs.whole = true,
+                    }
+                }
+            }
+            _ => refs.whole = true,
+        }
+    }
+    match cmp {
+        Expr::Arrow(a) => a.body.visit_with(&mut refs),
+        Expr::Fn(f) => f.function.body.visit_with(&mut refs),
+        _ => {}
+    }
+    Some(refs)
+}
+
+/// `memo(Component, comparator)` calls, keyed by the component they wrap
+#[derive(Default)]
+struct MemoComparators<'a> {
+    declarator: Option<String>,
+    found: Vec<(String, &'a Expr)>,
+}
+
+impl<'a> MemoComparators<'a> {
+    fn scan(module: &'a Module) -> Vec<(String, &'a Expr)> {
+        let mut scan = Self::default();
+        for item in &module.body {
+            let (decls, default_expr): (&[VarDeclarator], Option<&Expr>) = match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Var(v))) => (&v.decls, None),
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl: Decl::Var(v), .. })) => (&v.decls, None),
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(e)) => (&[], Some(&*e.expr)),
+                _ => continue,
+            };
+            for d in decls {
+                scan.declarator = d.name.as_ident().map(|id| id.sym.to_string());
+                if let Some(init) = &d.init {
+                    scan.call(init);
+                }
+            }
+            if let Some(e) = default_expr {
+                scan.declarator = None;
+                scan.call(e);
+            }
+        }
+        scan.found
+    }
+
+    fn call(&mut self, expr: &'a Expr) {
+        let Expr::Call(CallExpr { callee: Callee::Expr(callee), args, .. }) = expr else { return };
+        if !is_identifier_react_memo(callee) || args.len() < 2 {
+            return;
+        }
+        let component = match &*args[0].expr {
+            Expr::Ident(id) => Some(id.sym.to_string()),
+            _ => self.declarator.clone(),
+        };
+        if let Some(component) = component {
+            self.found.push((component, &args[1].expr));
+        }
+    }
+}
+
+/// `FindingKind::ComparatorMissingProp`
+pub struct ComparatorMissingPropRule;
+
+impl Rule for ComparatorMissingPropRule {
+    fn name(&self) -> &'static str {
+        "ComparatorMissingProp"
+    }
+    fn description(&self) -> &'static str {
+        "Function, object or array prop a custom `memo` comparator never reads: its changes are ignored"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let comparators = MemoComparators::scan(ctx.module);
+        if comparators.is_empty() {
+            return Vec::new();
+        }
+        let locals = local_type_members(ctx.module);
+        let components = component_fns(ctx.module);
+        let mut findings = Vec::new();
+        for (component, cmp) in comparators {
+            let Some(refs) = comparator_refs(cmp) else { continue };
+            if refs.whole {
+                continue;
+            }
+            let Some(c) = components.iter().find(|c| c.name == component) else { continue };
+            for prop in reference_props(c.props, &locals) {
+                if !refs.props.contains(&prop) {
+                    let kind = FindingKind::ComparatorMissingProp { component: component.clone(), prop };
+                    findings.push(finding_at(ctx.lines, cmp.span(), kind));
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// HTML attribute spellings React doesn't recognise, and the prop it expects instead
+const RENAMED_DOM_PROPS: &[(&str, &str)] = &[
+    ("class", "className"),
+    ("for", "htmlFor"),
+    ("tabindex", "tabIndex"),
+    ("readonly", "readOnly"),
+    ("maxlength", "maxLength"),
+    ("minlength", "minLength"),
+    ("colspan", "colSpan"),
+    ("rowspan", "rowSpan"),
+    ("charset", "charSet"),
+    ("autocomplete", "autoComplete"),
+    ("autofocus", "autoFocus"),
+    ("contenteditable", "contentEditable"),
+    ("crossorigin", "crossOrigin"),
+    ("enctype", "encType"),
+    ("accesskey", "accessKey"),
+    ("srcset", "srcSet"),
+    ("onclick", "onClick"),
+    ("onchange", "onChange"),
+    ("oninput", "onInput"),
+    ("onsubmit", "onSubmit"),
+    ("onkeydown", "onKeyDown"),
+    ("onkeyup", "onKeyUp"),
+    ("onblur", "onBlur"),
+    ("onfocus", "onFocus"),
+];
+
+struct DomPropScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl Visit for DomPropScan<'_> {
+    fn visit_jsx_opening_element(&mut self, el: &JSXOpeningElement) {
+        for attr in &el.attrs {
+            let JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(n), span, .. }) = attr else { continue };
+            if let Some((name, suggestion)) = RENAMED_DOM_PROPS.iter().find(|(name, _)| n.sym.as_ref() == *name) {
+                let kind = FindingKind::MisusedDomProp { name: name.to_string(), suggestion: suggestion.to_string() };
+                self.findings.push(finding_at(self.lines, *span, kind));
+            }
+        }
+        el.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::MisusedDomProp`: HTML spellings on any element (custom components
+/// tend to forward them to the DOM), and `key` read back from a component's props
+pub struct MisusedDomPropRule;
+
+impl Rule for MisusedDomPropRule {
+    fn name(&self) -> &'static str {
+        "MisusedDomProp"
+    }
+    fn description(&self) -> &'static str {
+        "HTML attribute spelling (`class`, `for`, `onclick`) or reserved `key` used as a prop"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = DomPropScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        for c in component_fns(ctx.module) {
+            let Pat::Object(obj) = c.props else { continue };
+            let key = obj.props.iter().find_map(|p| match p {
+                ObjectPatProp::Assign(a) if a.key.sym.as_ref() == "key" => Some(a.key.span),
+                ObjectPatProp::KeyValue(KeyValuePatProp { key: PropName::Ident(k), .. }) if k.sym.as_ref() == "key" => Some(k.span),
+                _ => None,
+            });
+            if let Some(span) = key {
+                // React consumes `key`; the component always receives undefined
+                let kind = FindingKind::MisusedDomProp { name: "key".into(), suggestion: "a prop with another name, e.g. `id`".into() };
+                scan.findings.push(finding_at(ctx.lines, span, kind));
+            }
+        }
+        scan.findings
+    }
+}
+
+struct RegexScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl RegexScan<'_> {
+    fn check(&mut self, pattern: &str, span: Span) {
+        let analysis = analyze_pattern(pattern);
+        // an unanalyzable pattern isn't safe, but there's no issue to report either
+        if !analysis.issues.is_empty() {
+            let kind = FindingKind::UnsafeRegex { pattern: pattern.to_string(), issues: analysis.issues, rewrite: analysis.rewrite };
+            self.findings.push(finding_at(self.lines, span, kind));
+        }
+    }
+}
+
+impl Visit for RegexScan<'_> {
+    fn visit_regex(&mut self, re: &Regex) {
+        self.check(re.exp.as_ref(), re.span);
+    }
+    fn visit_new_expr(&mut self, n: &NewExpr) {
+        if matches!(&*n.callee, Expr::Ident(id) if id.sym.as_ref() == "RegExp") {
+            if let Some(Expr::Lit(Lit::Str(pattern))) = n.args.as_ref().and_then(|a| a.first()).map(|a| &*a.expr) {
+                self.check(pattern.value.as_ref(), n.span);
+            }
+        }
+        n.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::UnsafeRegex`: opt-in (the `lint` command turns it on), since the
+/// JS rule already checks regexes when linting through ESLint
+pub struct UnsafeRegexRule;
+
+impl Rule for UnsafeRegexRule {
+    fn name(&self) -> &'static str {
+        "UnsafeRegex"
+    }
+    fn description(&self) -> &'static str {
+        "Regex open to catastrophic backtracking (ReDoS)"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Security
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = RegexScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// The expression a callback returns: an arrow's expression body or its only `return`
+fn returned_expr(callback: &Expr) -> Option<&Expr> {
+    let body = match callback {
+        Expr::Arrow(a) => match &*a.body {
+            BlockStmtOrExpr::Expr(e) => return Some(e),
+            BlockStmtOrExpr::BlockStmt(b) => b,
+        },
+        Expr::Fn(f) => f.function.body.as_ref()?,
+        Expr::Paren(p) => return returned_expr(&p.expr),
+        _ => return None,
+    };
+    match &body.stmts[..] {
+        [Stmt::Return(ReturnStmt { arg: Some(arg), .. })] => Some(arg),
+        _ => None,
+    }
+}
+
+/// `<>...</>` holding one element and nothing but whitespace besides
+fn is_single_element_fragment(expr: &Expr) -> bool {
+    let frag = match expr {
+        Expr::JSXFragment(f) => f,
+        Expr::Paren(p) => return is_single_element_fragment(&p.expr),
+        _ => return false,
+    };
+    let mut children = frag.children.iter().filter(|c| !matches!(c, JSXElementChild::JSXText(t) if t.value.trim().is_empty()));
+    matches!((children.next(), children.next()), (Some(JSXElementChild::JSXElement(_)), None))
+}
+
+struct FragmentInMapScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl Visit for FragmentInMapScan<'_> {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            let is_map = matches!(&**callee, Expr::Member(MemberExpr { prop: MemberProp::Ident(p), .. }) if p.sym.as_ref() == "map");
+            if let Some(ret) = call.args.first().filter(|_| is_map).and_then(|a| returned_expr(&a.expr)) {
+                if is_single_element_fragment(ret) {
+                    self.findings.push(finding_at(self.lines, ret.span(), FindingKind::FragmentInMap));
+                }
+            }
+        }
+        call.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::FragmentInMap`
+pub struct FragmentInMapRule;
+
+impl Rule for FragmentInMapRule {
+    fn name(&self) -> &'static str {
+        "FragmentInMap"
+    }
+    fn description(&self) -> &'static str {
+        "Fragment around the only element of a `.map` callback: an extra node per item"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = FragmentInMapScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// `JSON.parse` / `JSON.stringify`, as `parse` / `stringify`
+fn json_method(callee: &Expr) -> Option<&str> {
+    match callee {
+        Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(p), .. }) if matches!(&**obj, Expr::Ident(o) if o.sym.as_ref() == "JSON") => {
+            matches!(p.sym.as_ref(), "parse" | "stringify").then(|| p.sym.as_ref())
+        }
+        _ => None,
+    }
+}
+
+struct JsonInRenderScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+    /// Inside a JSX attribute value or a hook's deps array
+    escaping: bool,
+    /// Inside a reported call: `JSON.parse(JSON.stringify(x))` is one finding
+    in_json: bool,
+}
+
+impl JsonInRenderScan<'_> {
+    fn escaping(&mut self, node: &impl VisitWith<Self>) {
+        let outer = std::mem::replace(&mut self.escaping, true);
+        node.visit_children_with(self);
+        self.escaping = outer;
+    }
+}
+
+impl Visit for JsonInRenderScan<'_> {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        let Callee::Expr(callee) = &call.callee else { return call.visit_children_with(self) };
+        if let Some(method) = json_method(callee).filter(|_| !self.in_json) {
+            let kind = FindingKind::JsonInRender { method: method.to_string(), escapes: self.escaping };
+            self.findings.push(finding_at(self.lines, call.span, kind));
+            self.in_json = true;
+            call.visit_children_with(self);
+            self.in_json = false;
+            return;
+        }
+        if hook_name(callee).is_some() && call.args.len() >= 2 {
+            callee.visit_with(self);
+            let (last, rest) = call.args.split_last().expect("checked above");
+            rest.iter().for_each(|a| a.visit_with(self));
+            self.escaping(last);
+            return;
+        }
+        call.visit_children_with(self);
+    }
+    fn visit_jsx_attr_value(&mut self, v: &JSXAttrValue) {
+        self.escaping(v);
+    }
+    // event handlers, effects and memo callbacks don't run on every render
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+}
+
+/// `FindingKind::JsonInRender`
+pub struct JsonInRenderRule;
+
+impl Rule for JsonInRenderRule {
+    fn name(&self) -> &'static str {
+        "JsonInRender"
+    }
+    fn description(&self) -> &'static str {
+        "`JSON.parse`/`JSON.stringify` on the render path: a full (de)serialization per render"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = JsonInRenderScan { lines: ctx.lines, findings: Vec::new(), escaping: false, in_json: false };
+        for_each_render_body(ctx, |body| match body {
+            RenderBody::Block(b) => b.visit_children_with(&mut scan),
+            RenderBody::Expr(e) => e.visit_with(&mut scan),
+        });
+        scan.findings
+    }
+}
+
+/// Ternaries chained through either branch: `a ? x : b ? y : z` is 2
+fn ternary_depth(expr: &Expr) -> usize {
+    match expr {
+        Expr::Cond(c) => 1 + ternary_depth(&c.cons).max(ternary_depth(&c.alt)),
+        Expr::Paren(p) => ternary_depth(&p.expr),
+        _ => 0,
+    }
+}
+
+struct TernaryScan<'a> {
+    lines: &'a LineIndex,
+    min_depth: usize,
+    findings: Vec<Finding>,
+}
+
+impl Visit for TernaryScan<'_> {
+    fn visit_jsx_element_child(&mut self, child: &JSXElementChild) {
+        if let JSXElementChild::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(e), span }) = child {
+            let depth = ternary_depth(e);
+            if depth >= self.min_depth {
+                self.findings.push(finding_at(self.lines, *span, FindingKind::DeepJsxTernary { depth }));
+            }
+        }
+        child.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::DeepJsxTernary`
+pub struct DeepJsxTernaryRule {
+    /// Chains of at least this many ternaries are reported
+    pub min_depth: usize,
+}
+
+impl Default for DeepJsxTernaryRule {
+    fn default() -> Self {
+        Self { min_depth: 3 }
+    }
+}
+
+impl Rule for DeepJsxTernaryRule {
+    fn name(&self) -> &'static str {
+        "DeepJsxTernary"
+    }
+    fn description(&self) -> &'static str {
+        "Ternary chain in JSX at or deeper than the configured depth"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Maintainability
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = TernaryScan { lines: ctx.lines, min_depth: self.min_depth, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// `onClick`, `onSubmit`, ... (`on` then an uppercase letter)
+fn is_handler_prop(name: &str) -> bool {
+    name.strip_prefix("on").and_then(|rest| rest.chars().next()).map(|c| c.is_ascii_uppercase()).unwrap_or(false)
+}
+
+/// Literals that can never be called
+fn is_non_callable(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(Lit::Str(_) | Lit::Num(_) | Lit::Bool(_) | Lit::BigInt(_) | Lit::Regex(_)) => true,
+        Expr::Tpl(_) | Expr::Object(_) | Expr::Array(_) => true,
+        Expr::Paren(p) => is_non_callable(&p.expr),
+        _ => false,
+    }
+}
+
+/// Names bound anywhere in the module: `true` while every binding is a `const` set to a
+/// non-callable literal; params, `let`s and other declarations make a name unknown
+#[derive(Default)]
+struct LiteralBindings {
+    names: HashMap<String, bool>,
+}
+
+impl Visit for LiteralBindings {
+    fn visit_var_decl(&mut self, decl: &VarDecl) {
+        for d in &decl.decls {
+            match (&d.name, &d.init) {
+                (Pat::Ident(id), Some(init)) if decl.kind == VarDeclKind::Const && is_non_callable(init) => {
+                    self.names.entry(id.id.sym.to_string()).or_insert(true);
+                    init.visit_with(self);
+                }
+                _ => d.visit_with(self),
+            }
+        }
+    }
+    fn visit_binding_ident(&mut self, id: &BindingIdent) {
+        self.names.insert(id.id.sym.to_string(), false);
+    }
+}
+
+struct HandlerValueScan<'a> {
+    lines: &'a LineIndex,
+    literals: HashMap<String, bool>,
+    findings: Vec<Finding>,
+}
+
+impl HandlerValueScan<'_> {
+    fn is_non_function(&self, value: &JSXAttrValue) -> bool {
+        match value {
+            JSXAttrValue::Lit(_) => true,
+            JSXAttrValue::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(e), .. }) => match &**e {
+                Expr::Ident(id) => self.literals.get(id.sym.as_ref()).copied().unwrap_or(false),
+                e => is_non_callable(e),
+            },
+            _ => false,
+        }
+    }
+}
+
+impl Visit for HandlerValueScan<'_> {
+    fn visit_jsx_attr(&mut self, attr: &JSXAttr) {
+        if let (JSXAttrName::Ident(n), Some(value)) = (&attr.name, &attr.value) {
+            if is_handler_prop(n.sym.as_ref()) && self.is_non_function(value) {
+                self.findings.push(finding_at(self.lines, attr.span, FindingKind::NonFunctionHandler { prop: n.sym.to_string() }));
+            }
+        }
+        attr.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::NonFunctionHandler`
+pub struct NonFunctionHandlerRule;
+
+impl Rule for NonFunctionHandlerRule {
+    fn name(&self) -> &'static str {
+        "NonFunctionHandler"
+    }
+    fn description(&self) -> &'static str {
+        "`on*` prop set to something that can't be called"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut bindings = LiteralBindings::default();
+        ctx.module.visit_with(&mut bindings);
+        let mut scan = HandlerValueScan { lines: ctx.lines, literals: bindings.names, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// Literal kind of a JSX attribute value; `None` when it isn't written as a literal
+fn attr_value_kind(value: Option<&JSXAttrValue>) -> Option<&'static str> {
+    fn expr_kind(expr: &Expr) -> Option<&'static str> {
+        match expr {
+            Expr::Lit(Lit::Str(_)) | Expr::Tpl(_) => Some("string"),
+            Expr::Lit(Lit::Num(_)) => Some("number"),
+            Expr::Lit(Lit::Bool(_)) => Some("boolean"),
+            Expr::Lit(Lit::Null(_)) => Some("null"),
+            Expr::Ident(id) if id.sym.as_ref() == "undefined" => Some("undefined"),
+            Expr::Object(_) => Some("object"),
+            Expr::Array(_) => Some("array"),
+            Expr::Arrow(_) | Expr::Fn(_) => Some("function"),
+            Expr::JSXElement(_) | Expr::JSXFragment(_) => Some("element"),
+            Expr::Paren(p) => expr_kind(&p.expr),
+            _ => None,
+        }
+    }
+    match value {
+        // `<Input disabled />`
+        None => Some("boolean"),
+        Some(JSXAttrValue::Lit(_)) => Some("string"),
+        Some(JSXAttrValue::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(e), .. })) => expr_kind(e),
+        Some(_) => None,
+    }
+}
+
+/// Elements a component returns at the top level, through parens and ternaries
+#[derive(Default)]
+struct ReturnedElements<'a> {
+    elements: Vec<&'a JSXElement>,
+}
+
+impl<'a> ReturnedElements<'a> {
+    fn add(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::JSXElement(el) => self.elements.push(el),
+            Expr::Paren(p) => self.add(&p.expr),
+            Expr::Cond(c) => {
+                self.add(&c.cons);
+                self.add(&c.alt);
+            }
+            _ => {}
+        }
+    }
+
+    fn collect(&mut self, block: &'a BlockStmt) {
+        for stmt in &block.stmts {
+            self.collect_stmt(stmt);
+        }
+    }
+
+    // walks statements by hand (not `Visit`) to keep the `'a` borrows; nested functions are skipped
+    fn collect_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::Return(ReturnStmt { arg: Some(arg), .. }) => self.add(arg),
+            Stmt::Block(b) => self.collect(b),
+            Stmt::If(i) => {
+                self.collect_stmt(&i.cons);
+                if let Some(alt) = &i.alt {
+                    self.collect_stmt(alt);
+                }
+            }
+            Stmt::Switch(s) => s.cases.iter().flat_map(|c| &c.cons).for_each(|s| self.collect_stmt(s)),
+            Stmt::Try(t) => {
+                self.collect(&t.block);
+                if let Some(handler) = &t.handler {
+                    self.collect(&handler.body);
+                }
+            }
+            Stmt::Labeled(l) => self.collect_stmt(&l.body),
+            _ => {}
+        }
+    }
+}
+
+/// `FindingKind::InconsistentPropType`: the same element returned from several branches
+/// with a prop written as literals of different kinds (an object in one, `undefined` in
+/// another). Only literal values are compared, so it stays quiet on anything computed.
+pub struct InconsistentPropTypeRule;
+
+impl Rule for InconsistentPropTypeRule {
+    fn name(&self) -> &'static str {
+        "InconsistentPropType"
+    }
+    fn description(&self) -> &'static str {
+        "Same element returned from different branches with a prop set to literals of different types"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for c in component_fns(ctx.module) {
+            let mut returned = ReturnedElements::default();
+            match c.body {
+                RenderBody::Block(b) => returned.collect(b),
+                RenderBody::Expr(e) => returned.add(e),
+            }
+            // (element, prop) -> kind of the first branch that wrote it
+            let mut first: HashMap<(String, String), &'static str> = HashMap::new();
+            let mut reported: Vec<(String, String)> = Vec::new();
+            for el in returned.elements {
+                let Some(element) = jsx_element_name(&el.opening.name) else { continue };
+                for attr in &el.opening.attrs {
+                    let JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(n), value, span }) = attr else { continue };
+                    let Some(kind) = attr_value_kind(value.as_ref()) else { continue };
+                    let key = (element.clone(), n.sym.to_string());
+                    let seen = *first.entry(key.clone()).or_insert(kind);
+                    if seen != kind && !reported.contains(&key) {
+                        findings.push(finding_at(ctx.lines, *span, FindingKind::InconsistentPropType { prop: key.1.clone() }));
+                        reported.push(key);
+                    }
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// Counts reads and bindings of one name; property keys and member names aren't references
+struct NameRefs<'a> {
+    name: &'a str,
+    count: usize,
+}
+
+impl Visit for NameRefs<'_> {
+    fn visit_ident(&mut self, id: &Ident) {
+        if id.sym.as_ref() == self.name {
+            self.count += 1;
+        }
+    }
+    fn visit_member_prop(&mut self, prop: &MemberProp) {
+        if let MemberProp::Computed(c) = prop {
+            c.visit_with(self);
+        }
+    }
+    fn visit_prop_name(&mut self, name: &PropName) {
+        if let PropName::Computed(c) = name {
+            c.visit_with(self);
+        }
+    }
+    fn visit_jsx_attr_name(&mut self, _: &JSXAttrName) {}
+}
+
+/// `FindingKind::UnusedMemo`: `const x = useMemo(..)`/`useCallback(..)` at the top of a
+/// component body whose `x` is never read. Shadowing isn't tracked, so a same-named
+/// variable in a nested scope counts as a use.
+pub struct UnusedMemoRule;
+
+impl Rule for UnusedMemoRule {
+    fn name(&self) -> &'static str {
+        "UnusedMemo"
+    }
+    fn description(&self) -> &'static str {
+        "`useMemo`/`useCallback` result that is never read: pure overhead"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for c in component_fns(ctx.module) {
+            let RenderBody::Block(body) = c.body else { continue };
+            for stmt in &body.stmts {
+                let Stmt::Decl(Decl::Var(var)) = stmt else { continue };
+                for d in &var.decls {
+                    let (Pat::Ident(id), Some(init)) = (&d.name, &d.init) else { continue };
+                    let Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }) = &**init else { continue };
+                    if !matches!(hook_name(callee).as_deref(), Some("useMemo" | "useCallback")) {
+                        continue;
+                    }
+                    let mut refs = NameRefs { name: id.id.sym.as_ref(), count: 0 };
+                    body.visit_with(&mut refs);
+                    // the binding itself is the only occurrence
+                    if refs.count <= 1 {
+                        findings.push(finding_at(ctx.lines, id.id.span, FindingKind::UnusedMemo { name: id.id.sym.to_string() }));
+                    }
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// `FindingKind::UnusedProp`: a prop destructured in the parameter list whose binding is
+/// never read, in the body or another prop's default. `_`-prefixed props, nested patterns
+/// and the rest element are skipped; shadowing isn't tracked, as in `UnusedMemo`.
+pub struct UnusedPropRule;
+
+impl Rule for UnusedPropRule {
+    fn name(&self) -> &'static str {
+        "UnusedProp"
+    }
+    fn description(&self) -> &'static str {
+        "Destructured prop the component never reads"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Maintainability
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for c in component_fns(ctx.module) {
+            let Pat::Object(obj) = c.props else { continue };
+            for prop in &obj.props {
+                // (prop name, local binding)
+                let (key, binding) = match prop {
+                    ObjectPatProp::Assign(a) => (a.key.sym.to_string(), &a.key.id),
+                    ObjectPatProp::KeyValue(KeyValuePatProp { key: PropName::Ident(k), value }) => match &**value {
+                        Pat::Ident(id) => (k.sym.to_string(), &id.id),
+                        Pat::Assign(AssignPat { left, .. }) => match &**left {
+                            Pat::Ident(id) => (k.sym.to_string(), &id.id),
+                            _ => continue,
+                        },
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+                if key.starts_with('_') || binding.sym.starts_with('_') {
+                    continue;
+                }
+                let mut refs = NameRefs { name: binding.sym.as_ref(), count: 0 };
+                // not `obj` itself: its type annotation repeats every key
+                obj.props.visit_with(&mut refs);
+                match c.body {
+                    RenderBody::Block(b) => b.visit_with(&mut refs),
+                    RenderBody::Expr(e) => e.visit_with(&mut refs),
+                }
+                // the binding itself is the only occurrence
+                if refs.count <= 1 {
+                    findings.push(finding_at(ctx.lines, binding.span, FindingKind::UnusedProp { component: c.name.clone(), prop: key }));
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// Import sources `HeavyDependency` reports out of the box, with what to use instead
+pub const HEAVY_DEPENDENCIES: &[(&str, &str)] = &[
+    ("moment", "use date-fns, dayjs or Intl.DateTimeFormat; moment bundles every locale and can't be tree-shaken"),
+    ("lodash", "import single methods (`lodash/debounce`) or switch to `lodash-es`"),
+    ("underscore", "use native array/object methods or `lodash-es`"),
+    ("@mui/icons-material", "import each icon from its own path, e.g. `@mui/icons-material/Add`"),
+    ("core-js", "import only the polyfills your targets need, e.g. `core-js/actual/array/flat`"),
+];
+
+/// `FindingKind::HeavyDependency`: imports whose source exactly matches an entry of `advice`
+pub struct HeavyDependencyRule {
+    /// Import source -> advice
+    pub advice: BTreeMap<String, String>,
+}
+
+impl Default for HeavyDependencyRule {
+    fn default() -> Self {
+        Self { advice: HEAVY_DEPENDENCIES.iter().map(|(source, advice)| (source.to_string(), advice.to_string())).collect() }
+    }
+}
+
+impl HeavyDependencyRule {
+    /// The default table with `overrides` applied; an empty advice removes a source
+    pub fn with_overrides(overrides: &BTreeMap<String, String>) -> Self {
+        let mut rule = Self::default();
+        for (source, advice) in overrides {
+            if advice.is_empty() {
+                rule.advice.remove(source);
+            } else {
+                rule.advice.insert(source.clone(), advice.clone());
+            }
+        }
+        rule
+    }
+}
+
+impl Rule for HeavyDependencyRule {
+    fn name(&self) -> &'static str {
+        "HeavyDependency"
+    }
+    fn description(&self) -> &'static str {
+        "Import of a module known to bloat bundles, with a lighter alternative"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Bundle
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for item in &ctx.module.body {
+            let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else { continue };
+            // type-only imports are erased from the bundle
+            if import.type_only {
+                continue;
+            }
+            let source = import.src.value.to_string();
+            if let Some(advice) = self.advice.get(&source) {
+                findings.push(finding_at(ctx.lines, import.span, FindingKind::HeavyDependency { source, advice: advice.clone() }));
+            }
+        }
+        findings
+    }
+}
+
+/// Array methods that change the array in place
+const MUTATING_METHODS: &[&str] = &["push", "pop", "shift", "unshift", "splice", "sort", "reverse", "fill", "copyWithin"];
+
+/// Names bound by a pattern
+#[derive(Default)]
+struct BoundNames(Vec<String>);
+
+impl Visit for BoundNames {
+    fn visit_binding_ident(&mut self, id: &BindingIdent) {
+        self.0.push(id.id.sym.to_string());
+    }
+    // default values are expressions, not bindings
+    fn visit_expr(&mut self, _: &Expr) {}
+}
+
+/// `x` of each `const [x, setX] = useState(..)` / `useReducer(..)` in a component
+#[derive(Default)]
+struct StateValues(Vec<String>);
+
+impl Visit for StateValues {
+    fn visit_var_declarator(&mut self, d: &VarDeclarator) {
+        if let (Pat::Array(arr), Some(Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }))) = (&d.name, d.init.as_deref()) {
+            if matches!(hook_name(callee).as_deref(), Some("useState") | Some("useReducer")) {
+                if let Some(Some(Pat::Ident(state))) = arr.elems.first() {
+                    self.0.push(state.sym.to_string());
+                }
+            }
+        }
+        d.visit_children_with(self);
+    }
+}
+
+/// `props.items` for a member chain on a plain identifier, with that identifier
+fn member_path(expr: &Expr) -> Option<(String, String)> {
+    match expr {
+        Expr::Ident(id) => Some((id.sym.to_string(), id.sym.to_string())),
+        Expr::Member(m) => {
+            let (root, path) = member_path(&m.obj)?;
+            let path = match &m.prop {
+                MemberProp::Ident(p) => format!("{}.{}", path, p.sym),
+                _ => format!("{}[..]", path),
+            };
+            Some((root, path))
+        }
+        Expr::Paren(p) => member_path(&p.expr),
+        _ => None,
+    }
+}
+
+struct MutationScan<'a> {
+    lines: &'a LineIndex,
+    /// Prop and state bindings of the component
+    roots: Vec<String>,
+    findings: Vec<Finding>,
+}
+
+impl MutationScan<'_> {
+    fn check(&mut self, target: &Expr, span: Span) {
+        let Some((root, path)) = member_path(target) else { return };
+        // `someRef.current = ..` is how refs are meant to be written
+        if self.roots.contains(&root) && !path.ends_with(".current") {
+            self.findings.push(finding_at(self.lines, span, FindingKind::DirectMutation { target: path }));
+        }
+    }
+
+    /// A nested function whose params rebind a root refers to something else under that name
+    fn shadows(&self, params: &[&Pat]) -> bool {
+        let mut names = BoundNames::default();
+        params.iter().for_each(|p| p.visit_with(&mut names));
+        names.0.iter().any(|n| self.roots.contains(n))
+    }
+}
+
+impl Visit for MutationScan<'_> {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            if let Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(method), .. }) = &**callee {
+                if MUTATING_METHODS.contains(&method.sym.as_ref()) {
+                    self.check(obj, call.span);
+                }
+            }
+        }
+        call.visit_children_with(self);
+    }
+    fn visit_assign_expr(&mut self, assign: &AssignExpr) {
+        if let AssignTarget::Simple(SimpleAssignTarget::Member(m)) = &assign.left {
+            self.check(&Expr::Member(m.clone()), assign.span);
+        }
+        assign.visit_children_with(self);
+    }
+    fn visit_update_expr(&mut self, update: &UpdateExpr) {
+        if matches!(&*update.arg, Expr::Member(_)) {
+            self.check(&update.arg, update.span);
+        }
+        update.visit_children_with(self);
+    }
+    fn visit_unary_expr(&mut self, unary: &UnaryExpr) {
+        if unary.op == UnaryOp::Delete {
+            self.check(&unary.arg, unary.span);
+        }
+        unary.visit_children_with(self);
+    }
+    fn visit_arrow_expr(&mut self, a: &ArrowExpr) {
+        if !self.shadows(&a.params.iter().collect::<Vec<_>>()) {
+            a.visit_children_with(self);
+        }
+    }
+    fn visit_function(&mut self, f: &Function) {
+        if !self.shadows(&f.params.iter().map(|p| &p.pat).collect::<Vec<_>>()) {
+            f.visit_children_with(self);
+        }
+    }
+}
+
+/// `FindingKind::DirectMutation`: in-place array methods, assignments, `++`/`--` and
+/// `delete` on a member of a prop or `useState`/`useReducer` value, render path and
+/// handlers alike
+pub struct DirectMutationRule;
+
+impl Rule for DirectMutationRule {
+    fn name(&self) -> &'static str {
+        "DirectMutation"
+    }
+    fn description(&self) -> &'static str {
+        "Prop or state value changed in place, which React can't see"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for c in component_fns(ctx.module) {
+            let mut roots = BoundNames::default();
+            c.props.visit_with(&mut roots);
+            let mut state = StateValues::default();
+            let mut scan = MutationScan { lines: ctx.lines, roots: Vec::new(), findings: Vec::new() };
+            match c.body {
+                RenderBody::Block(b) => {
+                    b.visit_with(&mut state);
+                    scan.roots = roots.0.into_iter().chain(state.0).collect();
+                    b.visit_with(&mut scan);
+                }
+                RenderBody::Expr(e) => {
+                    scan.roots = roots.0;
+                    e.visit_with(&mut scan);
+                }
+            }
+            findings.extend(scan.findings);
+        }
+        findings
+    }
+}
+
+/// `(x, setX)` of each top-level `const [x, setX] = useState(..)` in a component body
+fn state_pairs(body: &BlockStmt) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    for stmt in &body.stmts {
+        let Stmt::Decl(Decl::Var(var)) = stmt else { continue };
+        for d in &var.decls {
+            let (Pat::Array(arr), Some(Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }))) = (&d.name, d.init.as_deref()) else { continue };
+            if hook_name(callee).as_deref() != Some("useState") {
+                continue;
+            }
+            if let (Some(Some(Pat::Ident(state))), Some(Some(Pat::Ident(setter)))) = (arr.elems.first(), arr.elems.get(1)) {
+                pairs.push((state.sym.to_string(), setter.sym.to_string()));
+            }
+        }
+    }
+    pairs
+}
+
+/// Setter calls an effect makes on every run: top-level statements of its body, not
+/// under an `if`, a loop or a nested callback
+fn unconditional_calls(callback: &Expr) -> Vec<&CallExpr> {
+    fn call_of(expr: &Expr) -> Option<&CallExpr> {
+        match expr {
+            Expr::Call(c) => Some(c),
+            Expr::Paren(p) => call_of(&p.expr),
+            _ => None,
+        }
+    }
+    let body = match callback {
+        Expr::Arrow(a) => match &*a.body {
+            BlockStmtOrExpr::Expr(e) => return call_of(e).into_iter().collect(),
+            BlockStmtOrExpr::BlockStmt(b) => b,
+        },
+        Expr::Fn(f) => match &f.function.body {
+            Some(b) => b,
+            None => return Vec::new(),
+        },
+        _ => return Vec::new(),
+    };
+    let mut calls = Vec::new();
+    for stmt in &body.stmts {
+        match stmt {
+            Stmt::Expr(ExprStmt { expr, .. }) => calls.extend(call_of(expr)),
+            // an `if` may return early, which would guard everything after it
+            Stmt::Return(_) | Stmt::If(_) => break,
+            _ => {}
+        }
+    }
+    calls
+}
+
+/// `FindingKind::PotentialRenderLoop`: an effect that always calls `setX` while `x`, or a
+/// top-level value computed from `x`, is in its dependency array. Guarded calls (inside
+/// an `if`, after an early return, in a callback) are left alone.
+pub struct PotentialRenderLoopRule;
+
+impl Rule for PotentialRenderLoopRule {
+    fn name(&self) -> &'static str {
+        "PotentialRenderLoop"
+    }
+    fn description(&self) -> &'static str {
+        "Effect that always sets state it depends on: it can re-run forever"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for c in component_fns(ctx.module) {
+            let RenderBody::Block(body) = c.body else { continue };
+            let pairs = state_pairs(body);
+            if pairs.is_empty() {
+                continue;
+            }
+            // values computed from state at the top level: name -> state it reads
+            let mut derived: HashMap<String, String> = HashMap::new();
+            for stmt in &body.stmts {
+                let Stmt::Decl(Decl::Var(var)) = stmt else { continue };
+                for d in &var.decls {
+                    let (Pat::Ident(id), Some(init)) = (&d.name, &d.init) else { continue };
+                    let read = pairs.iter().find(|(state, _)| {
+                        let mut refs = NameRefs { name: state, count: 0 };
+                        init.visit_with(&mut refs);
+                        refs.count > 0
+                    });
+                    if let Some((state, _)) = read {
+                        derived.insert(id.id.sym.to_string(), state.clone());
+                    }
+                }
+            }
+            for stmt in &body.stmts {
+                let Stmt::Expr(ExprStmt { expr, .. }) = stmt else { continue };
+                let Expr::Call(effect) = &**expr else { continue };
+                let Callee::Expr(callee) = &effect.callee else { continue };
+                if !hook_name(callee).map(|h| EFFECT_HOOKS.contains(&h.as_str())).unwrap_or(false) {
+                    continue;
+                }
+                let (Some(callback), Some(Expr::Array(deps))) = (effect.args.first(), effect.args.get(1).map(|a| &*a.expr)) else { continue };
+                let dep_states: Vec<&str> = deps
+                    .elems
+                    .iter()
+                    .flatten()
+                    .filter_map(|d| member_path(&d.expr))
+                    .filter_map(|(root, _)| {
+                        pairs.iter().find(|(state, _)| *state == root).map(|(state, _)| state.as_str()).or_else(|| derived.get(&root).map(String::as_str))
+                    })
+                    .collect();
+                for call in unconditional_calls(&callback.expr) {
+                    let Callee::Expr(setter_expr) = &call.callee else { continue };
+                    let Expr::Ident(setter) = &**setter_expr else { continue };
+                    let linked = pairs.iter().any(|(state, s)| s == setter.sym.as_ref() && dep_states.contains(&state.as_str()));
+                    if linked {
+                        findings.push(finding_at(ctx.lines, call.span, FindingKind::PotentialRenderLoop { setter: setter.sym.to_string() }));
+                    }
+                }
+            }
+        }
+        findings
+    }
+}
+
+struct StyleClassScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl Visit for StyleClassScan<'_> {
+    fn visit_jsx_opening_element(&mut self, el: &JSXOpeningElement) {
+        let attr = |name: &str| {
+            el.attrs.iter().find_map(|a| match a {
+                JSXAttrOrSpread::JSXAttr(attr @ JSXAttr { name: JSXAttrName::Ident(n), .. }) if n.sym.as_ref() == name => Some(attr),
+                _ => None,
+            })
+        };
+        // only `style={{ ... }}` written inline says which properties it sets
+        let sets_style = match attr("style").and_then(|a| a.value.as_ref()) {
+            Some(JSXAttrValue::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(e), .. })) => matches!(&**e, Expr::Object(o) if !o.props.is_empty()),
+            _ => false,
+        };
+        if sets_style && attr("className").is_some() {
+            self.findings.push(finding_at(self.lines, el.span, FindingKind::StyleClassOverlap));
+        }
+        el.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::StyleClassOverlap`; reported at `info` severity since CSS can't be resolved
+pub struct StyleClassOverlapRule;
+
+impl Rule for StyleClassOverlapRule {
+    fn name(&self) -> &'static str {
+        "StyleClassOverlap"
+    }
+    fn description(&self) -> &'static str {
+        "Element with both `className` and inline `style`: which one wins is unclear"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Maintainability
+    }
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = StyleClassScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// `Array.from(x)`
+fn is_array_from(callee: &Expr) -> bool {
+    matches!(callee, Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(p), .. })
+        if p.sym.as_ref() == "from" && matches!(&**obj, Expr::Ident(o) if o.sym.as_ref() == "Array"))
+}
+
+struct CloneScan<'a> {
+    lines: &'a LineIndex,
+    /// Prop and state bindings of the component
+    roots: Vec<String>,
+    findings: Vec<Finding>,
+}
+
+impl CloneScan<'_> {
+    fn check(&mut self, source: &Expr, span: Span) {
+        if let Some((root, path)) = member_path(source) {
+            if self.roots.contains(&root) {
+                self.findings.push(finding_at(self.lines, span, FindingKind::CollectionCloneInRender { source: path }));
+            }
+        }
+    }
+}
+
+impl Visit for CloneScan<'_> {
+    fn visit_array_lit(&mut self, arr: &ArrayLit) {
+        let spread = arr.elems.iter().flatten().find(|e| e.spread.is_some());
+        if let Some(e) = spread {
+            self.check(&e.expr, arr.span);
+        }
+        arr.visit_children_with(self);
+    }
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let (Callee::Expr(callee), Some(arg)) = (&call.callee, call.args.first()) {
+            if arg.spread.is_none() && is_array_from(callee) {
+                self.check(&arg.expr, call.span);
+            }
+        }
+        call.visit_children_with(self);
+    }
+    // handlers, effects and `useMemo` callbacks don't run on every render
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+}
+
+/// `FindingKind::CollectionCloneInRender`: `[...items]` or `Array.from(items)` of a prop or
+/// `useState`/`useReducer` value on the render path
+pub struct CollectionCloneInRenderRule;
+
+impl Rule for CollectionCloneInRenderRule {
+    fn name(&self) -> &'static str {
+        "CollectionCloneInRender"
+    }
+    fn description(&self) -> &'static str {
+        "Prop or state collection copied every render, breaking memoization downstream"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for c in component_fns(ctx.module) {
+            let mut roots = BoundNames::default();
+            c.props.visit_with(&mut roots);
+            let mut scan = CloneScan { lines: ctx.lines, roots: roots.0, findings: Vec::new() };
+            match c.body {
+                RenderBody::Block(b) => {
+                    let mut state = StateValues::default();
+                    b.visit_with(&mut state);
+                    scan.roots.extend(state.0);
+                    b.visit_children_with(&mut scan);
+                }
+                RenderBody::Expr(e) => e.visit_with(&mut scan),
+            }
+            findings.extend(scan.findings);
+        }
+        findings
+    }
+}
+
+/// `(() => ..)()` or `(function () {..})()`, possibly parenthesized
+fn is_iife(expr: &Expr) -> bool {
+    fn is_fn(expr: &Expr) -> bool {
+        match expr {
+            Expr::Arrow(_) | Expr::Fn(_) => true,
+            Expr::Paren(p) => is_fn(&p.expr),
+            _ => false,
+        }
+    }
+    match expr {
+        Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }) => is_fn(callee),
+        Expr::Paren(p) => is_iife(&p.expr),
+        _ => false,
+    }
+}
+
+struct JsxIifeScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl Visit for JsxIifeScan<'_> {
+    fn visit_jsx_expr_container(&mut self, c: &JSXExprContainer) {
+        if let JSXExpr::Expr(e) = &c.expr {
+            if is_iife(e) {
+                self.findings.push(finding_at(self.lines, e.span(), FindingKind::JsxIife));
+            }
+        }
+        c.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::JsxIife`: an IIFE as a JSX child or attribute value
+pub struct JsxIifeRule;
+
+impl Rule for JsxIifeRule {
+    fn name(&self) -> &'static str {
+        "JsxIife"
+    }
+    fn description(&self) -> &'static str {
+        "Function created and invoked inline in JSX on every render"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = JsxIifeScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// `x` of each `const x = useRef(..)` in a component
+#[derive(Default)]
+struct RefBindings(Vec<String>);
+
+impl Visit for RefBindings {
+    fn visit_var_declarator(&mut self, d: &VarDeclarator) {
+        if let (Pat::Ident(id), Some(Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }))) = (&d.name, d.init.as_deref()) {
+            if hook_name(callee).as_deref() == Some("useRef") {
+                self.0.push(id.sym.to_string());
+            }
+        }
+        d.visit_children_with(self);
+    }
+}
+
+struct RefDepsScan<'a> {
+    lines: &'a LineIndex,
+    refs: Vec<String>,
+    findings: Vec<Finding>,
+}
+
+impl Visit for RefDepsScan<'_> {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            if let Some((hook, index)) = hook_name(callee).and_then(|h| DEPS_HOOKS.iter().find(|(name, _)| *name == h)) {
+                if let Some(Expr::Array(deps)) = call.args.get(*index).map(|a| &*a.expr) {
+                    for dep in deps.elems.iter().flatten().filter(|d| d.spread.is_none()) {
+                        if matches!(&*dep.expr, Expr::Ident(id) if self.refs.contains(&id.sym.to_string())) {
+                            self.findings.push(finding_at(self.lines, dep.expr.span(), FindingKind::RefInDeps { hook: hook.to_string() }));
+                        }
+                    }
+                }
+            }
+        }
+        call.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::RefInDeps`: a `useRef` result named in a deps array of the same component
+pub struct RefInDepsRule;
+
+impl Rule for RefInDepsRule {
+    fn name(&self) -> &'static str {
+        "RefInDeps"
+    }
+    fn description(&self) -> &'static str {
+        "Ref from `useRef` in a deps array: its identity never changes, so it never triggers a re-run"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for c in component_fns(ctx.module) {
+            let RenderBody::Block(body) = c.body else { continue };
+            let mut refs = RefBindings::default();
+            body.visit_with(&mut refs);
+            if refs.0.is_empty() {
+                continue;
+            }
+            let mut scan = RefDepsScan { lines: ctx.lines, refs: refs.0, findings: Vec::new() };
+            body.visit_with(&mut scan);
+            findings.extend(scan.findings);
+        }
+        findings
+    }
+}
+
+/// `FindingKind::WideProps`; reported at `info` severity, as a design signal
+pub struct WidePropsRule {
+    /// Components destructuring more props than this are reported
+    pub max_props: usize,
+}
+
+impl Default for WidePropsRule {
+    fn default() -> Self {
+        Self { max_props: 12 }
+    }
+}
+
+impl Rule for WidePropsRule {
+    fn name(&self) -> &'static str {
+        "WideProps"
+    }
+    fn description(&self) -> &'static str {
+        "Component destructuring more props than the configured limit"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Maintainability
+    }
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for c in component_fns(ctx.module) {
+            let count = prop_count(Some(c.props));
+            if count > self.max_props {
+                findings.push(finding_at(ctx.lines, c.props.span(), FindingKind::WideProps { component: c.name, count }));
+            }
+        }
+        findings
+    }
+}
+
+/// `styled.div`...``, `styled(Button)`...``, `styled.div.attrs(..)`...``, `styled.div({..})`:
+/// whether `expr` builds a styled component. `.withConfig(..)` sets the name itself.
+fn is_styled_component(expr: &Expr) -> bool {
+    fn rooted_in_styled(expr: &Expr) -> bool {
+        match expr {
+            Expr::Ident(id) => id.sym.as_ref() == "styled",
+            Expr::Member(MemberExpr { prop: MemberProp::Ident(p), .. }) if p.sym.as_ref() == "withConfig" => false,
+            Expr::Member(m) => rooted_in_styled(&m.obj),
+            Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }) => rooted_in_styled(callee),
+            Expr::Paren(p) => rooted_in_styled(&p.expr),
+            _ => false,
+        }
+    }
+    match expr {
+        Expr::TaggedTpl(t) => rooted_in_styled(&t.tag),
+        Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }) => !matches!(&**callee, Expr::Ident(_)) && rooted_in_styled(callee),
+        Expr::Paren(p) => is_styled_component(&p.expr),
+        _ => false,
+    }
+}
+
+#[derive(Default)]
+struct DisplayNames {
+    /// `const X = styled...`, with the binding's span
+    styled: Vec<(String, Span)>,
+    /// `X` of each `X.displayName = ..`
+    named: Vec<String>,
+}
+
+impl Visit for DisplayNames {
+    fn visit_var_declarator(&mut self, d: &VarDeclarator) {
+        if let (Pat::Ident(id), Some(init)) = (&d.name, &d.init) {
+            if is_styled_component(init) {
+                self.styled.push((id.sym.to_string(), id.id.span));
+            }
+        }
+        d.visit_children_with(self);
+    }
+    fn visit_assign_expr(&mut self, assign: &AssignExpr) {
+        if let AssignTarget::Simple(SimpleAssignTarget::Member(MemberExpr { obj, prop: MemberProp::Ident(p), .. })) = &assign.left {
+            if let (Expr::Ident(target), "displayName") = (&**obj, p.sym.as_ref()) {
+                self.named.push(target.sym.to_string());
+            }
+        }
+        assign.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::MissingDisplayName`: a styled component bound to a name that never gets a
+/// `displayName` in the same file
+pub struct MissingDisplayNameRule;
+
+impl Rule for MissingDisplayNameRule {
+    fn name(&self) -> &'static str {
+        "MissingDisplayName"
+    }
+    fn description(&self) -> &'static str {
+        "Styled component without a `displayName`: DevTools shows the tag name"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Maintainability
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut names = DisplayNames::default();
+        ctx.module.visit_with(&mut names);
+        names
+            .styled
+            .into_iter()
+            .filter(|(name, _)| !names.named.contains(name))
+            .map(|(component, span)| finding_at(ctx.lines, span, FindingKind::MissingDisplayName { component }))
+            .collect()
+    }
+}
+
+/// Keys of an object literal, or None when a spread or computed key makes them unknowable
+fn object_keys(obj: &ObjectLit) -> Option<Vec<String>> {
+    obj.props
+        .iter()
+        .map(|p| match p {
+            PropOrSpread::Prop(prop) => match &**prop {
+                Prop::Shorthand(id) => Some(id.sym.to_string()),
+                Prop::KeyValue(KeyValueProp { key, .. }) | Prop::Getter(GetterProp { key, .. }) | Prop::Method(MethodProp { key, .. }) => match key {
+                    PropName::Ident(k) => Some(k.sym.to_string()),
+                    PropName::Str(s) => Some(s.value.to_string()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            PropOrSpread::Spread(_) => None,
+        })
+        .collect()
+}
+
+/// `x` -> keys of each `const x = { .. }` in the module
+#[derive(Default)]
+struct ObjectBindings(HashMap<String, Vec<String>>);
+
+impl Visit for ObjectBindings {
+    fn visit_var_declarator(&mut self, d: &VarDeclarator) {
+        if let (Pat::Ident(id), Some(Expr::Object(obj))) = (&d.name, d.init.as_deref()) {
+            if let Some(keys) = object_keys(obj) {
+                self.0.insert(id.sym.to_string(), keys);
+            }
+        }
+        d.visit_children_with(self);
+    }
+}
+
+struct SpreadClobberScan<'a> {
+    lines: &'a LineIndex,
+    objects: HashMap<String, Vec<String>>,
+    findings: Vec<Finding>,
+}
+
+impl SpreadClobberScan<'_> {
+    /// Keys the spread is known to carry, or None when they can't be resolved
+    fn spread_keys(&self, expr: &Expr) -> Option<Vec<String>> {
+        match expr {
+            Expr::Object(obj) => object_keys(obj),
+            Expr::Ident(id) => self.objects.get(id.sym.as_ref()).cloned(),
+            Expr::Paren(p) => self.spread_keys(&p.expr),
+            _ => None,
+        }
+    }
+}
+
+impl Visit for SpreadClobberScan<'_> {
+    fn visit_jsx_opening_element(&mut self, el: &JSXOpeningElement) {
+        let spreads: Vec<Option<Vec<String>>> = el
+            .attrs
+            .iter()
+            .filter_map(|a| match a {
+                JSXAttrOrSpread::SpreadElement(spread) => Some(self.spread_keys(&spread.expr)),
+                _ => None,
+            })
+            .collect();
+        if !spreads.is_empty() {
+            for attr in &el.attrs {
+                let JSXAttrOrSpread::JSXAttr(JSXAttr { name: JSXAttrName::Ident(n), span, .. }) = attr else { continue };
+                let prop = n.sym.as_ref();
+                let collides = spreads.iter().any(|keys| match keys {
+                    Some(keys) => keys.iter().any(|k| k == prop),
+                    None => is_handler_prop(prop),
+                });
+                if collides {
+                    self.findings.push(finding_at(self.lines, *span, FindingKind::SpreadClobber { prop: prop.to_string() }));
+                }
+            }
+        }
+        el.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::SpreadClobber`: an explicit attribute next to a spread that may carry the
+/// same prop; which one wins depends on their order
+pub struct SpreadClobberRule;
+
+impl Rule for SpreadClobberRule {
+    fn name(&self) -> &'static str {
+        "SpreadClobber"
+    }
+    fn description(&self) -> &'static str {
+        "Explicit attribute next to a spread that may carry the same prop: order decides which wins"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut objects = ObjectBindings::default();
+        ctx.module.visit_with(&mut objects);
+        let mut scan = SpreadClobberScan { lines: ctx.lines, objects: objects.0, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// Class name helpers built for this; calling them in render is the recommended form
+const CLASS_NAME_HELPERS: &[&str] = &["clsx", "classnames", "classNames", "cx", "cn", "twMerge"];
+
+/// How a `className` value is computed, None when it's trivial
+#[derive(Clone)]
+enum ClassNameComputation {
+    Call(String),
+    /// `+` with a non-literal term
+    Concat,
+    /// `cond ? a : b` with no call or concatenation in a branch
+    Ternary,
+}
+
+fn class_name_computation(expr: &Expr) -> Option<ClassNameComputation> {
+    match expr {
+        Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }) => {
+            let (_, path) = member_path(callee).unwrap_or_else(|| (String::new(), "(..)".to_string()));
+            let helper = path.rsplit('.').next().is_some_and(|name| CLASS_NAME_HELPERS.contains(&name));
+            (!helper).then_some(ClassNameComputation::Call(path))
+        }
+        Expr::Bin(BinExpr { op: BinaryOp::Add, left, right, .. }) => {
+            let terms = [class_name_computation(left), class_name_computation(right)];
+            if let Some(call) = terms.into_iter().flatten().find(|t| matches!(t, ClassNameComputation::Call(_))) {
+                return Some(call);
+            }
+            let literal = |e: &Expr| matches!(e, Expr::Lit(Lit::Str(_))) || matches!(e, Expr::Tpl(t) if t.exprs.is_empty());
+            (!literal(left) || !literal(right)).then_some(ClassNameComputation::Concat)
+        }
+        Expr::Paren(p) => class_name_computation(&p.expr),
+        // a call in a branch is reported as the call, then a concatenation as one
+        Expr::Cond(c) => {
+            let branches: Vec<ClassNameComputation> = [&c.cons, &c.alt].into_iter().filter_map(|branch| class_name_computation(branch)).collect();
+            let call = branches.iter().find(|b| matches!(b, ClassNameComputation::Call(_)));
+            let concat = branches.iter().find(|b| matches!(b, ClassNameComputation::Concat));
+            Some(call.or(concat).cloned().unwrap_or(ClassNameComputation::Ternary))
+        }
+        _ => None,
+    }
+}
+
+struct ClassNameScan<'a> {
+    lines: &'a LineIndex,
+    findings: Vec<Finding>,
+}
+
+impl Visit for ClassNameScan<'_> {
+    fn visit_jsx_attr(&mut self, attr: &JSXAttr) {
+        if let (JSXAttrName::Ident(n), Some(JSXAttrValue::JSXExprContainer(JSXExprContainer { expr: JSXExpr::Expr(e), .. }))) = (&attr.name, &attr.value) {
+            if n.sym.as_ref() == "className" {
+                if let Some(computation) = class_name_computation(e) {
+                    let (callee, ternary) = match computation {
+                        ClassNameComputation::Call(callee) => (Some(callee), false),
+                        ClassNameComputation::Concat => (None, false),
+                        ClassNameComputation::Ternary => (None, true),
+                    };
+                    self.findings.push(finding_at(self.lines, e.span(), FindingKind::DynamicClassNameComputation { callee, ternary }));
+                }
+            }
+        }
+        attr.visit_children_with(self);
+    }
+}
+
+/// `FindingKind::DynamicClassNameComputation`; ternaries and concatenations are `info`, calls `warning`
+pub struct DynamicClassNameComputationRule;
+
+impl Rule for DynamicClassNameComputationRule {
+    fn name(&self) -> &'static str {
+        "DynamicClassNameComputation"
+    }
+    fn description(&self) -> &'static str {
+        "`className` picked by a ternary, or rebuilt by string concatenation or a function call, on every render; ternaries and concatenations are reported as `info`"
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let mut scan = ClassNameScan { lines: ctx.lines, findings: Vec::new() };
+        ctx.module.visit_with(&mut scan);
+        scan.findings
+    }
+}
+
+/// Names bound at the top level of the module: imports, variables, functions and classes
+fn module_bindings(module: &Module) -> Vec<String> {
+    let mut names = BoundNames(Vec::new());
+    for item in &module.body {
+        let decl = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if !import.type_only => {
+                for spec in &import.specifiers {
+                    let local = match spec {
+                        ImportSpecifier::Named(s) => &s.local,
+                        ImportSpecifier::Default(s) => &s.local,
+                        ImportSpecifier::Namespace(s) => &s.local,
+                    };
+                    names.0.push(local.sym.to_string());
+                }
+                continue;
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+            ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+            _ => continue,
+        };
+        match decl {
+            Decl::Var(var) => var.decls.iter().for_each(|d| d.name.visit_with(&mut names)),
+            Decl::Fn(f) => names.0.push(f.ident.sym.to_string()),
+            Decl::Class(c) => names.0.push(c.ident.sym.to_string()),
+            _ => {}
+        }
+    }
+    names.0
+}
+
+/// `BoundNames` with the span of each binding
+struct BindingSpans(Vec<(String, Span)>);
+
+impl Visit for BindingSpans {
+    fn visit_binding_ident(&mut self, id: &BindingIdent) {
+        self.0.push((id.id.sym.to_string(), id.id.span));
+    }
+    fn visit_expr(&mut self, _: &Expr) {}
+}
+
+/// `FindingKind::ShadowedBinding`: a component parameter or destructured prop named like a
+/// module-level binding and read in the component. Only the module scope is tracked.
+pub struct ShadowedBindingRule;
+
+impl Rule for ShadowedBindingRule {
+    fn name(&self) -> &'static str {
+        "ShadowedBinding"
+    }
+    fn description(&self) -> &'static str {
+        "Prop or parameter named like a module-level binding it hides from the component"
+    }
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+    fn check(&self, ctx: &FileContext) -> Vec<Finding> {
+        let outer = module_bindings(ctx.module);
+        let mut findings = Vec::new();
+        for c in component_fns(ctx.module) {
+            let mut params = BindingSpans(Vec::new());
+            c.props.visit_with(&mut params);
+            for (name, span) in params.0 {
+                if name == c.name || !outer.contains(&name) {
+                    continue;
+                }
+                let mut refs = NameRefs { name: &name, count: 0 };
+                match &c.body {
+                    RenderBody::Block(b) => b.visit_with(&mut refs),
+                    RenderBody::Expr(e) => e.visit_with(&mut refs),
+                }
+                if refs.count > 0 {
+                    findings.push(finding_at(ctx.lines, span, FindingKind::ShadowedBinding { name }));
+                }
+            }
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::extract::{extract_all_with_rules, ImportSourceKind};
+    use crate::redos::RedosIssue;
+
+    /// Findings of `rule` alone on `source`, parsed as TSX
+    fn check(rule: impl Rule + 'static, source: &str) -> Vec<Finding> {
+        let mut registry = RuleRegistry::empty();
+        registry.register(Box::new(rule));
+        let (_, _, _, findings) = extract_all_with_rules(source, "a.tsx", &registry).expect("test source parses");
+        findings
+    }
+
+    #[test]
+    fn test_set_state_in_loop() {
+        let source = "export function List({ items, setTotal }) {
+  const [count, setCount] = useState(0);
+  const onClick = () => {
+    for (const item of items) {
+      setCount(c => c + 1);
+      setTotal(t => t + item);
+    }
+  };
+  return <button onClick={onClick}>{count}</button>;
+}
+";
+        let setters: Vec<(usize, usize, String)> = check(SetStateInLoopRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::SetStateInLoop { setter } => (f.line, f.column, setter),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // a local `useState` setter and one passed down as a prop
+        assert_eq!(setters, vec![(5, 7, "setCount".to_string()), (6, 7, "setTotal".to_string())]);
+    }
+
+    #[test]
+    fn test_timers_in_loop_are_not_setters() {
+        let source = "export function List({ items }) {
+  const onClick = () => {
+    for (const item of items) {
+      setTimeout(() => console.log(item), 0);
+      setInterval(() => console.log(item), 100);
+    }
+  };
+  return <button onClick={onClick} />;
+}
+";
+        assert!(check(SetStateInLoopRule, source).is_empty());
+    }
+
+    #[test]
+    fn test_class_name_severity_by_computation() {
+        let source = r#"export function Row({ active, size }) {
+  return (
+    <>
+      <div className={active ? "on" : "off"} />
+      <div className={"btn " + size} />
+      <div className={active ? getClasses(size) : "off"} />
+      <div className={clsx("btn", active && "on")} />
+      <div className="static" />
+    </>
+  );
+}
+"#;
+        let found: Vec<(Option<String>, bool, Severity)> = check(DynamicClassNameComputationRule, source)
+            .into_iter()
+            .map(|f| {
+                let severity = f.kind.default_severity();
+                match f.kind {
+                    FindingKind::DynamicClassNameComputation { callee, ternary } => (callee, ternary, severity),
+                    other => panic!("unexpected {:?}", other),
+                }
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (None, true, Severity::Info),
+                (None, false, Severity::Info),
+                (Some("getClasses".to_string()), false, Severity::Warning),
+            ]
+        );
+        // the catalog shows the strongest of the two
+        assert_eq!(DynamicClassNameComputationRule.default_severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn test_unstable_children() {
+        let source = "export function App({ label }) {
+  return (
+    <div>
+      <Card><span /></Card>
+      <Menu.Item children={<b>{label}</b>} />
+      <Card>text</Card>
+      <section><p /></section>
+    </div>
+  );
+}
+";
+        let found: Vec<(usize, usize, Option<usize>, String)> = check(UnstableChildrenRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::UnstableChildren { component } => (f.line, f.column, f.end_line, component),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // static text and DOM parents are fine
+        assert_eq!(found, vec![(4, 7, None, "Card".to_string()), (5, 7, None, "Menu.Item".to_string())]);
+    }
+
+    #[test]
+    fn test_hook_after_early_return() {
+        let source = "export function App({ user }) {
+  const [open] = useState(false);
+  if (!user) {
+    return null;
+  }
+  const theme = React.useContext(Theme);
+  const onClick = () => useTrack(user);
+  return <div onClick={onClick}>{open}{theme}</div>;
+}
+";
+        let found: Vec<(usize, usize, String)> = check(HookAfterEarlyReturnRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::HookAfterEarlyReturn { hook } => (f.line, f.column, hook),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // `useState` runs before the return; `useTrack` belongs to the handler, not this render
+        assert_eq!(found, vec![(6, 17, "useContext".to_string())]);
+    }
+
+    #[test]
+    fn test_expensive_allocation() {
+        let source = "export function Search({ q, ids }) {
+  const re = new RegExp(q);
+  const seen = useMemo(() => new Set(ids), [ids]);
+  const now = new Date();
+  return <div>{ids.map(id => new Map([[id, re]]).size)}{seen.size}{now.getTime()}</div>;
+}
+";
+        let found: Vec<(usize, usize, String)> = check(ExpensiveAllocationRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::ExpensiveAllocation { ctor } => (f.line, f.column, ctor),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // the `useMemo` callback doesn't run every render, `.map` callbacks do
+        assert_eq!(found, vec![(2, 14, "RegExp".to_string()), (5, 30, "Map".to_string())]);
+    }
+
+    #[test]
+    fn test_quadratic_lookup() {
+        let source = "export function List({ items, selectedIds, tags, label }) {
+  return (
+    <ul>
+      {items.map(item => (
+        <li key={item.id}>
+          {selectedIds.includes(item.id) ? \"x\" : \"\"}
+          {tags.find(t => t === item.tag)}{tags.indexOf(item.tag)}
+          {label.includes(\"new\") && \"!\"}
+        </li>
+      ))}
+    </ul>
+  );
+}
+";
+        let found: Vec<(usize, usize, Option<usize>, Vec<String>)> = check(QuadraticLookupRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::QuadraticLookup { methods } => (f.line, f.column, f.end_line, methods),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // one finding per loop; `label.includes("new")` is a substring check
+        assert_eq!(found, vec![(4, 8, Some(10), vec!["includes".to_string(), "find".to_string(), "indexOf".to_string()])]);
+    }
+
+    #[test]
+    fn test_ambiguous_export() {
+        let source = "function Button() { return <button />; }
+export { Button };
+
+export default Button;
+";
+        let found: Vec<(usize, usize, String, Vec<usize>)> = check(AmbiguousExportRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::AmbiguousExport { name, lines } => (f.line, f.column, name, lines),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(found, vec![(2, 10, "Button".to_string(), vec![2, 4])]);
+        // a default export of its own is no duplicate
+        let miss = check(AmbiguousExportRule, "export function Icon() { return <i />; }\nexport default function Button() { return <button />; }\n");
+        assert!(miss.is_empty());
+    }
+
+    #[test]
+    fn test_conditional_remount() {
+        let source = "export function Pick({ a, b }) {
+  return (
+    <div>
+      {a ? <Input /> : <Select />}
+      {a ? <Input key=\"i\" /> : <Select key=\"s\" />}
+      {a ? <Input x={1} /> : <Input x={2} />}
+      {(b && <Input />) || <Select />}
+    </div>
+  );
+}
+";
+        let found: Vec<(usize, usize)> = check(ConditionalRemountRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::ConditionalRemount => (f.line, f.column),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // keyed branches and same-type branches keep their state
+        assert_eq!(found, vec![(4, 7), (7, 7)]);
+    }
+
+    #[test]
+    fn test_children_manipulation() {
+        let source = "import { Children, cloneElement } from \"react\";
+export function List({ children, active }) {
+  const count = Children.count(children);
+  return (
+    <ul data-count={count}>
+      {React.Children.map(children, c => cloneElement(c, { active }))}
+    </ul>
+  );
+}
+";
+        let found: Vec<(usize, usize, String)> = check(ChildrenManipulationRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::ChildrenManipulation { method } => (f.line, f.column, method),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (3, 17, "Children.count".to_string()),
+                (6, 8, "Children.map".to_string()),
+                (6, 42, "cloneElement".to_string()),
+            ]
+        );
+        assert!(check(ChildrenManipulationRule, "export function List({ children }) { return <ul>{children}</ul>; }").is_empty());
+    }
+
+    #[test]
+    fn test_async_effect_callback() {
+        let source = "export function Page({ id }) {
+  useEffect(async () => { await load(id); }, [id]);
+  React.useLayoutEffect(async function measure() {}, []);
+  useEffect(() => { load(id); }, [id]);
+  const data = useMemo(async () => fetchAll(), []);
+  return <div>{data}</div>;
+}
+";
+        let found: Vec<(usize, usize, String)> = check(AsyncEffectCallbackRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::AsyncEffectCallback { hook } => (f.line, f.column, hook),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // only effect hooks need a cleanup function back
+        assert_eq!(found, vec![(2, 3, "useEffect".to_string()), (3, 3, "useLayoutEffect".to_string())]);
+    }
+
+    #[test]
+    fn test_eager_state_init() {
+        let source = "export function Table({ id }) {
+  const [rows] = useState(loadRows(id));
+  const [sort] = React.useState((readSort()));
+  const [page] = useState(() => loadPage(id));
+  const [size] = useState(DEFAULT_SIZE);
+  return <div>{rows.length}{sort}{page}{size}</div>;
+}
+";
+        let found: Vec<(usize, usize)> = check(EagerStateInitRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::EagerStateInit => (f.line, f.column),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // lazy initializers and plain values run nothing per render
+        assert_eq!(found, vec![(2, 18), (3, 18)]);
+    }
+
+    #[test]
+    fn test_code_split_point() {
+        let source = "import { Page } from \"./Page\";
+const Settings = React.lazy(() => import(\"./Settings\"));
+export async function loadChart() {
+  return import(\"chart.js\");
+}
+";
+        let found: Vec<(usize, usize, String, ImportSourceKind, bool)> = check(CodeSplitPointRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::CodeSplitPoint { module, kind, lazy } => (f.line, f.column, module, kind, lazy),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // static imports aren't split points
+        assert_eq!(
+            found,
+            vec![
+                (2, 35, "./Settings".to_string(), ImportSourceKind::Relative, true),
+                (4, 10, "chart.js".to_string(), ImportSourceKind::External, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_static_inline_style() {
+        let source = "export function Box({ gap }) {
+  return (
+    <Panel style={{ margin: 0, padding: \"4px\", zIndex: -1 }}>
+      <div style={{ margin: gap }} />
+      <div style={{}} />
+      <div style={{ ...base, color: \"red\" }} />
+    </Panel>
+  );
+}
+";
+        let found: Vec<(usize, usize, String)> = check(StaticInlineStyleRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::StaticInlineStyle { element } => (f.line, f.column, element),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // variables, spreads and empty objects don't make a hoistable constant
+        assert_eq!(found, vec![(3, 12, "Panel".to_string())]);
+    }
+
+    #[test]
+    fn test_rest_spread_forwarding() {
+        let source = "export const Field = ({ label, ...rest }) => (
+  <label>
+    {label}
+    <Input {...rest} />
+    <input {...rest} />
+    <Input {...other} />
+  </label>
+);
+";
+        let found: Vec<(usize, usize, String, String)> = check(RestSpreadForwardingRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::RestSpreadForwarding { component, element } => (f.line, f.column, component, element),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // DOM elements aren't memoized, and only the rest binding of the props counts
+        assert_eq!(found, vec![(4, 13, "Field".to_string(), "Input".to_string())]);
+    }
+
+    #[test]
+    fn test_ref_prop_without_forward_ref() {
+        let source = "export function Input({ value, ref }) { return <input ref={ref} value={value} />; }
+export const Area = ({ ref: areaRef }) => <textarea ref={areaRef} />;
+function Select({ ref }) { return <select ref={ref} />; }
+export const Forwarded = forwardRef(Select);
+";
+        let found: Vec<(usize, usize, String)> = check(RefPropWithoutForwardRefRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::RefPropWithoutForwardRef { component } => (f.line, f.column, component),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // reported on the `ref` key, renamed or not; `Select` is wrapped by name
+        assert_eq!(found, vec![(1, 32, "Input".to_string()), (2, 24, "Area".to_string())]);
+    }
+
+    #[test]
+    fn test_comparator_missing_prop() {
+        let source = "interface RowProps {
+  item: Item;
+  label: string;
+  onSelect: () => void;
+  tags: string[];
+}
+function Row({ item, label, onSelect, tags, style = {} }: RowProps) {
+  return <div style={style} onClick={onSelect}>{item.name}{label}{tags}</div>;
+}
+export const MemoRow = memo(Row, (prev, next) => prev.item === next.item && prev.label === next.label);
+export const LooseRow = memo(Row, (prev, next) => shallowEqual(prev, next));
+";
+        let found: Vec<(usize, usize, String, String)> = check(ComparatorMissingPropRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::ComparatorMissingProp { component, prop } => (f.line, f.column, component, prop),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // primitives compare fine by value; a comparator handing the whole props off may check anything
+        assert_eq!(
+            found,
+            vec![
+                (10, 34, "Row".to_string(), "onSelect".to_string()),
+                (10, 34, "Row".to_string(), "tags".to_string()),
+                (10, 34, "Row".to_string(), "style".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_misused_dom_prop() {
+        let source = "export function Name({ key, label }) {
+  return <label for=\"name\" class=\"field\" htmlFor=\"x\">{label}{key}</label>;
+}
+";
+        let found: Vec<(usize, usize, String, String)> = check(MisusedDomPropRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::MisusedDomProp { name, suggestion } => (f.line, f.column, name, suggestion),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (2, 17, "for".to_string(), "htmlFor".to_string()),
+                (2, 28, "class".to_string(), "className".to_string()),
+                (1, 24, "key".to_string(), "a prop with another name, e.g. `id`".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fragment_in_map() {
+        let source = "export function List({ items }) {
+  return (
+    <ul>
+      {items.map(item => <><li>{item}</li></>)}
+      {items.map(function (item) {
+        return (
+          <>
+            <li>{item}</li>
+          </>
+        );
+      })}
+      {items.map(item => <><dt>{item}</dt><dd /></>)}
+    </ul>
+  );
+}
+";
+        let found: Vec<(usize, usize, Option<usize>)> = check(FragmentInMapRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::FragmentInMap => (f.line, f.column, f.end_line),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // a fragment grouping several elements is what fragments are for
+        assert_eq!(found, vec![(4, 26, None), (6, 16, Some(10))]);
+    }
+
+    #[test]
+    fn test_json_in_render() {
+        let source = "export function Debug({ data, raw }) {
+  const copy = JSON.parse(JSON.stringify(data));
+  const parsed = useMemo(() => JSON.parse(raw), [raw]);
+  return (
+    <Viewer value={JSON.stringify(copy)} onCopy={() => copy(JSON.stringify(parsed))}>
+      {JSON.stringify(parsed)}
+    </Viewer>
+  );
+}
+";
+        let found: Vec<(usize, usize, String, bool)> = check(JsonInRenderRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::JsonInRender { method, escapes } => (f.line, f.column, method, escapes),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // the nested call is one finding; memo callbacks and handlers don't run every render
+        assert_eq!(
+            found,
+            vec![
+                (2, 16, "parse".to_string(), false),
+                (5, 20, "stringify".to_string(), true),
+                (6, 8, "stringify".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deep_jsx_ternary() {
+        let source = "export function Status({ a, b, c }) {
+  return (
+    <div>
+      {a ? <A /> : b ? <B /> : c ? <C /> : null}
+      {a ? (b ? <A /> : <B />) : <C />}
+    </div>
+  );
+}
+";
+        let depths = |rule: DeepJsxTernaryRule| -> Vec<(usize, usize, usize)> {
+            check(rule, source)
+                .into_iter()
+                .map(|f| match f.kind {
+                    FindingKind::DeepJsxTernary { depth } => (f.line, f.column, depth),
+                    other => panic!("unexpected {:?}", other),
+                })
+                .collect()
+        };
+        assert_eq!(depths(DeepJsxTernaryRule::default()), vec![(4, 7, 3)]);
+        // nesting in either branch counts
+        assert_eq!(depths(DeepJsxTernaryRule { min_depth: 2 }), vec![(4, 7, 3), (5, 7, 2)]);
+    }
+
+    #[test]
+    fn test_non_function_handler() {
+        let source = "const SAVE = \"save()\";
+const handler = () => save();
+export function Save({ onDone }) {
+  return (
+    <form onSubmit={123} onReset={handler}>
+      <button onClick=\"save()\" onFocus={SAVE} onBlur={onDone} />
+    </form>
+  );
+}
+";
+        let found: Vec<(usize, usize, String)> = check(NonFunctionHandlerRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::NonFunctionHandler { prop } => (f.line, f.column, prop),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // consts bound to literals count as literals; props and functions may be callable
+        assert_eq!(
+            found,
+            vec![
+                (5, 11, "onSubmit".to_string()),
+                (6, 15, "onClick".to_string()),
+                (6, 32, "onFocus".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inconsistent_prop_type() {
+        let source = "export function Field({ error, hint }) {
+  if (error) {
+    return <Input hint={{ text: error }} size={2} />;
+  }
+  return loading ? <Input hint={undefined} size={3} /> : <Input hint={hint} size=\"lg\" />;
+}
+";
+        let found: Vec<(usize, usize, String)> = check(InconsistentPropTypeRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::InconsistentPropType { prop } => (f.line, f.column, prop),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // each prop once, at the first branch disagreeing with the first; `hint={hint}` isn't a literal
+        assert_eq!(found, vec![(5, 27, "hint".to_string()), (5, 77, "size".to_string())]);
+    }
+
+    #[test]
+    fn test_unused_memo() {
+        let source = "export function Cart({ items }) {
+  const total = useMemo(() => sum(items), [items]);
+  const onClear = useCallback(() => clear(), []);
+  const count = React.useMemo(() => items.length, [items]);
+  return <div title={String(count)} />;
+}
+";
+        let found: Vec<(usize, usize, String)> = check(UnusedMemoRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::UnusedMemo { name } => (f.line, f.column, name),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // reported on the binding
+        assert_eq!(found, vec![(2, 9, "total".to_string()), (3, 9, "onClear".to_string())]);
+    }
+
+    #[test]
+    fn test_literal_in_deps() {
+        let source = "export function Item({ id, api }, ref) {
+  useEffect(() => {}, [{ id }, id, () => api]);
+  useImperativeHandle(ref, () => ({}), [[id]]);
+  useMemo(() => [id], [id]);
+  return <div />;
+}
+";
+        let found: Vec<(usize, usize, String)> = check(LiteralInDepsRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::LiteralInDeps { hook } => (f.line, f.column, hook),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // only the deps argument counts, wherever the hook takes it
+        assert_eq!(
+            found,
+            vec![
+                (2, 24, "useEffect".to_string()),
+                (2, 36, "useEffect".to_string()),
+                (3, 41, "useImperativeHandle".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_heavy_dependency() {
+        let source = "import moment from \"moment\";
+import type { Moment } from \"moment\";
+import debounce from \"lodash/debounce\";
+import { chunk } from \"lodash\";
+";
+        let found = |rule: HeavyDependencyRule| -> Vec<(usize, usize, String, String)> {
+            check(rule, source)
+                .into_iter()
+                .map(|f| match f.kind {
+                    FindingKind::HeavyDependency { source, advice } => (f.line, f.column, source, advice),
+                    other => panic!("unexpected {:?}", other),
+                })
+                .collect()
+        };
+        // type-only imports are erased; `lodash/debounce` isn't `lodash`
+        let defaults = found(HeavyDependencyRule::default());
+        let sources: Vec<(usize, &str)> = defaults.iter().map(|(line, _, source, _)| (*line, source.as_str())).collect();
+        assert_eq!(sources, vec![(1, "moment"), (4, "lodash")]);
+        assert!(defaults[0].3.starts_with("use date-fns"));
+
+        let overrides = BTreeMap::from([("moment".to_string(), String::new()), ("lodash".to_string(), "use lodash-es".to_string())]);
+        assert_eq!(found(HeavyDependencyRule::with_overrides(&overrides)), vec![(4, 1, "lodash".to_string(), "use lodash-es".to_string())]);
+    }
+
+    #[test]
+    fn test_direct_mutation() {
+        let source = "export function List({ items, user, inputRef }) {
+  const [state, setState] = useState({ count: 0 });
+  items.push(1);
+  const onClick = () => {
+    state.count++;
+    delete user.draft;
+    inputRef.current = null;
+  };
+  const sorted = items.map(items => items.sort());
+  return <ul onClick={onClick}>{sorted}</ul>;
+}
+";
+        let found: Vec<(usize, usize, String)> = check(DirectMutationRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::DirectMutation { target } => (f.line, f.column, target),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // handlers count too; `.current` writes and shadowing callback params don't
+        assert_eq!(
+            found,
+            vec![
+                (3, 3, "items".to_string()),
+                (5, 5, "state.count".to_string()),
+                (6, 5, "user.draft".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_potential_render_loop() {
+        let source = "export function Counter({ step }) {
+  const [count, setCount] = useState(0);
+  const [label, setLabel] = useState(\"\");
+  const doubled = count * 2;
+  useEffect(() => { setCount(count + step); }, [count]);
+  useEffect(() => setCount(doubled), [doubled]);
+  useEffect(() => { if (count < 10) setCount(count + 1); }, [count]);
+  useEffect(() => { setLabel(\"x\"); }, [step]);
+  return <div>{count}{label}</div>;
+}
+";
+        let found: Vec<(usize, usize, String)> = check(PotentialRenderLoopRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::PotentialRenderLoop { setter } => (f.line, f.column, setter),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // a value derived from state links it too; guarded calls and unrelated deps don't loop
+        assert_eq!(found, vec![(5, 21, "setCount".to_string()), (6, 19, "setCount".to_string())]);
+    }
+
+    #[test]
+    fn test_style_class_overlap() {
+        let source = "export function Card({ style }) {
+  return (
+    <div className=\"card\" style={{ margin: 0 }}>
+      <span className=\"title\" style={style} />
+      <span className=\"body\" style={{}} />
+    </div>
+  );
+}
+";
+        let found: Vec<(usize, usize, Severity)> = check(StyleClassOverlapRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::StyleClassOverlap => (f.line, f.column, f.kind.default_severity()),
+                ref other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // a style passed through says nothing about which properties it sets
+        assert_eq!(found, vec![(3, 5, Severity::Info)]);
+        assert_eq!(StyleClassOverlapRule.default_severity(), Severity::Info);
+    }
+
+    #[test]
+    fn test_collection_clone_in_render() {
+        let source = "export function List({ items, data }) {
+  const [selected] = useState([]);
+  const sorted = [...items].sort();
+  const picked = Array.from(selected);
+  const rows = useMemo(() => [...data.rows], [data]);
+  const local = [...DEFAULTS];
+  return <ul onClick={() => send([...items])}>{sorted}{picked}{rows}{local}</ul>;
+}
+";
+        let found: Vec<(usize, usize, String)> = check(CollectionCloneInRenderRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::CollectionCloneInRender { source } => (f.line, f.column, source),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // memo callbacks and handlers are off the render path; module values aren't props or state
+        assert_eq!(found, vec![(3, 18, "items".to_string()), (4, 18, "selected".to_string())]);
+    }
+
+    #[test]
+    fn test_unused_prop() {
+        let source = "export function Card({ title, subtitle, size = 1, gap = size * 2, _legacy, style: { color }, ...rest }) {
+  return <h1 data-gap={gap}>{title}</h1>;
+}
+";
+        let found: Vec<(usize, usize, String, String)> = check(UnusedPropRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::UnusedProp { component, prop } => (f.line, f.column, component, prop),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // read by another prop's default; `_` props, nested patterns and the rest are skipped
+        assert_eq!(found, vec![(1, 31, "Card".to_string(), "subtitle".to_string())]);
+    }
+
+    #[test]
+    fn test_unstable_hook_arg() {
+        let source = "function useSearch(query, options = {}) {
+  return useQuery([query, options]);
+}
+export function User({ id }) {
+  const user = useFetch({ id }, id);
+  const [open] = useState({ id });
+  const found = useSearch(id, () => id);
+  return <div>{user.name}{open}{found}</div>;
+}
+";
+        let found: Vec<(usize, usize, String)> = check(UnstableHookArgRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::UnstableHookArg { hook } => (f.line, f.column, hook),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // React's own hooks are left to `LiteralInDeps`
+        assert_eq!(
+            found,
+            vec![
+                (1, 37, "useSearch".to_string()),
+                (2, 19, "useQuery".to_string()),
+                (5, 25, "useFetch".to_string()),
+                (7, 31, "useSearch".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_jsx_iife() {
+        let source = "export function Label({ items, label }) {
+  const text = (() => label.trim())();
+  return (
+    <div title={(function () { return label; })()}>
+      {(() => {
+        return items.map((i) => <b key={i}>{i}</b>);
+      })()}
+      {text}
+    </div>
+  );
+}
+";
+        let found: Vec<(usize, usize, Option<usize>)> = check(JsxIifeRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::JsxIife => (f.line, f.column, f.end_line),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // an IIFE outside JSX is computed once per render like any other expression
+        assert_eq!(found, vec![(4, 17, None), (5, 8, Some(7))]);
+    }
+
+    #[test]
+    fn test_ref_in_deps() {
+        let source = "export function Box({ open }) {
+  const ref = useRef(null);
+  const [size, setSize] = useState(0);
+  useEffect(() => ref.current.focus(), [open, ref]);
+  const measure = useCallback(() => setSize(ref.current.offsetWidth), [ref, size]);
+  useMemo(() => size * 2, [size]);
+  return <div ref={ref} onClick={measure} />;
+}
+";
+        let found: Vec<(usize, usize, String)> = check(RefInDepsRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::RefInDeps { hook } => (f.line, f.column, hook),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // `open` and `size` are ordinary deps
+        assert_eq!(found, vec![(4, 47, "useEffect".to_string()), (5, 72, "useCallback".to_string())]);
+    }
+
+    #[test]
+    fn test_wide_props() {
+        let source = "export function Form({ a, b, c, d }) {
+  return <form />;
+}
+export function Field({ a, b, c, ...rest }) {
+  return <input {...rest} />;
+}
+";
+        let found: Vec<(usize, usize, String, usize, Severity)> = check(WidePropsRule { max_props: 3 }, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::WideProps { ref component, count } => (f.line, f.column, component.clone(), count, f.kind.default_severity()),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // `...rest` isn't counted, so `Field` sits exactly at the limit
+        assert_eq!(found, vec![(1, 22, "Form".to_string(), 4, Severity::Info)]);
+        assert_eq!(WidePropsRule::default().default_severity(), Severity::Info);
+        assert!(check(WidePropsRule::default(), source).is_empty());
+    }
+
+    #[test]
+    fn test_missing_display_name() {
+        let source = "const Title = styled.h1`font-size: 2em;`;
+const Button = styled(Base).attrs({ type: \"button\" })`padding: 0;`;
+const Box = styled.div({ display: \"flex\" });
+const Named = styled.div.withConfig({ displayName: \"Named\" })`margin: 0;`;
+const Card = styled.section`border: 1px;`;
+Card.displayName = \"Card\";
+";
+        let found: Vec<(usize, usize, String)> = check(MissingDisplayNameRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::MissingDisplayName { component } => (f.line, f.column, component),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // `.withConfig` names the component itself, and `Card` gets one afterwards
+        assert_eq!(
+            found,
+            vec![(1, 7, "Title".to_string()), (2, 7, "Button".to_string()), (3, 7, "Box".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_spread_clobber() {
+        let source = "const defaults = { type: \"button\", label: \"Save\" };
+export function Save({ props, save }) {
+  return (
+    <div>
+      <Button {...props} onClick={save} label=\"x\" />
+      <Button {...defaults} type=\"submit\" onClick={save} />
+      <Button {...{ disabled: true }} disabled={false} />
+    </div>
+  );
+}
+";
+        let found: Vec<(usize, usize, String)> = check(SpreadClobberRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::SpreadClobber { prop } => (f.line, f.column, prop),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // an unknown spread only clobbers handlers; a known one, exactly its own keys
+        assert_eq!(
+            found,
+            vec![
+                (5, 26, "onClick".to_string()),
+                (6, 29, "type".to_string()),
+                (7, 39, "disabled".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shadowed_binding() {
+        let source = "import { format } from \"date-fns\";
+const theme = { color: \"red\" };
+export function Price({ format, theme, amount }) {
+  return <span>{format(amount)}</span>;
+}
+export const Total = (format) => <b>{format}</b>;
+";
+        let found: Vec<(usize, usize, String, Severity)> = check(ShadowedBindingRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::ShadowedBinding { ref name } => (f.line, f.column, name.clone(), f.kind.default_severity()),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // `theme` is shadowed but never read
+        assert_eq!(
+            found,
+            vec![
+                (3, 25, "format".to_string(), Severity::Info),
+                (6, 23, "format".to_string(), Severity::Info),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_large_union_prop() {
+        let source = "interface IconProps {
+  name: \"a\" | \"b\" | \"c\" | \"d\";
+  size: \"s\" | \"m\" | \"l\";
+}
+export function Icon({ name, size }: IconProps) { return <i className={name + size} />; }
+";
+        let found: Vec<(usize, usize, String, String, usize)> = check(LargeUnionPropRule { max_members: 3 }, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::LargeUnionProp { component, prop, members } => (f.line, f.column, component, prop, members),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // a union of exactly `max_members` is still fine
+        assert_eq!(found, vec![(2, 3, "Icon".to_string(), "name".to_string(), 4)]);
+        assert!(check(LargeUnionPropRule::default(), source).is_empty());
+    }
+
+    #[test]
+    fn test_unsafe_regex() {
+        let source = "export const re = /^(a+)+$/;
+export const safe = /^abc$/;
+export const built = new RegExp(\"(a|ab)*c\", \"g\");
+export const dynamic = new RegExp(pattern);
+export const unknown = /[^]+/;
+";
+        let found: Vec<_> = check(UnsafeRegexRule, source)
+            .into_iter()
+            .map(|f| match f.kind {
+                FindingKind::UnsafeRegex { pattern, issues, rewrite } => (f.line, f.column, pattern, issues, rewrite),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // a `RegExp` built from a variable can't be checked, nor syntax the analysis can't read
+        assert_eq!(
+            found,
+            vec![
+                (1, 19, "^(a+)+$".to_string(), vec![RedosIssue::NestedQuantifier], Some("^(a+)$".to_string())),
+                (3, 22, "(a|ab)*c".to_string(), vec![RedosIssue::OverlappingAlternation], None),
+            ]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::analyzer::rules::RuleRegistry;
 use crate::parser;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
@@ -35,3 +36,32 @@ pub fn traverse_ast(ast_json: String) -> Result<TraverseStats> {
     parser::traverse_ast(&ast, &mut counter);
     Ok(TraverseStats { nodes_visited: counter.0 })
 }
+
+/// `traverse_ast` counting only nodes of the given kinds (`NodeKind` names, e.g. "JsxElement")
+#[napi]
+pub fn traverse_ast_filtered(ast_json: String, kinds: Vec<String>) -> Result<TraverseStats> {
+    let ast: parser::AstNode = serde_json::from_str(&ast_json)
+        .map_err(|e| Error::from_reason(format!("invalid ast json: {}", e)))?;
+    let kinds = kinds
+        .iter()
+        .map(|k| serde_json::from_value::<parser::NodeKind>(serde_json::json!({ "type": k })))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| Error::from_reason(format!("unknown node kind: {}", e)))?;
+    let mut counter = Counter(0);
+    parser::traverse_ast_filtered(&ast, &mut counter, &kinds);
+    Ok(TraverseStats { nodes_visited: counter.0 })
+}
+
+/// Nodes of `source`, counted while parsing: no AST JSON is built or re-parsed
+#[napi]
+pub fn count_nodes(source: String, filename: String) -> Result<TraverseStats> {
+    let mut counter = Counter(0);
+    parser::parse_and_visit(&source, &filename, &mut counter).map_err(|err| Error::from_reason(format!("parse error: {}", err.0)))?;
+    Ok(TraverseStats { nodes_visited: counter.0 })
+}
+
+/// Every rule, opt-in ones included, as JSON: `[{ id, name, default_severity, description, category, enabled_by_default }]`
+#[napi]
+pub fn list_rules() -> Result<String> {
+    serde_json::to_string(&RuleRegistry::all().catalog()).map_err(|e| Error::from_reason(format!("serialize rules failed: {}", e)))
+}
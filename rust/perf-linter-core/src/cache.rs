@@ -1,9 +1,9 @@
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
-use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
 use std::fs;
-use std::hash::{Hash, Hasher};
-use std::path::{Path, PathBuf};
+use std::io;
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -14,10 +14,89 @@ pub struct CacheEntry<T> {
     pub content_hash: u64,
     /// Last modified timestamp
     pub modified_at: u64,
+    /// Lines in the source file
+    pub line_count: usize,
     /// The cached data (AST, metadata, etc.)
     pub data: T,
 }
 
+/// Per-file pointer into the content-addressed store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathEntry {
+    /// Content hash of the file when it was last stored or hit
+    pub content_hash: u64,
+    /// Last modified timestamp of this file's entry
+    pub modified_at: u64,
+    /// Lines in the source file
+    pub line_count: usize,
+}
+
+/// Metadata of a cached file, read without deserializing its payload
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheMeta {
+    pub content_hash: u64,
+    pub modified_at: u64,
+    pub line_count: usize,
+    /// Whether the entry was read from disk rather than the in-memory cache
+    pub on_disk: bool,
+}
+
+/// The whole cache directory as one file, so CI can save and restore a single artifact
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheArchive {
+    /// Version of the cache that exported it; entry names are only valid under it
+    version: String,
+    /// `.cache` file name -> contents
+    entries: BTreeMap<String, String>,
+}
+
+/// 64-bit FNV-1a over `parts`, each followed by a 0xff byte (never found in UTF-8) so
+/// `["ab", "c"]` and `["a", "bc"]` differ. Unlike `DefaultHasher`, whose output may change
+/// between Rust releases, it's fixed, so checksums and file names persist across builds.
+fn stable_hash(parts: &[&[u8]]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let mut hash = OFFSET_BASIS;
+    for byte in parts.iter().flat_map(|part| part.iter().chain([&0xff])) {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(PRIME);
+    }
+    hash
+}
+
+/// Hash the cache keys file content by; also used to spot saves that changed nothing
+pub fn content_hash(content: &str) -> u64 {
+    stable_hash(&[content.as_bytes()])
+}
+
+/// Cache file contents: the payload's `content_hash` in hex, a newline, then the JSON
+fn seal(json: &str) -> String {
+    format!("{:016x}\n{}", content_hash(json), json)
+}
+
+/// The JSON of a sealed cache file, or None when its checksum doesn't match
+fn unseal(raw: &str) -> Option<&str> {
+    let (checksum, json) = raw.split_once('\n')?;
+    (u64::from_str_radix(checksum, 16).ok()? == content_hash(json)).then_some(json)
+}
+
+/// Read a cache file written by `write_sealed`. A file that fails its checksum (torn
+/// write, disk error, edited by hand) is deleted and read as a miss.
+fn read_sealed(path: &Path) -> Option<String> {
+    let raw = fs::read_to_string(path).ok()?;
+    match unseal(&raw) {
+        Some(json) => Some(json.to_string()),
+        None => {
+            fs::remove_file(path).ok();
+            None
+        }
+    }
+}
+
+fn write_sealed(path: &Path, json: &str) {
+    fs::write(path, seal(json)).ok();
+}
+
 /// Incremental cache manager with persistent storage
 pub struct IncrementalCache<T>
 where
@@ -25,6 +104,12 @@ where
 {
     /// In-memory cache storage
     memory_cache: Arc<DashMap<String, CacheEntry<T>>>,
+    /// Share payloads between files with identical content
+    content_addressed: bool,
+    /// Path -> content hash pointers (content-addressed mode only)
+    path_index: Arc<DashMap<String, PathEntry>>,
+    /// Payloads keyed by content hash (content-addressed mode only)
+    content_cache: Arc<DashMap<u64, CacheEntry<T>>>,
     /// Path to the cache directory
     cache_dir: PathBuf,
     /// Cache version (invalidate when structure changes)
@@ -42,16 +127,27 @@ where
 
         Self {
             memory_cache: Arc::new(DashMap::new()),
+            content_addressed: false,
+            path_index: Arc::new(DashMap::new()),
+            content_cache: Arc::new(DashMap::new()),
             cache_dir,
             version: version.to_string(),
         }
     }
 
+    /// Create a cache that keys payloads by content hash, so files with identical
+    /// content (generated duplicates, copied fixtures) share a single entry.
+    /// Each path still keeps its own `PathEntry` pointing at the shared payload.
+    pub fn new_content_addressed(cache_dir: impl AsRef<Path>, version: &str) -> Self {
+        Self {
+            content_addressed: true,
+            ..Self::new(cache_dir, version)
+        }
+    }
+
     /// Calculate hash for file content
     fn hash_content(content: &str) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        content.hash(&mut hasher);
-        hasher.finish()
+        content_hash(content)
     }
 
     /// Get current timestamp in seconds
@@ -64,17 +160,24 @@ where
 
     /// Get cache file path for a source file
     fn get_cache_path(&self, file_path: &str) -> PathBuf {
-        let mut hasher = DefaultHasher::new();
-        file_path.hash(&mut hasher);
-        self.version.hash(&mut hasher);
-        let cache_key = hasher.finish();
+        let cache_key = stable_hash(&[file_path.as_bytes(), self.version.as_bytes()]);
         self.cache_dir.join(format!("{:x}.cache", cache_key))
     }
 
+    /// Get cache file path for a shared content-addressed payload
+    fn get_content_path(&self, content_hash: u64) -> PathBuf {
+        let cache_key = stable_hash(&[&content_hash.to_le_bytes(), self.version.as_bytes()]);
+        self.cache_dir.join(format!("content-{:x}.cache", cache_key))
+    }
+
     /// Try to get cached data for a file if still valid
     pub fn get(&self, file_path: &str, content: &str) -> Option<T> {
         let content_hash = Self::hash_content(content);
 
+        if self.content_addressed {
+            return self.get_by_content(file_path, content_hash);
+        }
+
         // Check memory cache first
         if let Some(entry) = self.memory_cache.get(file_path) {
             if entry.content_hash == content_hash {
@@ -84,7 +187,7 @@ where
 
         // Check disk cache
         let cache_path = self.get_cache_path(file_path);
-        if let Ok(cache_data) = fs::read_to_string(&cache_path) {
+        if let Some(cache_data) = read_sealed(&cache_path) {
             if let Ok(entry) = serde_json::from_str::<CacheEntry<T>>(&cache_data) {
                 if entry.content_hash == content_hash {
                     // Restore to memory cache
@@ -98,12 +201,117 @@ where
         None
     }
 
+    /// Content-addressed lookup: any file with the same content hits the shared payload
+    fn get_by_content(&self, file_path: &str, content_hash: u64) -> Option<T> {
+        let (data, line_count) = if let Some(entry) = self.content_cache.get(&content_hash) {
+            (entry.data.clone(), entry.line_count)
+        } else {
+            let cache_data = read_sealed(&self.get_content_path(content_hash))?;
+            let entry = serde_json::from_str::<CacheEntry<T>>(&cache_data).ok()?;
+            if entry.content_hash != content_hash {
+                return None;
+            }
+            let found = (entry.data.clone(), entry.line_count);
+            self.content_cache.insert(content_hash, entry);
+            found
+        };
+
+        // Point this path at the shared payload so its metadata stays recoverable
+        let known = self
+            .path_index
+            .get(file_path)
+            .map(|p| p.content_hash == content_hash)
+            .unwrap_or(false);
+        if !known {
+            self.set_path_entry(file_path, content_hash, line_count);
+        }
+
+        Some(data)
+    }
+
+    /// Record (in memory and on disk) which content a path currently points at
+    fn set_path_entry(&self, file_path: &str, content_hash: u64, line_count: usize) {
+        let entry = PathEntry {
+            content_hash,
+            modified_at: Self::current_timestamp(),
+            line_count,
+        };
+        if let Ok(json) = serde_json::to_string(&entry) {
+            write_sealed(&self.get_cache_path(file_path), &json);
+        }
+        self.path_index.insert(file_path.to_string(), entry);
+    }
+
+    /// Look up a file's cached hash, timestamp and line count without building the payload.
+    /// Doesn't validate against current content; compare `content_hash` yourself.
+    pub fn peek(&self, file_path: &str) -> Option<CacheMeta> {
+        let in_memory = if self.content_addressed {
+            self.path_index.get(file_path).map(|e| (e.content_hash, e.modified_at, e.line_count))
+        } else {
+            self.memory_cache.get(file_path).map(|e| (e.content_hash, e.modified_at, e.line_count))
+        };
+        if let Some((content_hash, modified_at, line_count)) = in_memory {
+            return Some(CacheMeta { content_hash, modified_at, line_count, on_disk: false });
+        }
+
+        // Both `CacheEntry` and `PathEntry` files carry these fields; `data` is skipped
+        let cache_data = read_sealed(&self.get_cache_path(file_path))?;
+        let entry = serde_json::from_str::<PathEntry>(&cache_data).ok()?;
+        Some(CacheMeta {
+            content_hash: entry.content_hash,
+            modified_at: entry.modified_at,
+            line_count: entry.line_count,
+            on_disk: true,
+        })
+    }
+
+    /// Whatever was last stored for `file_path`, without its current content to check
+    /// against; for files known to be unchanged, so they needn't be read
+    pub fn get_last(&self, file_path: &str) -> Option<T> {
+        let meta = self.peek(file_path)?;
+        if self.content_addressed {
+            return self.get_by_content(file_path, meta.content_hash);
+        }
+        if let Some(entry) = self.memory_cache.get(file_path) {
+            return Some(entry.data.clone());
+        }
+        let entry = serde_json::from_str::<CacheEntry<T>>(&read_sealed(&self.get_cache_path(file_path))?).ok()?;
+        self.memory_cache.insert(file_path.to_string(), entry.clone());
+        Some(entry.data)
+    }
+
+    /// Whether `content` differs from what is cached for `file_path` (or nothing is)
+    pub fn is_stale(&self, file_path: &str, content: &str) -> bool {
+        self.peek(file_path)
+            .map(|meta| meta.content_hash != Self::hash_content(content))
+            .unwrap_or(true)
+    }
+
     /// Store data in cache
     pub fn set(&self, file_path: &str, content: &str, data: T) {
         let content_hash = Self::hash_content(content);
+        let line_count = content.lines().count();
+        if self.content_addressed {
+            if !self.content_cache.contains_key(&content_hash) {
+                let entry = CacheEntry {
+                    content_hash,
+                    modified_at: Self::current_timestamp(),
+                    line_count,
+                    data,
+                };
+                if let Ok(json) = serde_json::to_string(&entry) {
+                    write_sealed(&self.get_content_path(content_hash), &json);
+                }
+                self.content_cache.insert(content_hash, entry);
+            }
+            self.set_path_entry(file_path, content_hash, line_count);
+            return;
+        }
+
         let entry = CacheEntry {
             content_hash,
             modified_at: Self::current_timestamp(),
+            line_count,
             data: data.clone(),
         };
 
@@ -114,21 +322,73 @@ where
         // Store on disk (async would be better, but keeping it simple)
         let cache_path = self.get_cache_path(file_path);
         if let Ok(json) = serde_json::to_string(&entry) {
-            fs::write(cache_path, json).ok();
+            write_sealed(&cache_path, &json);
         }
     }
 
     /// Clear all caches (memory and disk)
     pub fn clear(&self) {
         self.memory_cache.clear();
+        self.path_index.clear();
+        self.content_cache.clear();
         fs::remove_dir_all(&self.cache_dir).ok();
         fs::create_dir_all(&self.cache_dir).ok();
     }
 
+    /// Drop one file's entry (memory and its `.cache` on disk), e.g. after it was deleted.
+    /// Content-addressed payloads stay, since other paths may point at them.
+    pub fn clear_file(&self, file_path: &str) {
+        self.memory_cache.remove(file_path);
+        self.path_index.remove(file_path);
+        fs::remove_file(self.get_cache_path(file_path)).ok();
+    }
+
+    /// Write every `.cache` file of the cache directory into a single archive at `path`.
+    /// Returns the number of entries written.
+    pub fn export_archive(&self, path: impl AsRef<Path>) -> io::Result<usize> {
+        let mut entries = BTreeMap::new();
+        for dir_entry in fs::read_dir(&self.cache_dir)? {
+            let file = dir_entry?.path();
+            let Some(name) = file.file_name().and_then(|n| n.to_str()) else { continue };
+            if name.ends_with(".cache") {
+                entries.insert(name.to_string(), fs::read_to_string(&file)?);
+            }
+        }
+        let archive = CacheArchive { version: self.version.clone(), entries };
+        fs::write(path, serde_json::to_string(&archive)?)?;
+        Ok(archive.entries.len())
+    }
+
+    /// Restore an archive made by `export_archive` into the cache directory and return how
+    /// many entries were written; names that aren't a plain file name are skipped.
+    /// Fails with `InvalidData` when it was exported under another cache version.
+    pub fn import_archive(&self, path: impl AsRef<Path>) -> io::Result<usize> {
+        let archive: CacheArchive = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if archive.version != self.version {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("cache archive version {} does not match {}", archive.version, self.version),
+            ));
+        }
+        fs::create_dir_all(&self.cache_dir)?;
+        let mut written = 0;
+        for (name, contents) in &archive.entries {
+            // Names come from the archive; never write outside the cache directory
+            let mut components = Path::new(name).components();
+            if !matches!((components.next(), components.next()), (Some(Component::Normal(_)), None)) {
+                continue;
+            }
+            fs::write(self.cache_dir.join(name), contents)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
     /// Get cache statistics
     pub fn stats(&self) -> CacheStats {
         CacheStats {
-            memory_entries: self.memory_cache.len(),
+            memory_entries: self.memory_cache.len() + self.path_index.len(),
+            content_entries: self.content_cache.len(),
             cache_dir: self.cache_dir.display().to_string(),
         }
     }
@@ -137,6 +397,8 @@ where
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CacheStats {
     pub memory_entries: usize,
+    /// Distinct payloads held by the content-addressed store
+    pub content_entries: usize,
     pub cache_dir: String,
 }
 
@@ -170,6 +432,20 @@ mod tests {
         fs::remove_dir_all(&temp_dir).ok();
     }
 
+    #[test]
+    fn test_get_last_without_content() {
+        let temp_dir = std::env::temp_dir().join("perf_linter_last_cache");
+        {
+            let cache = IncrementalCache::<String>::new_content_addressed(&temp_dir, "1.0");
+            cache.set("a.ts", "const a = 1;", "a_ast".to_string());
+        }
+        let cache = IncrementalCache::<String>::new_content_addressed(&temp_dir, "1.0");
+        assert_eq!(cache.get_last("a.ts"), Some("a_ast".to_string()));
+        assert!(cache.get_last("b.ts").is_none());
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
     #[test]
     fn test_persistent_cache() {
         let temp_dir = std::env::temp_dir().join("perf_linter_persist_cache");
@@ -192,4 +468,179 @@ mod tests {
         // Cleanup
         fs::remove_dir_all(&temp_dir).ok();
     }
+
+    #[test]
+    fn test_content_addressed_dedup() {
+        let temp_dir = std::env::temp_dir().join("perf_linter_content_cache");
+        let content = "export const z = 3;";
+        let data = "shared_ast".to_string();
+
+        {
+            let cache = IncrementalCache::<String>::new_content_addressed(&temp_dir, "1.0");
+            cache.set("a.ts", content, data.clone());
+
+            // Same content under another path hits the shared entry
+            assert_eq!(cache.get("b.ts", content), Some(data.clone()));
+            let stats = cache.stats();
+            assert_eq!(stats.memory_entries, 2);
+            assert_eq!(stats.content_entries, 1);
+        }
+
+        // Shared payload and per-path pointers survive a restart
+        {
+            let cache = IncrementalCache::<String>::new_content_addressed(&temp_dir, "1.0");
+            assert_eq!(cache.get("c.ts", content), Some(data));
+            assert!(cache.get("c.ts", "export const z = 4;").is_none());
+        }
+
+        // Cleanup
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_peek_metadata() {
+        let temp_dir = std::env::temp_dir().join("perf_linter_peek_cache");
+        let content = "const w = 5;";
+
+        {
+            let cache = IncrementalCache::<String>::new(&temp_dir, "1.0");
+            assert!(cache.peek("w.ts").is_none());
+            cache.set("w.ts", content, "ast".to_string());
+            let meta = cache.peek("w.ts").unwrap();
+            assert!(!meta.on_disk);
+            assert!(!cache.is_stale("w.ts", content));
+            assert!(cache.is_stale("w.ts", "const w = 6;"));
+        }
+
+        // Fresh instance reads only the metadata from disk
+        {
+            let cache = IncrementalCache::<String>::new(&temp_dir, "1.0");
+            assert!(cache.peek("w.ts").unwrap().on_disk);
+            assert!(!cache.is_stale("w.ts", content));
+        }
+
+        // Content-addressed path pointers peek the same way
+        {
+            let cache = IncrementalCache::<String>::new_content_addressed(&temp_dir, "2.0");
+            cache.set("v.ts", content, "ast".to_string());
+            assert!(!cache.is_stale("v.ts", content));
+        }
+
+        // Cleanup
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_clear_file() {
+        let temp_dir = std::env::temp_dir().join("perf_linter_clear_file_cache");
+        let content = "const u = 7;";
+
+        {
+            let cache = IncrementalCache::<String>::new(&temp_dir, "1.0");
+            cache.set("keep.ts", content, "kept".to_string());
+            cache.set("drop.ts", content, "dropped".to_string());
+            cache.clear_file("drop.ts");
+            assert!(cache.get("drop.ts", content).is_none());
+            assert_eq!(cache.get("keep.ts", content), Some("kept".to_string()));
+        }
+
+        // Removed from disk too, while the other file survives a restart
+        {
+            let cache = IncrementalCache::<String>::new(&temp_dir, "1.0");
+            assert!(cache.peek("drop.ts").is_none());
+            assert_eq!(cache.get("keep.ts", content), Some("kept".to_string()));
+        }
+
+        // Content-addressed: the shared payload still serves other paths
+        {
+            let cache = IncrementalCache::<String>::new_content_addressed(&temp_dir, "2.0");
+            cache.set("a.ts", content, "shared".to_string());
+            cache.set("b.ts", content, "shared".to_string());
+            cache.clear_file("a.ts");
+            assert!(cache.peek("a.ts").is_none());
+            assert_eq!(cache.get("b.ts", content), Some("shared".to_string()));
+        }
+
+        // Cleanup
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_corrupted_file_is_a_miss() {
+        let temp_dir = std::env::temp_dir().join("perf_linter_corrupt_cache");
+        let content = "const s = 9;";
+
+        let cache_path = {
+            let cache = IncrementalCache::<String>::new(&temp_dir, "1.0");
+            cache.clear();
+            cache.set("s.ts", content, "original".to_string());
+            cache.get_cache_path("s.ts")
+        };
+        // Same shape, different data: parses fine but fails the checksum
+        let raw = fs::read_to_string(&cache_path).unwrap();
+        fs::write(&cache_path, raw.replace("original", "tampered")).unwrap();
+
+        {
+            let cache = IncrementalCache::<String>::new(&temp_dir, "1.0");
+            assert!(cache.get("s.ts", content).is_none());
+            assert!(!cache_path.exists());
+        }
+
+        // Cleanup
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_checksums_are_fixed_across_builds() {
+        // FNV-1a values; a change here orphans every cache already on disk
+        assert_eq!(content_hash("const x = 1;"), 0x7291_8754_4283_8dea);
+        assert_eq!(seal("{}"), "c736581983dda06e\n{}");
+        assert_eq!(unseal("c736581983dda06e\n{}"), Some("{}"));
+        assert_ne!(stable_hash(&[b"ab", b"c"]), stable_hash(&[b"a", b"bc"]));
+    }
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let source_dir = std::env::temp_dir().join("perf_linter_archive_source");
+        let restored_dir = std::env::temp_dir().join("perf_linter_archive_restored");
+        let archive = std::env::temp_dir().join("perf_linter_cache_archive.json");
+        let content = "const t = 8;";
+
+        {
+            let cache = IncrementalCache::<String>::new_content_addressed(&source_dir, "1.0");
+            cache.clear();
+            cache.set("t.ts", content, "archived".to_string());
+            assert_eq!(cache.export_archive(&archive).unwrap(), 2);
+        }
+
+        // A cold cache elsewhere serves the archived entry after import
+        {
+            let cache = IncrementalCache::<String>::new_content_addressed(&restored_dir, "1.0");
+            assert_eq!(cache.import_archive(&archive).unwrap(), 2);
+            assert_eq!(cache.get("t.ts", content), Some("archived".to_string()));
+        }
+
+        // Names that would land outside the cache directory are skipped, not counted
+        {
+            let unsafe_archive = std::env::temp_dir().join("perf_linter_cache_archive_unsafe.json");
+            let entries = r#"{ "version": "1.0", "entries": { "ok.cache": "{}", "../escape.cache": "{}", "..": "{}", "/abs.cache": "{}" } }"#;
+            fs::write(&unsafe_archive, entries).unwrap();
+            let cache = IncrementalCache::<String>::new(&restored_dir, "1.0");
+            assert_eq!(cache.import_archive(&unsafe_archive).unwrap(), 1);
+            assert!(!restored_dir.parent().unwrap().join("escape.cache").exists());
+            fs::remove_file(&unsafe_archive).ok();
+        }
+
+        // Another version refuses it
+        {
+            let cache = IncrementalCache::<String>::new(&restored_dir, "2.0");
+            let err = cache.import_archive(&archive).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+
+        // Cleanup
+        fs::remove_dir_all(&source_dir).ok();
+        fs::remove_dir_all(&restored_dir).ok();
+        fs::remove_file(&archive).ok();
+    }
 }
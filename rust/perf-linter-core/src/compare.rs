@@ -0,0 +1,187 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+/// The parts of an `index` graph or `lint` report a comparison reads; other fields are ignored
+#[derive(Debug, Default, Deserialize)]
+struct Run {
+    #[serde(default, deserialize_with = "findings_by_file")]
+    findings: BTreeMap<String, Vec<FindingRef>>,
+    /// Only present in `index` output
+    #[serde(default)]
+    component_counts: Option<ComponentCounts>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FindingRef {
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+/// A finding of `lint --group-by none`, which lists findings flat with their `file`
+#[derive(Debug, Deserialize)]
+struct FlatFindingRef {
+    file: String,
+    #[serde(flatten)]
+    finding: FindingRef,
+}
+
+/// `findings` keyed by file, or the flat list `--group-by none` prints, grouped by file
+fn findings_by_file<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<String, Vec<FindingRef>>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Findings {
+        ByFile(BTreeMap<String, Vec<FindingRef>>),
+        Flat(Vec<FlatFindingRef>),
+    }
+    Ok(match Findings::deserialize(deserializer)? {
+        Findings::ByFile(by_file) => by_file,
+        Findings::Flat(flat) => {
+            let mut by_file: BTreeMap<String, Vec<FindingRef>> = BTreeMap::new();
+            for f in flat {
+                by_file.entry(f.file).or_default().push(f.finding);
+            }
+            by_file
+        }
+    })
+}
+
+/// Totals over every component in every file, not just the one per file `components` shows
+#[derive(Debug, Deserialize)]
+struct ComponentCounts {
+    components: usize,
+    memoized_components: usize,
+}
+
+/// Finding counts of one `type` in both runs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleDelta {
+    pub before: usize,
+    pub after: usize,
+    pub fixed: usize,
+    pub introduced: usize,
+}
+
+/// Share of memoized components in both runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RatioDelta {
+    pub before: f64,
+    pub after: f64,
+    pub change: f64,
+}
+
+/// What changed between two serialized runs; see `compare`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comparison {
+    pub before_total: usize,
+    pub after_total: usize,
+    pub fixed: usize,
+    pub introduced: usize,
+    /// Per finding `type`, for every type present in either run
+    pub by_rule: BTreeMap<String, RuleDelta>,
+    /// Only when both runs are `index` graphs, which carry components
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memoized_ratio: Option<RatioDelta>,
+}
+
+/// Compare two `index`/`lint` outputs. Line numbers shift between runs, so findings are
+/// matched by count per file and type: fewer of a type in a file counts as fixed, more
+/// as introduced.
+pub fn compare(before: &str, after: &str) -> Result<Comparison, serde_json::Error> {
+    let before: Run = serde_json::from_str(before)?;
+    let after: Run = serde_json::from_str(after)?;
+    let (before_counts, after_counts) = (counts(&before), counts(&after));
+
+    let mut by_rule: BTreeMap<String, RuleDelta> = BTreeMap::new();
+    for (key, &n) in &before_counts {
+        let m = after_counts.get(key).copied().unwrap_or(0);
+        let delta = by_rule.entry(key.1.clone()).or_default();
+        delta.before += n;
+        delta.fixed += n.saturating_sub(m);
+    }
+    for (key, &m) in &after_counts {
+        let n = before_counts.get(key).copied().unwrap_or(0);
+        let delta = by_rule.entry(key.1.clone()).or_default();
+        delta.after += m;
+        delta.introduced += m.saturating_sub(n);
+    }
+
+    let memoized_ratio = match (memoized_ratio(&before), memoized_ratio(&after)) {
+        (Some(before), Some(after)) => Some(RatioDelta { before, after, change: after - before }),
+        _ => None,
+    };
+    Ok(Comparison {
+        before_total: before_counts.values().sum(),
+        after_total: after_counts.values().sum(),
+        fixed: by_rule.values().map(|d| d.fixed).sum(),
+        introduced: by_rule.values().map(|d| d.introduced).sum(),
+        by_rule,
+        memoized_ratio,
+    })
+}
+
+/// (file, type) -> findings
+fn counts(run: &Run) -> BTreeMap<(String, String), usize> {
+    let mut counts = BTreeMap::new();
+    for (file, findings) in &run.findings {
+        for f in findings {
+            *counts.entry((file.clone(), f.kind.clone())).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+fn memoized_ratio(run: &Run) -> Option<f64> {
+    let counts = run.component_counts.as_ref()?;
+    if counts.components == 0 {
+        return Some(0.0);
+    }
+    Some(counts.memoized_components as f64 / counts.components as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_and_introduced_per_rule() {
+        let before = r#"{
+            "component_counts": { "components": 4, "memoized_components": 1 },
+            "findings": {
+                "a.tsx": [{ "type": "JsxIife", "line": 3 }, { "type": "JsxIife", "line": 9 }],
+                "b.tsx": [{ "type": "EagerStateInit", "line": 1 }]
+            }
+        }"#;
+        let after = r#"{
+            "component_counts": { "components": 4, "memoized_components": 3 },
+            "findings": {
+                "a.tsx": [{ "type": "JsxIife", "line": 4 }, { "type": "UnusedMemo", "line": 2 }]
+            }
+        }"#;
+        let cmp = compare(before, after).unwrap();
+        assert_eq!((cmp.before_total, cmp.after_total, cmp.fixed, cmp.introduced), (3, 2, 2, 1));
+        assert_eq!(cmp.by_rule["JsxIife"], RuleDelta { before: 2, after: 1, fixed: 1, introduced: 0 });
+        assert_eq!(cmp.by_rule["EagerStateInit"].fixed, 1);
+        assert_eq!(cmp.by_rule["UnusedMemo"].introduced, 1);
+        assert_eq!(cmp.memoized_ratio.unwrap().change, 0.5);
+    }
+
+    #[test]
+    fn test_lint_reports_have_no_ratio() {
+        let report = r#"{ "findings": { "a.tsx": [{ "type": "JsxIife", "line": 3, "severity": "error" }] } }"#;
+        let cmp = compare(report, report).unwrap();
+        assert_eq!((cmp.fixed, cmp.introduced), (0, 0));
+        assert!(cmp.memoized_ratio.is_none());
+    }
+
+    #[test]
+    fn test_flat_findings_compare_like_grouped_ones() {
+        let grouped = r#"{ "findings": { "a.tsx": [{ "type": "JsxIife", "line": 3 }, { "type": "JsxIife", "line": 9 }] } }"#;
+        let flat = r#"{ "findings": [
+            { "file": "a.tsx", "type": "JsxIife", "line": 3 },
+            { "file": "b.tsx", "type": "UnusedMemo", "line": 1 }
+        ] }"#;
+        let cmp = compare(grouped, flat).unwrap();
+        assert_eq!((cmp.before_total, cmp.after_total, cmp.fixed, cmp.introduced), (2, 2, 1, 1));
+        assert_eq!(cmp.by_rule["UnusedMemo"].introduced, 1);
+    }
+}
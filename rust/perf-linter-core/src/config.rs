@@ -0,0 +1,257 @@
+use crate::analyzer::findings::{FindingKind, Severity};
+use crate::analyzer::rules::RuleRegistry;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Config file names looked up in the project root, in this order
+pub const CONFIG_FILE_NAMES: &[&str] = &[".perflinterrc.json", ".perflinterrc.jsonc"];
+
+/// `.perflinterrc.*`; command-line flags take precedence over it
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LintConfig {
+    /// Rule names (as in `RuleRegistry::names`) to switch off
+    #[serde(default)]
+    pub disabled_rules: Vec<String>,
+    /// Only index files matching these globs, relative to the root
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub max_union_members: Option<usize>,
+    /// Shallowest JSX ternary chain `DeepJsxTernary` reports
+    #[serde(default)]
+    pub min_ternary_depth: Option<usize>,
+    /// Widest prop list `WideProps` lets through
+    #[serde(default)]
+    pub max_props: Option<usize>,
+    /// tsconfig to read path aliases from, relative to the config file
+    #[serde(default)]
+    pub tsconfig: Option<String>,
+    /// Finding `type` -> `off`, `info`, `warn` or `error` for `lint`
+    #[serde(default)]
+    pub rules: HashMap<String, Severity>,
+    /// Import source -> advice, added to the built-in heavy dependency table;
+    /// `""` turns a built-in entry off
+    #[serde(default)]
+    pub heavy_dependencies: BTreeMap<String, String>,
+    /// Directories where a capitalized function must render JSX to count as a component
+    #[serde(default)]
+    pub non_component_dirs: Vec<String>,
+}
+
+/// A config file that couldn't be read or parsed; `line`/`column` are 1-based, 0 when unknown
+#[derive(Debug)]
+pub struct ConfigError {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}: {}", self.path, self.message)
+        } else {
+            write!(f, "{}:{}:{}: {}", self.path, self.line, self.column, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl LintConfig {
+    /// The first of `CONFIG_FILE_NAMES` present in `root`
+    pub fn find(root: &str) -> Option<PathBuf> {
+        CONFIG_FILE_NAMES.iter().map(|name| Path::new(root).join(name)).find(|p| p.is_file())
+    }
+
+    /// Read a config file; comments and trailing commas are accepted whatever the extension
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let display = path.display().to_string();
+        let raw = std::fs::read_to_string(path).map_err(|e| ConfigError { path: display.clone(), line: 0, column: 0, message: e.to_string() })?;
+        let mut config = Self::parse(&raw).map_err(|e| ConfigError { path: display, ..e })?;
+        if let Some(tsconfig) = &config.tsconfig {
+            let dir = path.parent().unwrap_or_else(|| Path::new("."));
+            config.tsconfig = Some(dir.join(tsconfig).to_string_lossy().to_string());
+        }
+        Ok(config)
+    }
+
+    /// Parse config text; the error's `path` is left empty
+    pub fn parse(source: &str) -> Result<Self, ConfigError> {
+        serde_json::from_str(&strip_jsonc(source)).map_err(|e| ConfigError {
+            path: String::new(),
+            line: e.line(),
+            column: e.column(),
+            message: e.to_string(),
+        })
+    }
+
+    /// `rules` keyed by `type_name`, however the file spelled them; unknown types are left
+    /// out (`problems` reports them)
+    pub fn severities(&self) -> HashMap<String, Severity> {
+        self.rules
+            .iter()
+            .filter_map(|(name, severity)| FindingKind::canonical_type_name(name).map(|t| (t.to_string(), *severity)))
+            .collect()
+    }
+
+    /// Names that parse but match nothing: `disabledRules` entries that aren't rules (opt-in
+    /// ones included) and `rules` keys that aren't finding types. Unknown keys and bad
+    /// severities already fail `parse`.
+    pub fn problems(&self) -> Vec<String> {
+        let registry = RuleRegistry::all();
+        let rule_names: Vec<&str> = registry.names().collect();
+        let mut problems: Vec<String> = self
+            .disabled_rules
+            .iter()
+            .filter(|name| !rule_names.contains(&name.as_str()))
+            .map(|name| format!("disabledRules: unknown rule `{}`", name))
+            .collect();
+        let mut types: Vec<&String> = self.rules.keys().filter(|t| FindingKind::canonical_type_name(t).is_none()).collect();
+        types.sort();
+        problems.extend(types.into_iter().map(|t| format!("rules: unknown finding type `{}`", t)));
+        problems
+    }
+}
+
+/// Blank out `//` and `/* */` comments and trailing commas so strict JSON can parse
+/// the rest. Newlines are kept and everything else is replaced byte for byte, so
+/// parse errors point at the original line and column.
+pub fn strip_jsonc(source: &str) -> String {
+    let bytes = source.as_bytes();
+    let mut out = bytes.to_vec();
+    let blank = |out: &mut Vec<u8>, from: usize, to: usize| {
+        for b in &mut out[from..to] {
+            if *b != b'\n' {
+                *b = b' ';
+            }
+        }
+    };
+    let mut i = 0;
+    // position of a comma that may turn out to be trailing
+    let mut pending_comma: Option<usize> = None;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                pending_comma = None;
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                let end = bytes[i..].iter().position(|&b| b == b'\n').map(|p| i + p).unwrap_or(bytes.len());
+                blank(&mut out, i, end);
+                i = end;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = source[i + 2..].find("*/").map(|p| i + 2 + p + 2).unwrap_or(bytes.len());
+                blank(&mut out, i, end);
+                i = end;
+            }
+            b',' => {
+                pending_comma = Some(i);
+                i += 1;
+            }
+            b'}' | b']' => {
+                if let Some(comma) = pending_comma.take() {
+                    out[comma] = b' ';
+                }
+                i += 1;
+            }
+            b if b.is_ascii_whitespace() => i += 1,
+            _ => {
+                pending_comma = None;
+                i += 1;
+            }
+        }
+    }
+    // only ASCII bytes outside strings were replaced, so this stays valid UTF-8
+    String::from_utf8(out).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comments_and_trailing_commas() {
+        let source = r#"{
+            // why: generated code
+            "disabledRules": ["EagerStateInit", /* noisy */ "MisusedDomProp",],
+            "include": ["src/**",],
+            "maxUnionMembers": 8,
+        }"#;
+        let config = LintConfig::parse(source).unwrap();
+        assert_eq!(config.disabled_rules, vec!["EagerStateInit", "MisusedDomProp"]);
+        assert_eq!(config.include, vec!["src/**"]);
+        assert_eq!(config.max_union_members, Some(8));
+    }
+
+    #[test]
+    fn test_comment_markers_inside_strings_are_kept() {
+        let config = LintConfig::parse(r#"{ "include": ["src/**/*.tsx", "a//b", "c,]"] }"#).unwrap();
+        assert_eq!(config.include, vec!["src/**/*.tsx", "a//b", "c,]"]);
+    }
+
+    #[test]
+    fn test_rule_severities() {
+        let config = LintConfig::parse(r#"{ "rules": { "JsxIife": "error", "JsonInRender": "warn", "FragmentInMap": "off" } }"#).unwrap();
+        assert_eq!(config.rules["JsxIife"], Severity::Error);
+        assert_eq!(config.rules["JsonInRender"], Severity::Warning);
+        assert_eq!(config.rules["FragmentInMap"], Severity::Off);
+        assert!(LintConfig::parse(r#"{ "rules": { "JsxIife": "fatal" } }"#).is_err());
+    }
+
+    #[test]
+    fn test_severities_normalize_type_names() {
+        let config = LintConfig::parse(r#"{ "rules": { "json-in-render": "error", "fragment_in_map": "off", "no-such-type": "warn" } }"#).unwrap();
+        let severities = config.severities();
+        assert_eq!(severities["JsonInRender"], Severity::Error);
+        assert_eq!(severities["FragmentInMap"], Severity::Off);
+        assert_eq!(severities.len(), 2);
+    }
+
+    #[test]
+    fn test_heavy_dependencies() {
+        let config = LintConfig::parse(r#"{ "heavyDependencies": { "moment": "", "ramda": "import from `ramda/src/*`" } }"#).unwrap();
+        assert_eq!(config.heavy_dependencies["moment"], "");
+        assert_eq!(config.heavy_dependencies.len(), 2);
+    }
+
+    #[test]
+    fn test_problems() {
+        let config = LintConfig::parse(r#"{ "disabledRules": ["JsonInRender", "JsonInRendr"], "rules": { "quadratic-lookup": "off", "inline-handler": "warn" } }"#).unwrap();
+        assert_eq!(config.problems(), vec!["disabledRules: unknown rule `JsonInRendr`", "rules: unknown finding type `inline-handler`"]);
+        assert!(LintConfig::parse(r#"{ "disabled_rules": [] }"#).unwrap_err().message.contains("unknown field"));
+    }
+
+    #[test]
+    fn test_opt_in_rules_can_be_disabled() {
+        let config = LintConfig::parse(r#"{ "disabledRules": ["UnsafeRegex", "LargeUnionProp"] }"#).unwrap();
+        assert!(config.problems().is_empty());
+    }
+
+    #[test]
+    fn test_every_rule_name_is_a_finding_type() {
+        // rule tests check each rule's findings carry its name, so this keeps
+        // `TYPE_NAMES` in step with `type_name`
+        let mut rules: Vec<&str> = RuleRegistry::all().names().chain(FindingKind::CROSS_FILE_TYPE_NAMES.iter().copied()).collect();
+        let mut types = FindingKind::TYPE_NAMES.to_vec();
+        rules.sort();
+        types.sort();
+        assert_eq!(rules, types);
+    }
+
+    #[test]
+    fn test_error_points_at_line() {
+        let source = "{\n  // comment\n  \"maxUnionMembers\": \"eight\"\n}";
+        let err = LintConfig::parse(source).unwrap_err();
+        assert_eq!(err.line, 3);
+    }
+}
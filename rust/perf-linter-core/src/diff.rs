@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// Lines added or modified per file, from the `+` side of a unified diff
+#[derive(Debug, Clone, Default)]
+pub struct ChangedLines {
+    /// Path as written in the diff (`b/` prefix removed) -> inclusive 1-based ranges
+    files: HashMap<String, Vec<(usize, usize)>>,
+}
+
+impl ChangedLines {
+    /// Read the `+++` file names and `@@ -a,b +c,d @@` hunk headers; deleted files and
+    /// pure deletions (`+c,0`) contribute no lines
+    pub fn parse(diff: &str) -> Self {
+        let mut files: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+        let mut current: Option<String> = None;
+        for line in diff.lines() {
+            if let Some(target) = line.strip_prefix("+++ ") {
+                let target = target.split('\t').next().unwrap_or(target).trim();
+                current = (target != "/dev/null").then(|| target.strip_prefix("b/").unwrap_or(target).to_string());
+            } else if let (Some(hunk), Some(file)) = (line.strip_prefix("@@ "), &current) {
+                if let Some(range) = hunk_target_range(hunk) {
+                    files.entry(file.clone()).or_default().push(range);
+                }
+            }
+        }
+        Self { files }
+    }
+
+    /// `git diff --unified=0 --relative <base>` run in `dir`, so paths are relative to it,
+    /// plus every line of the untracked files that aren't ignored, which `git diff` never lists
+    pub fn from_git(dir: &str, base: &str) -> io::Result<Self> {
+        let mut changed = Self::parse(&git_stdout(dir, &["diff", "--unified=0", "--no-color", "--relative", base])?);
+        let untracked = git_stdout(dir, &["ls-files", "--others", "--exclude-standard"])?;
+        for path in untracked.lines().filter(|l| !l.is_empty()) {
+            changed.files.insert(path.to_string(), vec![(1, usize::MAX)]);
+        }
+        Ok(changed)
+    }
+
+    /// Whether `line` of `path` was touched; `path` is relative to the directory the
+    /// diff was taken in (`from_git`'s `dir`) and must match exactly
+    pub fn contains(&self, path: &str, line: usize) -> bool {
+        let path = path.replace('\\', "/");
+        self.files.get(&path).is_some_and(|ranges| ranges.iter().any(|&(start, end)| start <= line && line <= end))
+    }
+}
+
+/// Files touched since a base revision, for indexing only what changed
+#[derive(Debug, Clone, Default)]
+pub struct ChangedFiles {
+    /// Added, modified, copied or renamed-to paths
+    changed: Vec<String>,
+    /// Deleted or renamed-from paths
+    deleted: Vec<String>,
+}
+
+impl ChangedFiles {
+    /// `git diff --name-status` lines: `M\tpath`, `D\tpath`, `R100\told\tnew`
+    pub fn parse_name_status(output: &str) -> Self {
+        let mut files = Self::default();
+        for line in output.lines() {
+            let mut fields = line.split('\t');
+            let (Some(status), Some(path)) = (fields.next(), fields.next()) else { continue };
+            match status.chars().next() {
+                Some('D') => files.deleted.push(path.to_string()),
+                Some('R') => {
+                    files.deleted.push(path.to_string());
+                    files.changed.extend(fields.next().map(str::to_string));
+                }
+                Some('C') => files.changed.extend(fields.next().map(str::to_string)),
+                Some(_) => files.changed.push(path.to_string()),
+                None => {}
+            }
+        }
+        files
+    }
+
+    /// One path per line, relative to `dir`; paths that no longer exist count as deleted
+    pub fn from_list(dir: &str, list: &str) -> Self {
+        let mut files = Self::default();
+        for path in list.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if Path::new(dir).join(path).exists() {
+                files.changed.push(path.to_string());
+            } else {
+                files.deleted.push(path.to_string());
+            }
+        }
+        files
+    }
+
+    /// `git diff --name-status -M --relative <base>` run in `dir`, so paths are relative to it,
+    /// plus untracked files that aren't ignored, which `git diff` never lists
+    pub fn from_git(dir: &str, base: &str) -> io::Result<Self> {
+        let mut files = Self::parse_name_status(&git_stdout(dir, &["diff", "--name-status", "-M", "--relative", base])?);
+        let untracked = git_stdout(dir, &["ls-files", "--others", "--exclude-standard"])?;
+        files.changed.extend(untracked.lines().filter(|l| !l.is_empty()).map(str::to_string));
+        Ok(files)
+    }
+
+    /// Whether `path` (relative to the directory the list was taken in) was added or modified
+    pub fn contains(&self, path: &str) -> bool {
+        self.changed.contains(&path.replace('\\', "/"))
+    }
+
+    pub fn deleted(&self) -> &[String] {
+        &self.deleted
+    }
+}
+
+/// Stdout of `git <args>` run in `dir`; a non-zero exit becomes an error carrying stderr
+fn git_stdout(dir: &str, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// `+c,d` from the text after `@@ `; `+c` alone means one line
+fn hunk_target_range(hunk: &str) -> Option<(usize, usize)> {
+    let target = hunk.split_whitespace().find_map(|part| part.strip_prefix('+'))?;
+    let (start, count) = match target.split_once(',') {
+        Some((start, count)) => (start.parse::<usize>().ok()?, count.parse::<usize>().ok()?),
+        None => (target.parse::<usize>().ok()?, 1),
+    };
+    (count > 0).then(|| (start, start + count - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/src/App.tsx b/src/App.tsx
+index 1111111..2222222 100644
+--- a/src/App.tsx
++++ b/src/App.tsx
+@@ -10,0 +11,3 @@ export function App() {
++  const a = 1;
++  const b = 2;
++  const c = 3;
+@@ -40 +43 @@ export function App() {
+-  old();
++  new();
+@@ -50,2 +53,0 @@
+-  gone();
+-  gone();
+diff --git a/src/Old.tsx b/src/Old.tsx
+deleted file mode 100644
+--- a/src/Old.tsx
++++ /dev/null
+@@ -1,2 +0,0 @@
+-x
+-y
+";
+
+    #[test]
+    fn test_hunk_ranges() {
+        let changed = ChangedLines::parse(DIFF);
+        assert!(changed.contains("src/App.tsx", 11));
+        assert!(changed.contains("src/App.tsx", 13));
+        assert!(!changed.contains("src/App.tsx", 14));
+        assert!(changed.contains("src/App.tsx", 43));
+        // pure deletions and deleted files touch no remaining line
+        assert!(!changed.contains("src/App.tsx", 53));
+        assert!(!changed.contains("src/Old.tsx", 1));
+    }
+
+    #[test]
+    fn test_name_status() {
+        let files = ChangedFiles::parse_name_status("M\tsrc/App.tsx\nD\tsrc/Old.tsx\nR087\tsrc/Btn.tsx\tsrc/Button.tsx\nA\tsrc/New.tsx\n");
+        assert!(files.contains("src/App.tsx"));
+        assert!(files.contains("src/Button.tsx"));
+        assert!(files.contains("src/New.tsx"));
+        assert!(!files.contains("src/Btn.tsx"));
+        assert_eq!(files.deleted(), ["src/Old.tsx", "src/Btn.tsx"]);
+    }
+
+    #[test]
+    fn test_git_lists_untracked_files() {
+        let dir = std::env::temp_dir().join("perf_linter_untracked_repo");
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        let dir_str = dir.to_string_lossy().to_string();
+        let git = |args: &[&str]| git_stdout(&dir_str, args).unwrap();
+        git(&["init", "-q"]);
+        std::fs::write(dir.join("src/App.tsx"), "export const App = 1;\n").unwrap();
+        std::fs::write(dir.join(".gitignore"), "dist/\n").unwrap();
+        git(&["add", "."]);
+        git(&["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "init"]);
+        std::fs::write(dir.join("src/New.tsx"), "export const New = 1;\n").unwrap();
+        std::fs::create_dir_all(dir.join("dist")).unwrap();
+        std::fs::write(dir.join("dist/out.js"), "x\n").unwrap();
+
+        let files = ChangedFiles::from_git(&dir_str, "HEAD").unwrap();
+        assert!(files.contains("src/New.tsx"));
+        assert!(!files.contains("src/App.tsx"));
+        assert!(!files.contains("dist/out.js"));
+
+        // every line of an untracked file is new
+        std::fs::write(dir.join("src/App.tsx"), "export const App = 1;\nexport const B = 2;\n").unwrap();
+        let lines = ChangedLines::from_git(&dir_str, "HEAD").unwrap();
+        assert!(lines.contains("src/New.tsx", 1) && lines.contains("src/New.tsx", 500));
+        assert!(!lines.contains("src/App.tsx", 1));
+        assert!(lines.contains("src/App.tsx", 2));
+        assert!(!lines.contains("dist/out.js", 1));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_paths_match_exactly() {
+        let changed = ChangedLines::parse(DIFF);
+        // another package's `src/App.tsx` must not count as this one's `App.tsx`
+        assert!(!changed.contains("App.tsx", 11));
+        assert!(!changed.contains("pkg/src/App.tsx", 11));
+        assert!(changed.contains("src\\App.tsx", 11));
+    }
+}
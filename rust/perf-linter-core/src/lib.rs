@@ -1,11 +1,24 @@
 pub mod parser;
+#[cfg(feature = "node")]
 pub mod bridge;
 pub mod cache;
+pub mod compare;
+pub mod config;
+pub mod diff;
+pub mod redos;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub mod analyzer {
     pub mod extract;
+    pub mod findings;
     pub mod metadata;
+    pub mod provider;
+    pub mod remap;
+    pub mod rules;
 }
 
 // Re-export selected API for consumers
-pub use parser::{parse_file, traverse_ast, AstNode, NodeKind, SpanJson};
-pub use cache::{IncrementalCache, CacheEntry, CacheStats};
+pub use parser::{extract_regex_literals, parse_and_visit, parse_file, traverse_ast, traverse_ast_filtered, AstNode, NodeKind, RegexLiteral, SpanJson};
+pub use cache::{IncrementalCache, CacheEntry, CacheStats, CacheMeta};
+pub use analyzer::extract::{analyze_source, extract_all, ExportInfo, FileAnalysis, ImportMeta};
+pub use analyzer::metadata::ComponentMeta;
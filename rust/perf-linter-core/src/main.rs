@@ -1,15 +1,28 @@
-use clap::{Args, Parser, Subcommand};
-use regex::Regex;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
-use std::io::{self, Read};
+use std::io::{self, IsTerminal, Read, Write};
 use perf_linter_core::parser::parse_typescript;
-use perf_linter_core::analyzer::metadata::MetadataGraph;
+use perf_linter_core::redos::analyze_pattern;
+use perf_linter_core::analyzer::extract::PathAliases;
+use perf_linter_core::analyzer::findings::{FindingKind, LintFinding, Severity};
+use perf_linter_core::analyzer::metadata::{estimate_scope, IndexOptions, MetadataGraph};
+use perf_linter_core::analyzer::rules::RuleRegistry;
+use perf_linter_core::compare::compare;
+use perf_linter_core::config::LintConfig;
+use perf_linter_core::diff::{ChangedFiles, ChangedLines};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::level_filters::LevelFilter;
 
 #[derive(Parser)]
 #[command(author, version, about = "perf-linter core engine", long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Engine diagnostics on STDERR: off, error, warn, info, debug or trace
+    #[arg(long, global = true, default_value = "off")]
+    log_level: LevelFilter,
 }
 
 #[derive(Subcommand)]
@@ -20,6 +33,36 @@ enum Commands {
     Parse(ParseArgs),
     /// Index a project folder and output cross-file metadata graph as JSON
     Index(IndexArgs),
+    /// Report how many files `index` would visit, without parsing them
+    EstimateScope(EstimateArgs),
+    /// Index a project and run every rule, including ReDoS checks on regex literals
+    Lint(LintArgs),
+    /// Compare two saved `index` or `lint` outputs: findings fixed and introduced per rule
+    Compare(CompareArgs),
+    /// Check a config file: unknown keys, rule names and severities; exits 4 on any problem
+    ValidateConfig(ValidateConfigArgs),
+    /// List every rule, opt-in ones included, with its default severity, description and category as JSON
+    Rules,
+    /// Index a project and print its resolved import graph and import cycles
+    Graph(GraphArgs),
+}
+
+/// Process exit statuses, the same for every subcommand; 0 is success. CI scripts branch
+/// on these, so the numbers must not change. clap's own usage errors also exit 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExitCode {
+    /// `lint --fail-on`: a finding at or above the threshold
+    Findings = 1,
+    /// Bad input, or STDIN, a file or `git` that couldn't be read
+    Usage = 2,
+    /// Source (`parse`) or saved JSON (`compare`) that doesn't parse
+    Parse = 3,
+    /// Config file missing (`validate-config`), unreadable or invalid
+    Config = 4,
+}
+
+fn exit(code: ExitCode) -> ! {
+    std::process::exit(code as i32)
 }
 
 #[derive(Deserialize)]
@@ -34,48 +77,30 @@ struct RedosOutput {
     rewrite: Option<String>,
 }
 
-fn detect_simple_nested_quantifier(pattern: &str) -> Option<String> {
-    // Very conservative detection for patterns like: ^((\w)+)+$ or (a+)+, capture the inner char class token
-    // We keep the JS rule's simple rewrite: (x+)+ => (x+) (no nested +)
-    // Rust equivalent using regex:
-    // ^(?P<prefix>\^?)\((?P<char>\w)\+\)\+(?P<suffix>\$?)$
-    let re = Regex::new(r"^(?P<prefix>\^?)\((?P<char>\w)\+\)\+(?P<suffix>\$?)$").ok()?;
-    if let Some(caps) = re.captures(pattern) {
-        let prefix = caps.name("prefix").map(|m| m.as_str()).unwrap_or("");
-        let ch = caps.name("char")?.as_str();
-        let suffix = caps.name("suffix").map(|m| m.as_str()).unwrap_or("");
-        return Some(format!("{}({}+){suffix}", prefix, ch, suffix = suffix));
-    }
-    None
-}
-
-fn is_likely_safe(pattern: &str) -> bool {
-    // Minimal heuristic: if simple nested quantifier detected, mark unsafe; else assume safe.
-    detect_simple_nested_quantifier(pattern).is_none()
-}
-
 fn main() {
     let cli = Cli::parse();
+    if cli.log_level != LevelFilter::OFF {
+        tracing_subscriber::fmt().with_max_level(cli.log_level).with_writer(io::stderr).init();
+    }
     match cli.command {
         Commands::CheckRedos => {
             // read JSON from stdin
             let mut buf = String::new();
             if io::stdin().read_to_string(&mut buf).is_err() {
-                // on input error, default to safe to avoid breaking pipelines
+                // on input error, still print a safe verdict for callers that only read STDOUT
                 println!("{}", serde_json::to_string(&RedosOutput { safe: true, rewrite: None }).unwrap());
-                return;
+                exit(ExitCode::Usage);
             }
             let input: RedosInput = match serde_json::from_str(&buf) {
                 Ok(v) => v,
                 Err(_) => {
                     println!("{}", serde_json::to_string(&RedosOutput { safe: true, rewrite: None }).unwrap());
-                    return;
+                    exit(ExitCode::Usage);
                 }
             };
 
-            let rewrite = detect_simple_nested_quantifier(&input.pattern);
-            let safe = rewrite.is_none() && is_likely_safe(&input.pattern);
-            let out = RedosOutput { safe, rewrite };
+            let analysis = analyze_pattern(&input.pattern);
+            let out = RedosOutput { safe: analysis.is_safe(), rewrite: analysis.rewrite };
             println!("{}", serde_json::to_string(&out).unwrap());
         }
         Commands::Parse(args) => {
@@ -83,7 +108,7 @@ fn main() {
             let mut src = String::new();
             if io::stdin().read_to_string(&mut src).is_err() {
                 eprintln!("perf-linter-core parse: failed to read from STDIN");
-                std::process::exit(2);
+                exit(ExitCode::Usage);
             }
             let filename = args.filename.unwrap_or_else(|| "input.tsx".to_string());
             match parse_typescript(&src, &filename) {
@@ -98,16 +123,239 @@ fn main() {
                     let out = ParseErrorOut { error: err.0 };
                     println!("{}", serde_json::to_string(&out).unwrap());
                     // Non-zero to signal failure to callers that check status
-                    std::process::exit(1);
+                    exit(ExitCode::Parse);
                 }
             }
         }
         Commands::Index(idx) => {
-            let root = idx.project_root;
-            let graph = MetadataGraph::index_project(&root);
-            println!("{}", graph.to_json());
+            let root = &idx.project.project_root;
+            let config = load_config("index", root, idx.project.config.as_deref());
+            let mut options = index_options("index", &config, &idx.project);
+            options.collect_docs = idx.docs;
+            let graph = index_graph("index", root, &options, idx.project.since.as_deref());
+            if let Some(base) = idx.diff.as_deref() {
+                graph.retain_changed(&load_diff("index", root, base));
+            }
+            if idx.sourcemap {
+                graph.remap_to_original_sources();
+            }
+            if let Some(min) = idx.barrel_imports {
+                println!("{}", serde_json::to_string(&graph.barrel_imports(min)).unwrap());
+            } else if idx.memo_priority {
+                println!("{}", serde_json::to_string(&graph.memo_priorities(idx.min_props)).unwrap());
+            } else if idx.import_aliases {
+                println!("{}", serde_json::to_string(&graph.inconsistent_import_aliases()).unwrap());
+            } else if idx.unresolved_imports {
+                println!("{}", serde_json::to_string(&graph.unresolved_imports()).unwrap());
+            } else if idx.file_metrics {
+                println!("{}", serde_json::to_string(&graph.file_metrics()).unwrap());
+            } else if idx.stats {
+                println!("{}", serde_json::to_string(&graph.stats(idx.top_files)).unwrap());
+            } else {
+                println!("{}", graph.to_json_with_limit(idx.max_findings));
+            }
+        }
+        Commands::Lint(args) => {
+            let root = &args.project.project_root;
+            let config = load_config("lint", root, args.project.config.as_deref());
+            let options = IndexOptions { check_regex: true, ..index_options("lint", &config, &args.project) };
+            let graph = index_graph("lint", root, &options, args.project.since.as_deref());
+            let changed = args.diff.as_deref().map(|base| load_diff("lint", root, base));
+            if let Some(changed) = &changed {
+                graph.retain_changed(changed);
+            }
+            // `--rule` wins over the config file's `rules`
+            let mut severities = config.severities();
+            severities.extend(args.rule);
+            let report = graph.lint_report(args.min_props, &severities, changed.as_ref());
+            if args.json_lines {
+                let mut out = io::stdout().lock();
+                'lines: for (file, findings) in &report.findings {
+                    for finding in findings {
+                        // a closed pipe (`| head`) ends the output early; that's fine
+                        if writeln!(out, "{}", serde_json::to_string(&FindingLine { file, finding }).unwrap()).is_err() {
+                            break 'lines;
+                        }
+                    }
+                }
+            } else if args.format == OutputFormat::Human {
+                let color = !args.no_color && (args.color || (std::env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal()));
+                print_human(&report.findings, color);
+            } else if args.group_by == GroupBy::None {
+                // same report, with `findings` as one list ordered by file, line and column
+                let flat: Vec<FindingLine> = report.findings.iter().flat_map(|(file, findings)| findings.iter().map(move |finding| FindingLine { file, finding })).collect();
+                let mut json = serde_json::to_value(&report).unwrap();
+                json["findings"] = serde_json::to_value(&flat).unwrap();
+                println!("{}", json);
+            } else {
+                println!("{}", serde_json::to_string(&report).unwrap());
+            }
+            if let Some(level) = args.fail_on {
+                if report.findings.values().flatten().any(|f| f.severity >= level) {
+                    exit(ExitCode::Findings);
+                }
+            }
+        }
+        Commands::Compare(args) => {
+            let read = |path: &str| {
+                std::fs::read_to_string(path).unwrap_or_else(|err| {
+                    eprintln!("perf-linter-core compare: cannot read {}: {}", path, err);
+                    exit(ExitCode::Usage);
+                })
+            };
+            match compare(&read(&args.before), &read(&args.after)) {
+                Ok(comparison) => println!("{}", serde_json::to_string(&comparison).unwrap()),
+                Err(err) => {
+                    eprintln!("perf-linter-core compare: invalid input: {}", err);
+                    exit(ExitCode::Parse);
+                }
+            }
+        }
+        Commands::ValidateConfig(args) => {
+            let Some(path) = args.config.map(PathBuf::from).or_else(|| LintConfig::find(&args.project_root)) else {
+                eprintln!("perf-linter-core validate-config: no config file in {}", args.project_root);
+                exit(ExitCode::Config);
+            };
+            let problems = match LintConfig::load(&path) {
+                Ok(config) => config.problems().into_iter().map(|p| format!("{}: {}", path.display(), p)).collect(),
+                Err(err) => vec![err.to_string()],
+            };
+            if problems.is_empty() {
+                println!("{}: ok", path.display());
+            } else {
+                problems.iter().for_each(|p| eprintln!("{}", p));
+                exit(ExitCode::Config);
+            }
+        }
+        Commands::Graph(args) => {
+            let root = &args.project.project_root;
+            let config = load_config("graph", root, args.project.config.as_deref());
+            let options = index_options("graph", &config, &args.project);
+            let graph = index_graph("graph", root, &options, args.project.since.as_deref()).import_graph();
+            match args.format {
+                GraphFormat::Json => println!("{}", serde_json::to_string(&graph).unwrap()),
+                GraphFormat::Dot => print!("{}", graph.to_dot(args.cycles)),
+            }
+        }
+        Commands::Rules => {
+            println!("{}", serde_json::to_string(&RuleRegistry::all().catalog()).unwrap());
+        }
+        Commands::EstimateScope(args) => {
+            let estimate = estimate_scope(&args.project_root);
+            println!("{}", serde_json::to_string(&estimate).unwrap());
+        }
+    }
+}
+
+/// `--config`, else a `.perflinterrc.*` in the root, else defaults; exits on a broken file
+fn load_config(command: &str, root: &str, explicit: Option<&str>) -> LintConfig {
+    let path = match explicit {
+        Some(path) => Path::new(path).to_path_buf(),
+        None => match LintConfig::find(root) {
+            Some(path) => path,
+            None => return LintConfig::default(),
+        },
+    };
+    LintConfig::load(&path).unwrap_or_else(|err| {
+        eprintln!("perf-linter-core {}: invalid config {}", command, err);
+        exit(ExitCode::Config);
+    })
+}
+
+/// A finding with its file, for output that isn't keyed by file
+#[derive(Serialize)]
+struct FindingLine<'a> {
+    file: &'a str,
+    #[serde(flatten)]
+    finding: &'a LintFinding,
+}
+
+/// `path:line:column: severity [Type] message`, one finding per line
+fn print_human(findings: &BTreeMap<String, Vec<LintFinding>>, color: bool) {
+    let mut out = io::stdout().lock();
+    for (file, findings) in findings {
+        for f in findings {
+            let (label, ansi) = match f.severity {
+                Severity::Error => ("error", "31"),
+                Severity::Warning => ("warning", "33"),
+                Severity::Info => ("info", "36"),
+                Severity::Off => continue,
+            };
+            let label = if color { format!("\x1b[{}m{}\x1b[0m", ansi, label) } else { label.to_string() };
+            let line = format!("{}:{}:{}: {} [{}] {}", file, f.finding.line, f.finding.column, label, f.finding.kind.type_name(), f.finding.kind.message());
+            // a closed pipe (`| head`) ends the output early; that's fine
+            if writeln!(out, "{}", line).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// `--rule TYPE=LEVEL`; `TYPE` may be written `json-in-render` as well as `JsonInRender`
+fn parse_rule_severity(arg: &str) -> Result<(String, Severity), String> {
+    let (kind, level) = arg.split_once('=').ok_or_else(|| format!("expected TYPE=LEVEL, got `{}`", arg))?;
+    let kind = FindingKind::canonical_type_name(kind).ok_or_else(|| format!("unknown finding type `{}`", kind))?;
+    Ok((kind.to_string(), level.parse()?))
+}
+
+/// Lines changed since `base` (a git ref), or in a unified diff on STDIN when `base` is `-`
+fn load_diff(command: &str, root: &str, base: &str) -> ChangedLines {
+    if base == "-" {
+        let mut diff = String::new();
+        if io::stdin().read_to_string(&mut diff).is_err() {
+            eprintln!("perf-linter-core {}: failed to read diff from STDIN", command);
+            exit(ExitCode::Usage);
+        }
+        return ChangedLines::parse(&diff);
+    }
+    ChangedLines::from_git(root, base).unwrap_or_else(|err| {
+        eprintln!("perf-linter-core {}: git diff {} failed: {}", command, base, err);
+        exit(ExitCode::Usage);
+    })
+}
+
+/// `IndexOptions` from the shared flags, falling back to the config file for each
+fn index_options(command: &str, config: &LintConfig, args: &ProjectArgs) -> IndexOptions {
+    let mut options = IndexOptions::default();
+    if let Some(ms) = args.parse_timeout_ms {
+        options.parse_timeout = Duration::from_millis(ms);
+    }
+    if let Some(tsconfig) = args.tsconfig.as_ref().or(config.tsconfig.as_ref()) {
+        match PathAliases::from_tsconfig(tsconfig) {
+            Some(aliases) => options.path_aliases = aliases,
+            None => eprintln!("perf-linter-core {}: could not read paths from {}", command, tsconfig),
         }
     }
+    options.include_globs = if args.include.is_empty() { config.include.clone() } else { args.include.clone() };
+    options.max_union_members = args.max_union_members.or(config.max_union_members);
+    options.disabled_rules = config.disabled_rules.clone();
+    options.min_ternary_depth = config.min_ternary_depth;
+    options.max_props = config.max_props;
+    options.heavy_dependencies = config.heavy_dependencies.clone();
+    options.non_component_dirs = config.non_component_dirs.clone();
+    options.cache_dir = args.cache_dir.as_ref().map(PathBuf::from);
+    options.dedup_cache = args.dedup_cache;
+    options
+}
+
+/// The whole project, or with `since` only the files changed since that git ref
+/// (`-` reads a list of paths relative to `root` from STDIN), the rest coming from the cache
+fn index_graph(command: &str, root: &str, options: &IndexOptions, since: Option<&str>) -> MetadataGraph {
+    let Some(base) = since else { return MetadataGraph::index_project_with_options(root, options) };
+    let changes = if base == "-" {
+        let mut list = String::new();
+        if io::stdin().read_to_string(&mut list).is_err() {
+            eprintln!("perf-linter-core {}: failed to read file list from STDIN", command);
+            exit(ExitCode::Usage);
+        }
+        ChangedFiles::from_list(root, &list)
+    } else {
+        ChangedFiles::from_git(root, base).unwrap_or_else(|err| {
+            eprintln!("perf-linter-core {}: git diff {} failed: {}", command, base, err);
+            exit(ExitCode::Usage);
+        })
+    };
+    MetadataGraph::index_project_since(root, options, &changes)
 }
 
 #[derive(Args, Debug, Default)]
@@ -117,9 +365,174 @@ struct ParseArgs {
     filename: Option<String>,
 }
 
+// What to index and how; shared by `index`, `lint` and `graph`
+#[derive(Args, Debug, Default)]
+struct ProjectArgs {
+    /// Path to the project root
+    #[arg()]
+    project_root: String,
+    /// Abandon a file whose parse takes longer than this (default 10000)
+    #[arg(long)]
+    parse_timeout_ms: Option<u64>,
+    /// tsconfig.json whose `compilerOptions.paths` mark aliased imports
+    #[arg(long)]
+    tsconfig: Option<String>,
+    /// Only index files matching this glob, relative to the root (repeatable)
+    #[arg(long)]
+    include: Vec<String>,
+    /// Report props typed as unions with more members than this
+    #[arg(long)]
+    max_union_members: Option<usize>,
+    /// Config file; defaults to `.perflinterrc.json` or `.jsonc` in the root
+    #[arg(long)]
+    config: Option<String>,
+    /// Only analyze files changed since this git ref (`-` reads a file list from STDIN);
+    /// the others are loaded from the cache for cross-file context and report nothing
+    #[arg(long)]
+    since: Option<String>,
+    /// Cache directory; overrides `PERF_LINTER_CACHE_DIR` and the system temp directory
+    #[arg(long)]
+    cache_dir: Option<String>,
+    /// Share one cache entry between files with identical content (generated duplicates, copied fixtures)
+    #[arg(long)]
+    dedup_cache: bool,
+}
+
+// The flags printing something other than the graph form the `output` group: one at a time
 #[derive(Args, Debug, Default)]
 struct IndexArgs {
-    /// Path to the project root to index
+    #[command(flatten)]
+    project: ProjectArgs,
+    /// Map findings back to original sources using adjacent `.map` files
+    #[arg(long)]
+    sourcemap: bool,
+    /// Print a summary (counts per rule, top files, memoized ratio) instead of the graph
+    #[arg(long, group = "output")]
+    stats: bool,
+    /// Print lines, components, memoized components and findings per rule for each file instead of the graph
+    #[arg(long, group = "output")]
+    file_metrics: bool,
+    /// Print relative/absolute/aliased imports that resolve to no file instead of the graph
+    #[arg(long, group = "output")]
+    unresolved_imports: bool,
+    /// Print exports imported under different local names in different files instead of the graph
+    #[arg(long, group = "output")]
+    import_aliases: bool,
+    /// Stop emitting findings after this many (across all files); unlimited by default
+    #[arg(long)]
+    max_findings: Option<usize>,
+    /// How many files `--stats` ranks by finding count
+    #[arg(long, default_value_t = 10)]
+    top_files: usize,
+    /// Print unmemoized components ranked by prop count instead of the graph
+    #[arg(long, group = "output")]
+    memo_priority: bool,
+    /// Prop count above which `--memo-priority` weighs a component double
+    #[arg(long, default_value_t = 5)]
+    min_props: usize,
+    /// Print imports of files with at least this many re-exports instead of the graph
+    #[arg(long, num_args = 0..=1, default_missing_value = "5", group = "output")]
+    barrel_imports: Option<usize>,
+    /// Attach each component's leading JSDoc/comment to it as `doc`
+    #[arg(long)]
+    docs: bool,
+    /// Keep only findings on lines changed since this git ref (`-` reads a unified diff with
+    /// paths relative to the project root, e.g. from `git diff --relative`, from STDIN)
+    #[arg(long)]
+    diff: Option<String>,
+}
+
+#[derive(Args, Debug, Default)]
+struct LintArgs {
+    #[command(flatten)]
+    project: ProjectArgs,
+    /// Prop count above which memo priorities weigh a component double
+    #[arg(long, default_value_t = 5)]
+    min_props: usize,
+    /// Keep only findings on lines changed since this git ref (`-` reads a unified diff with
+    /// paths relative to the project root, e.g. from `git diff --relative`, from STDIN)
+    #[arg(long)]
+    diff: Option<String>,
+    /// Report a finding type as `off`, `info`, `warn` or `error`, e.g. `json-in-render=error` (repeatable)
+    #[arg(long, value_parser = parse_rule_severity)]
+    rule: Vec<(String, Severity)>,
+    /// Exit with status 1 when a finding at or above this severity is reported
+    #[arg(long)]
+    fail_on: Option<Severity>,
+    /// Print one finding per line (`{"file", "type", "line", ...}`) instead of the report
+    #[arg(long)]
+    json_lines: bool,
+    /// `json` (the report) or `human` (`path:line:column: severity [type] message` lines)
+    #[arg(long, value_enum, default_value_t = OutputFormat::Json)]
+    format: OutputFormat,
+    /// `file` nests `findings` under their path; `none` makes them one list of `{"file", "type", ...}`
+    #[arg(long, value_enum, default_value_t = GroupBy::File)]
+    group_by: GroupBy,
+    /// Color `--format human` severities even when STDOUT isn't a terminal
+    #[arg(long, conflicts_with = "no_color")]
+    color: bool,
+    /// Never color `--format human` output; setting `NO_COLOR` does the same
+    #[arg(long)]
+    no_color: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum GroupBy {
+    #[default]
+    File,
+    None,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    #[default]
+    Json,
+    Human,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum GraphFormat {
+    /// `{ "edges": { file: [imported files] }, "cycles": [[files]] }`
+    #[default]
+    Json,
+    /// Graphviz, e.g. `| dot -Tsvg > imports.svg`
+    Dot,
+}
+
+#[derive(Args, Debug, Default)]
+struct GraphArgs {
+    #[command(flatten)]
+    project: ProjectArgs,
+    #[arg(long, value_enum, default_value_t = GraphFormat::Json)]
+    format: GraphFormat,
+    /// Draw files and imports that form a cycle in red (`--format dot`)
+    #[arg(long)]
+    cycles: bool,
+}
+
+#[derive(Args, Debug, Default)]
+struct CompareArgs {
+    /// JSON saved from the earlier run
+    #[arg()]
+    before: String,
+    /// JSON saved from the later run
+    #[arg()]
+    after: String,
+}
+
+#[derive(Args, Debug, Default)]
+struct ValidateConfigArgs {
+    /// Directory to look for `.perflinterrc.json` or `.jsonc` in
+    #[arg(default_value = ".")]
+    project_root: String,
+    /// Config file to check instead
+    #[arg(long)]
+    config: Option<String>,
+}
+
+#[derive(Args, Debug, Default)]
+struct EstimateArgs {
+    /// Path to the project root to scan
     #[arg()]
     project_root: String,
 }
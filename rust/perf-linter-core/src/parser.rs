@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
 use swc_common::{
+    comments::Comments,
     errors::{ColorConfig, Handler},
     sync::Lrc,
-    FileName, SourceMap, Span, DUMMY_SP,
+    BytePos, FileName, SourceFile, SourceMap, Span, DUMMY_SP,
 };
+use swc_ecma_ast::{ArrowExpr, CallExpr, Class, Function, JSXElement, JSXFragment, Module, Regex, Script};
 use swc_ecma_parser::{EsConfig, Parser, StringInput, Syntax, TsConfig};
+use swc_ecma_visit::{Visit, VisitWith};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpanJson {
@@ -19,11 +22,50 @@ impl From<Span> for SpanJson {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Maps SWC byte positions within one source file to 1-based line/column
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    start_pos: u32,
+    line_starts: Vec<u32>,
+}
+
+impl Default for LineIndex {
+    fn default() -> Self {
+        Self { start_pos: 0, line_starts: vec![0] }
+    }
+}
+
+impl LineIndex {
+    /// `start_pos` is the file's offset inside its `SourceMap` (`SourceFile::start_pos`)
+    pub fn new(source: &str, start_pos: BytePos) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(i, _)| i as u32 + 1));
+        Self { start_pos: start_pos.0, line_starts }
+    }
+
+    /// 1-based (line, column); the column counts bytes from the start of the line
+    pub fn line_col(&self, pos: BytePos) -> (usize, usize) {
+        let offset = pos.0.saturating_sub(self.start_pos);
+        let line = self.line_starts.partition_point(|&start| start <= offset).max(1);
+        let column = offset - self.line_starts[line - 1];
+        (line, column as usize + 1)
+    }
+
+    pub fn line(&self, pos: BytePos) -> usize {
+        self.line_col(pos).0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum NodeKind {
     Root,
-    // We can extend with more node kinds later
+    /// `function` declarations and expressions, methods and arrow functions
+    Function,
+    Class,
+    Call,
+    JsxElement,
+    JsxFragment,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,48 +96,75 @@ pub fn parse_file(source: &str) -> Result<AstNode, ParseError> {
     let mut parser = Parser::new(syntax, input, None);
 
     // Try parse as a module; if fails, as script
-    if parser.parse_module().is_ok() || parser.take_errors().is_empty() {
-        Ok(AstNode { kind: NodeKind::Root, span: DUMMY_SP.into(), children: vec![] })
-    } else {
-        parser.take_errors().into_iter().for_each(|e| e.into_diagnostic(&handler).emit());
-        // Try script fallback
-        let fm2 = cm.new_source_file(FileName::Custom("input.js".into()), source.into());
-        let input2 = StringInput::from(&*fm2);
-        let mut parser2 = Parser::new(
-            Syntax::Es(EsConfig {
-                jsx: true,
-                decorators: true,
-                ..Default::default()
-            }),
-            input2,
-            None,
-        );
-        match parser2.parse_script() {
-            Ok(_) => Ok(AstNode { kind: NodeKind::Root, span: DUMMY_SP.into(), children: vec![] }),
-            Err(err) => {
-                let mut s = String::new();
-                err.into_diagnostic(&handler).emit();
-                s.push_str("Parse error");
-                Err(ParseError(s))
+    match parser.parse_module() {
+        Ok(module) => Ok(build_tree(&module, fm.start_pos)),
+        Err(err) => {
+            err.into_diagnostic(&handler).emit();
+            // Try script fallback
+            let fm2 = cm.new_source_file(FileName::Custom("input.js".into()), source.into());
+            let input2 = StringInput::from(&*fm2);
+            let mut parser2 = Parser::new(
+                Syntax::Es(EsConfig {
+                    jsx: true,
+                    decorators: true,
+                    ..Default::default()
+                }),
+                input2,
+                None,
+            );
+            match parser2.parse_script() {
+                Ok(script) => Ok(build_tree(&script, fm2.start_pos)),
+                Err(err) => {
+                    let mut s = String::new();
+                    err.into_diagnostic(&handler).emit();
+                    s.push_str("Parse error");
+                    Err(ParseError(s))
+                }
             }
         }
     }
 }
 
 /// Parse a source string as JS/TS (with JSX) using SWC, honoring the provided filename
-/// to choose sensible defaults. Returns a minimal AST JSON for now (Root node),
-/// but establishes a stable interface for the CLI and future visitors.
+/// to choose sensible defaults. Returns the `NodeKind` nodes as a tree rooted at `Root`.
 pub fn parse_typescript(source: &str, filename: &str) -> Result<AstNode, ParseError> {
+    let (module, fm) = parse_source(source, filename, None)?;
+    Ok(build_tree(&module, fm.start_pos))
+}
+
+/// Parse `source` as a module with the syntax `filename` implies. A `.tsx` file that fails is
+/// retried as plain TypeScript: `<T>(x: T) => x` is a JSX tag to the TSX grammar, but a .tsx
+/// file without JSX (generic helpers, hooks) is still valid. Diagnostics are emitted only once
+/// the last attempt has failed, and are the first attempt's. Also returns the parsed file.
+pub(crate) fn parse_source(source: &str, filename: &str, comments: Option<&dyn Comments>) -> Result<(Module, Lrc<SourceFile>), ParseError> {
     let cm: Lrc<SourceMap> = Default::default();
-    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm.clone()));
+    let attempt = |syntax: Syntax| {
+        let fm = cm.new_source_file(FileName::Custom(filename.to_string()), source.into());
+        let mut parser = Parser::new(syntax, StringInput::from(&*fm), comments);
+        parser.parse_module().map(|module| (module, fm))
+    };
 
-    let fname = FileName::Custom(filename.to_string());
-    let fm = cm.new_source_file(fname, source.into());
-    let input = StringInput::from(&*fm);
+    let syntax = syntax_for(filename);
+    let err = match attempt(syntax) {
+        Ok(parsed) => return Ok(parsed),
+        Err(err) => err,
+    };
+    if let Syntax::Typescript(ts) = syntax {
+        if ts.tsx {
+            if let Ok(parsed) = attempt(Syntax::Typescript(TsConfig { tsx: false, ..ts })) {
+                return Ok(parsed);
+            }
+        }
+    }
+    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm.clone()));
+    err.into_diagnostic(&handler).emit();
+    Err(ParseError("Parse error".into()))
+}
 
-    // Heuristic: if filename suggests TS, enable TSX; else ES with JSX
+/// Heuristic: if filename suggests TS, enable TSX; else ES with JSX
+fn syntax_for(filename: &str) -> Syntax {
     let is_ts = filename.ends_with(".ts") || filename.ends_with(".tsx") || filename.ends_with(".d.ts");
-    let syntax = if is_ts {
+    if is_ts {
         Syntax::Typescript(TsConfig {
             tsx: filename.ends_with(".tsx"),
             decorators: true,
@@ -105,21 +174,122 @@ pub fn parse_typescript(source: &str, filename: &str) -> Result<AstNode, ParseEr
         })
     } else {
         Syntax::Es(EsConfig {
-            jsx: filename.ends_with(".jsx") || filename.ends_with(".tsx") || filename.ends_with(".jsx"),
+            jsx: filename.ends_with(".jsx") || filename.ends_with(".tsx"),
             decorators: true,
             ..Default::default()
         })
-    };
+    }
+}
 
-    let mut parser = Parser::new(syntax, input, None);
-    if parser.parse_module().is_ok() || parser.take_errors().is_empty() {
-        Ok(AstNode { kind: NodeKind::Root, span: DUMMY_SP.into(), children: vec![] })
-    } else {
-        parser.take_errors().into_iter().for_each(|e| e.into_diagnostic(&handler).emit());
-        Err(ParseError("Parse error".into()))
+/// A `/pattern/flags` literal; `span` is a byte range into the parsed source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegexLiteral {
+    pub pattern: String,
+    pub flags: String,
+    pub span: SpanJson,
+    /// 1-based position of the opening `/`
+    pub line: usize,
+    pub column: usize,
+}
+
+struct RegexCollector<'a> {
+    lines: &'a LineIndex,
+    start_pos: u32,
+    found: Vec<RegexLiteral>,
+}
+
+impl Visit for RegexCollector<'_> {
+    fn visit_regex(&mut self, re: &Regex) {
+        let (line, column) = self.lines.line_col(re.span.lo);
+        self.found.push(RegexLiteral {
+            pattern: re.exp.to_string(),
+            flags: re.flags.to_string(),
+            span: SpanJson { lo: re.span.lo.0 - self.start_pos, hi: re.span.hi.0 - self.start_pos },
+            line,
+            column,
+        });
+    }
+}
+
+/// Every regex literal in `source`, in source order; empty when the file doesn't parse
+pub fn extract_regex_literals(source: &str, filename: &str) -> Vec<RegexLiteral> {
+    let Ok((module, fm)) = parse_source(source, filename, None) else { return Vec::new() };
+    let lines = LineIndex::new(&fm.src, fm.start_pos);
+    let mut collector = RegexCollector { lines: &lines, start_pos: fm.start_pos.0, found: Vec::new() };
+    module.visit_with(&mut collector);
+    collector.found
+}
+
+/// Walks an SWC program and calls `visitor.enter`/`exit` for every node with a `NodeKind`;
+/// the nodes passed have no `children` and spans relative to the file start
+struct NodeEvents<'v> {
+    visitor: &'v mut dyn Visitor,
+    start_pos: u32,
+}
+
+impl NodeEvents<'_> {
+    fn node<N: for<'a> VisitWith<NodeEvents<'a>>>(&mut self, kind: NodeKind, span: Span, n: &N) {
+        let span = SpanJson { lo: span.lo.0.saturating_sub(self.start_pos), hi: span.hi.0.saturating_sub(self.start_pos) };
+        let node = AstNode { kind, span, children: vec![] };
+        self.visitor.enter(&node);
+        n.visit_children_with(self);
+        self.visitor.exit(&node);
+    }
+}
+
+impl Visit for NodeEvents<'_> {
+    fn visit_module(&mut self, m: &Module) {
+        self.node(NodeKind::Root, m.span, m);
+    }
+    fn visit_script(&mut self, s: &Script) {
+        self.node(NodeKind::Root, s.span, s);
+    }
+    fn visit_function(&mut self, f: &Function) {
+        self.node(NodeKind::Function, f.span, f);
+    }
+    fn visit_arrow_expr(&mut self, a: &ArrowExpr) {
+        self.node(NodeKind::Function, a.span, a);
+    }
+    fn visit_class(&mut self, c: &Class) {
+        self.node(NodeKind::Class, c.span, c);
+    }
+    fn visit_call_expr(&mut self, c: &CallExpr) {
+        self.node(NodeKind::Call, c.span, c);
+    }
+    fn visit_jsx_element(&mut self, el: &JSXElement) {
+        self.node(NodeKind::JsxElement, el.span, el);
+    }
+    fn visit_jsx_fragment(&mut self, f: &JSXFragment) {
+        self.node(NodeKind::JsxFragment, f.span, f);
+    }
+}
+
+/// Nests the nodes it is handed back into a tree, in `enter`/`exit` order
+#[derive(Default)]
+struct TreeBuilder {
+    open: Vec<AstNode>,
+    root: Option<AstNode>,
+}
+
+impl Visitor for TreeBuilder {
+    fn enter(&mut self, node: &AstNode) {
+        self.open.push(node.clone());
+    }
+    fn exit(&mut self, _node: &AstNode) {
+        let Some(done) = self.open.pop() else { return };
+        match self.open.last_mut() {
+            Some(parent) => parent.children.push(done),
+            None => self.root = Some(done),
+        }
     }
 }
 
+fn build_tree<N: for<'a> VisitWith<NodeEvents<'a>>>(program: &N, start_pos: BytePos) -> AstNode {
+    let mut builder = TreeBuilder::default();
+    program.visit_with(&mut NodeEvents { visitor: &mut builder, start_pos: start_pos.0 });
+    builder.root.unwrap_or(AstNode { kind: NodeKind::Root, span: DUMMY_SP.into(), children: vec![] })
+}
+
 pub trait Visitor {
     fn enter(&mut self, _node: &AstNode) {}
     fn exit(&mut self, _node: &AstNode) {}
@@ -132,3 +302,95 @@ pub fn traverse_ast(node: &AstNode, visitor: &mut dyn Visitor) {
     }
     visitor.exit(node);
 }
+
+/// Parse `source` and hand `visitor` each node as the SWC AST is walked, instead of building
+/// an `AstNode` tree and then traversing it. Nodes carry no `children`; nesting shows in the
+/// order of `enter`/`exit` calls. Same nodes and spans as `parse_typescript`.
+pub fn parse_and_visit(source: &str, filename: &str, visitor: &mut dyn Visitor) -> Result<(), ParseError> {
+    let (module, fm) = parse_source(source, filename, None)?;
+    module.visit_with(&mut NodeEvents { visitor, start_pos: fm.start_pos.0 });
+    Ok(())
+}
+
+/// `traverse_ast` calling `enter`/`exit` only for nodes whose kind is in `kinds`;
+/// children of skipped nodes are still visited
+pub fn traverse_ast_filtered(node: &AstNode, visitor: &mut dyn Visitor, kinds: &[NodeKind]) {
+    let matches = kinds.contains(&node.kind);
+    if matches {
+        visitor.enter(node);
+    }
+    for child in &node.children {
+        traverse_ast_filtered(child, visitor, kinds);
+    }
+    if matches {
+        visitor.exit(node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Kinds(Vec<NodeKind>);
+
+    impl Visitor for Kinds {
+        fn enter(&mut self, node: &AstNode) {
+            self.0.push(node.kind.clone());
+        }
+    }
+
+    const SOURCE: &str = "export function App() {\n  const onClick = () => track('click');\n  return <><Button onClick={onClick} /></>;\n}\n";
+
+    #[test]
+    fn test_parse_typescript_builds_node_tree() {
+        let ast = parse_typescript(SOURCE, "app.tsx").expect("parses");
+        assert_eq!(ast.kind, NodeKind::Root);
+        let app = &ast.children[0];
+        assert_eq!(app.kind, NodeKind::Function);
+        assert!(SOURCE[app.span.lo as usize..app.span.hi as usize].ends_with("</>;\n}"));
+        let nested: Vec<&NodeKind> = app.children.iter().map(|c| &c.kind).collect();
+        assert_eq!(nested, [&NodeKind::Function, &NodeKind::JsxFragment]);
+        assert_eq!(app.children[0].children[0].kind, NodeKind::Call);
+        assert_eq!(app.children[1].children[0].kind, NodeKind::JsxElement);
+    }
+
+    #[test]
+    fn test_traverse_ast_filtered_visits_only_selected_kinds() {
+        let ast = parse_typescript(SOURCE, "app.tsx").expect("parses");
+        let mut kinds = Kinds::default();
+        traverse_ast_filtered(&ast, &mut kinds, &[NodeKind::Call, NodeKind::JsxElement]);
+        assert_eq!(kinds.0, [NodeKind::Call, NodeKind::JsxElement]);
+
+        let mut none = Kinds::default();
+        traverse_ast_filtered(&ast, &mut none, &[NodeKind::Class]);
+        assert!(none.0.is_empty());
+    }
+
+    #[test]
+    fn test_parse_and_visit_matches_tree_traversal() {
+        let mut streamed = Kinds::default();
+        parse_and_visit(SOURCE, "app.tsx", &mut streamed).expect("parses");
+        let mut walked = Kinds::default();
+        traverse_ast(&parse_typescript(SOURCE, "app.tsx").expect("parses"), &mut walked);
+        assert_eq!(streamed.0.len(), 6);
+        assert_eq!(streamed.0, walked.0);
+    }
+
+    #[test]
+    fn test_tsx_without_jsx_falls_back_to_typescript() {
+        let ast = parse_typescript(include_str!("../fixtures/generic-helpers.tsx"), "generic-helpers.tsx").expect("parses as .ts");
+        assert_eq!(ast.children.len(), 2);
+        assert!(ast.children.iter().all(|c| c.kind == NodeKind::Function));
+        assert!(parse_typescript("const x = <div>{</div>;", "broken.tsx").is_err());
+    }
+
+    #[test]
+    fn test_regex_literals_in_tsx_without_jsx() {
+        // `<T>(..)` is a JSX tag to the TSX grammar; the retry as TypeScript still finds the regex
+        let source = "export const id = <T>(x: T) => x;\nexport const slug = /^[a-z-]+$/i;\n";
+        let found: Vec<(String, String, usize, usize)> =
+            extract_regex_literals(source, "helpers.tsx").into_iter().map(|r| (r.pattern, r.flags, r.line, r.column)).collect();
+        assert_eq!(found, vec![("^[a-z-]+$".to_string(), "i".to_string(), 2, 21)]);
+    }
+}
@@ -0,0 +1,427 @@
+use regex_syntax::ast::{
+    self, parse::Parser, Ast, ClassPerlKind, ClassSet, ClassSetItem, RepetitionKind, RepetitionRange,
+};
+use serde::{Deserialize, Serialize};
+
+/// Structural causes of catastrophic backtracking
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RedosIssue {
+    /// An unbounded quantifier inside another one, e.g. `(a+)+`
+    NestedQuantifier,
+    /// Alternatives that can match the same input under a quantifier, e.g. `(a|ab)*`
+    OverlappingAlternation,
+    /// A variable-length quantifier inside an unbounded group that lets the same input split
+    /// more than one way, e.g. `(a{1,2})+`
+    QuantifiedGroupWithQuantifier,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedosAnalysis {
+    pub issues: Vec<RedosIssue>,
+    /// Equivalent pattern without the redundant outer quantifier, when one exists
+    pub rewrite: Option<String>,
+    /// The pattern uses syntax the analysis can't read, so nothing is known about it
+    #[serde(default)]
+    pub unanalyzable: bool,
+}
+
+impl RedosAnalysis {
+    /// Whether the pattern was analyzed and nothing was found; an unanalyzable one never is
+    pub fn is_safe(&self) -> bool {
+        !self.unanalyzable && self.issues.is_empty()
+    }
+}
+
+/// Parse `pattern` and look for nested unbounded quantifiers, overlapping
+/// alternations and ambiguous quantified groups. Lookarounds and backreferences,
+/// which the parser doesn't support, are analyzed as empty groups; a pattern that
+/// still doesn't parse comes back `unanalyzable`.
+pub fn analyze_pattern(pattern: &str) -> RedosAnalysis {
+    let stripped = strip_unsupported(pattern);
+    let Ok(ast) = Parser::new().parse(&stripped.pattern) else {
+        return RedosAnalysis { unanalyzable: true, ..Default::default() };
+    };
+    let mut analysis = RedosAnalysis::default();
+    walk(&ast, pattern, &stripped, &mut analysis);
+    analysis
+}
+
+/// `pattern` with each lookaround and backreference replaced by `(?:)`, and for each of its
+/// bytes the offset of the byte it came from in the original
+struct Stripped {
+    pattern: String,
+    origins: Vec<usize>,
+}
+
+impl Stripped {
+    /// Byte range in the original pattern of `span` in the stripped one
+    fn original(&self, span: &ast::Span) -> (usize, usize) {
+        let (start, end) = (span.start.offset, span.end.offset);
+        (self.origins[start], if end > start { self.origins[end - 1] + 1 } else { self.origins[start] })
+    }
+}
+
+/// Index just past the group opening at `start` (a `(`), or the end of `pattern` when unclosed
+fn group_end(pattern: &[u8], start: usize) -> usize {
+    let (mut depth, mut in_class, mut i) = (0usize, false, start);
+    while i < pattern.len() {
+        match pattern[i] {
+            b'\\' => i += 1,
+            b'[' => in_class = true,
+            b']' => in_class = false,
+            b'(' if !in_class => depth += 1,
+            b')' if !in_class => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    pattern.len()
+}
+
+/// Replace what `regex_syntax` rejects but JS allows: `(?=..)`, `(?!..)`, `(?<=..)`, `(?<!..)`,
+/// `\1` and `\k<name>`
+fn strip_unsupported(pattern: &str) -> Stripped {
+    let bytes = pattern.as_bytes();
+    let mut out = Stripped { pattern: String::with_capacity(pattern.len()), origins: Vec::with_capacity(pattern.len() + 1) };
+    let (mut i, mut in_class) = (0, false);
+    while i < bytes.len() {
+        let rest = &pattern[i..];
+        let skip = if in_class {
+            None
+        } else if ["(?=", "(?!", "(?<=", "(?<!"].iter().any(|p| rest.starts_with(p)) {
+            Some(group_end(bytes, i))
+        } else if rest.starts_with("\\k<") {
+            Some(rest.find('>').map_or(bytes.len(), |end| i + end + 1))
+        } else if rest.len() > 1 && rest.starts_with('\\') && matches!(bytes[i + 1], b'1'..=b'9') {
+            Some(i + 1 + rest[1..].bytes().take_while(u8::is_ascii_digit).count())
+        } else {
+            None
+        };
+        if let Some(end) = skip {
+            out.pattern.push_str("(?:)");
+            out.origins.extend([i, i, i, end - 1]);
+            i = end;
+            continue;
+        }
+        let len = rest.chars().next().map_or(1, char::len_utf8);
+        let take = match bytes[i] {
+            // an escape is copied whole, so `\[` and `\(` don't open anything
+            b'\\' => len + rest[len..].chars().next().map_or(0, char::len_utf8),
+            b'[' => {
+                in_class = true;
+                len
+            }
+            b']' => {
+                in_class = false;
+                len
+            }
+            _ => len,
+        };
+        out.pattern.push_str(&rest[..take]);
+        out.origins.extend(i..i + take);
+        i += take;
+    }
+    out.origins.push(pattern.len());
+    out
+}
+
+fn is_unbounded(kind: &RepetitionKind) -> bool {
+    matches!(kind, RepetitionKind::ZeroOrMore | RepetitionKind::OneOrMore | RepetitionKind::Range(RepetitionRange::AtLeast(_)))
+}
+
+fn is_variable_length(kind: &RepetitionKind) -> bool {
+    match kind {
+        RepetitionKind::Range(RepetitionRange::Exactly(_)) => false,
+        RepetitionKind::Range(RepetitionRange::Bounded(lo, hi)) => lo != hi,
+        _ => true,
+    }
+}
+
+fn push_issue(analysis: &mut RedosAnalysis, issue: RedosIssue) {
+    if !analysis.issues.contains(&issue) {
+        analysis.issues.push(issue);
+    }
+}
+
+fn walk(node: &Ast, pattern: &str, stripped: &Stripped, analysis: &mut RedosAnalysis) {
+    match node {
+        Ast::Repetition(rep) => {
+            if is_unbounded(&rep.op.kind) {
+                let mut inner = Vec::new();
+                collect_repetitions(&rep.ast, &mut inner);
+                if inner.iter().any(|k| is_unbounded(k)) {
+                    push_issue(analysis, RedosIssue::NestedQuantifier);
+                    if analysis.rewrite.is_none() {
+                        analysis.rewrite = redundant_outer_rewrite(rep, pattern, stripped);
+                    }
+                } else if has_ambiguous_quantifier(&rep.ast, &first_chars(&rep.ast)) {
+                    push_issue(analysis, RedosIssue::QuantifiedGroupWithQuantifier);
+                }
+                if has_overlapping_alternation(&rep.ast) {
+                    push_issue(analysis, RedosIssue::OverlappingAlternation);
+                }
+            }
+            walk(&rep.ast, pattern, stripped, analysis);
+        }
+        Ast::Group(g) => walk(&g.ast, pattern, stripped, analysis),
+        Ast::Alternation(alt) => alt.asts.iter().for_each(|a| walk(a, pattern, stripped, analysis)),
+        Ast::Concat(c) => c.asts.iter().for_each(|a| walk(a, pattern, stripped, analysis)),
+        _ => {}
+    }
+}
+
+fn collect_repetitions<'a>(node: &'a Ast, out: &mut Vec<&'a RepetitionKind>) {
+    match node {
+        Ast::Repetition(rep) => {
+            out.push(&rep.op.kind);
+            collect_repetitions(&rep.ast, out);
+        }
+        Ast::Group(g) => collect_repetitions(&g.ast, out),
+        Ast::Alternation(alt) => alt.asts.iter().for_each(|a| collect_repetitions(a, out)),
+        Ast::Concat(c) => c.asts.iter().for_each(|a| collect_repetitions(a, out)),
+        _ => {}
+    }
+}
+
+/// `(x+)+` => `(x+)` and `(x*)*` => `(x*)`: the outer quantifier adds nothing. Spans are
+/// in `stripped` and mapped back, so the rewrite keeps any lookarounds of `pattern`.
+fn redundant_outer_rewrite(rep: &ast::Repetition, pattern: &str, stripped: &Stripped) -> Option<String> {
+    let Ast::Group(group) = &*rep.ast else { return None };
+    let Ast::Repetition(inner) = &*group.ast else { return None };
+    let same = matches!(
+        (&rep.op.kind, &inner.op.kind),
+        (RepetitionKind::OneOrMore, RepetitionKind::OneOrMore) | (RepetitionKind::ZeroOrMore, RepetitionKind::ZeroOrMore)
+    );
+    if !same {
+        return None;
+    }
+    let (start, end) = stripped.original(&rep.span);
+    let (group_start, group_end) = stripped.original(&group.span);
+    let group_src = &pattern[group_start..group_end];
+    Some(format!("{}{}{}", &pattern[..start], group_src, &pattern[end..]))
+}
+
+/// Rough first-character descriptor used to compare alternatives
+#[derive(Clone)]
+enum First {
+    Char(char),
+    Any,
+    Perl(ClassPerlKind, bool),
+    /// Non-negated bracket class made of literals and ranges
+    Ranges(Vec<(char, char)>),
+}
+
+fn perl_matches(kind: &ClassPerlKind, negated: bool, c: char) -> bool {
+    let m = match kind {
+        ClassPerlKind::Digit => c.is_ascii_digit(),
+        ClassPerlKind::Space => c.is_whitespace(),
+        ClassPerlKind::Word => c.is_alphanumeric() || c == '_',
+    };
+    m != negated
+}
+
+fn overlaps(a: &First, b: &First) -> bool {
+    match (a, b) {
+        (First::Any, _) | (_, First::Any) => true,
+        (First::Char(x), First::Char(y)) => x == y,
+        (First::Char(c), First::Perl(k, n)) | (First::Perl(k, n), First::Char(c)) => perl_matches(k, *n, *c),
+        (First::Char(c), First::Ranges(r)) | (First::Ranges(r), First::Char(c)) => r.iter().any(|(lo, hi)| lo <= c && c <= hi),
+        (First::Perl(k1, n1), First::Perl(k2, n2)) => {
+            (k1 == k2 && n1 == n2) || (!n1 && !n2 && matches!((k1, k2), (ClassPerlKind::Word, ClassPerlKind::Digit) | (ClassPerlKind::Digit, ClassPerlKind::Word)))
+        }
+        (First::Perl(k, n), First::Ranges(r)) | (First::Ranges(r), First::Perl(k, n)) => {
+            r.iter().any(|(lo, hi)| perl_matches(k, *n, *lo) || perl_matches(k, *n, *hi))
+        }
+        (First::Ranges(r1), First::Ranges(r2)) => r1.iter().any(|(a1, b1)| r2.iter().any(|(a2, b2)| a1 <= b2 && a2 <= b1)),
+    }
+}
+
+fn bracket_ranges(set: &ClassSet) -> Option<Vec<(char, char)>> {
+    let items = match set {
+        ClassSet::Item(ClassSetItem::Union(u)) => u.items.iter().collect::<Vec<_>>(),
+        ClassSet::Item(item) => vec![item],
+        ClassSet::BinaryOp(_) => return None,
+    };
+    items
+        .into_iter()
+        .map(|item| match item {
+            ClassSetItem::Literal(l) => Some((l.c, l.c)),
+            ClassSetItem::Range(r) => Some((r.start.c, r.end.c)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Possible first characters of `node`; None when it can't be described
+fn first_chars(node: &Ast) -> Option<Vec<First>> {
+    match node {
+        Ast::Literal(l) => Some(vec![First::Char(l.c)]),
+        Ast::Dot(_) => Some(vec![First::Any]),
+        Ast::ClassPerl(p) => Some(vec![First::Perl(p.kind.clone(), p.negated)]),
+        Ast::ClassBracketed(b) if !b.negated => bracket_ranges(&b.kind).map(|r| vec![First::Ranges(r)]),
+        Ast::Group(g) => first_chars(&g.ast),
+        Ast::Repetition(rep) => first_chars(&rep.ast),
+        Ast::Concat(c) => c.asts.iter().find(|a| !is_zero_width(a)).and_then(first_chars),
+        Ast::Alternation(alt) => {
+            let mut all = Vec::new();
+            for a in &alt.asts {
+                all.extend(first_chars(a)?);
+            }
+            Some(all)
+        }
+        _ => None,
+    }
+}
+
+/// Whether two branches of an alternation under `node` can start with the same character
+fn has_overlapping_alternation(node: &Ast) -> bool {
+    match node {
+        Ast::Alternation(alt) => {
+            let firsts: Vec<Option<Vec<First>>> = alt.asts.iter().map(first_chars).collect();
+            for (i, a) in firsts.iter().enumerate() {
+                for b in firsts.iter().skip(i + 1) {
+                    if let (Some(a), Some(b)) = (a, b) {
+                        if a.iter().any(|x| b.iter().any(|y| overlaps(x, y))) {
+                            return true;
+                        }
+                    }
+                }
+            }
+            alt.asts.iter().any(has_overlapping_alternation)
+        }
+        Ast::Group(g) => has_overlapping_alternation(&g.ast),
+        Ast::Concat(c) => c.asts.iter().any(has_overlapping_alternation),
+        Ast::Repetition(rep) => has_overlapping_alternation(&rep.ast),
+        _ => false,
+    }
+}
+
+/// Nodes that never consume a character: anchors, flags and empty groups (what
+/// `strip_unsupported` leaves of lookarounds)
+fn is_zero_width(node: &Ast) -> bool {
+    match node {
+        Ast::Empty(_) | Ast::Flags(_) | Ast::Assertion(_) => true,
+        Ast::Group(g) => is_zero_width(&g.ast),
+        _ => false,
+    }
+}
+
+/// Whether `node` can match the empty string
+fn is_nullable(node: &Ast) -> bool {
+    match node {
+        Ast::Repetition(rep) => match &rep.op.kind {
+            RepetitionKind::ZeroOrOne | RepetitionKind::ZeroOrMore => true,
+            RepetitionKind::Range(RepetitionRange::Exactly(n) | RepetitionRange::AtLeast(n) | RepetitionRange::Bounded(n, _)) => *n == 0 || is_nullable(&rep.ast),
+            RepetitionKind::OneOrMore => is_nullable(&rep.ast),
+        },
+        Ast::Group(g) => is_nullable(&g.ast),
+        Ast::Concat(c) => c.asts.iter().all(is_nullable),
+        Ast::Alternation(alt) => alt.asts.iter().any(is_nullable),
+        other => is_zero_width(other),
+    }
+}
+
+/// Possible first characters of what matches after `rest`, reaching `tail` when all of
+/// `rest` can be empty; None when some can't be described
+fn follow_chars(rest: &[Ast], tail: &Option<Vec<First>>) -> Option<Vec<First>> {
+    let mut all = Vec::new();
+    for node in rest.iter().filter(|a| !is_zero_width(a)) {
+        all.extend(first_chars(node)?);
+        if !is_nullable(node) {
+            return Some(all);
+        }
+    }
+    all.extend(tail.as_ref()?.iter().cloned());
+    Some(all)
+}
+
+/// Whether a variable-length quantifier in `node` can take characters that could also start
+/// what follows it (`follow` after `node` ends), so the same input splits more than one way:
+/// `(a{1,2})+` or `(\w?\d)+`, but not `(ab?)+`
+fn has_ambiguous_quantifier(node: &Ast, follow: &Option<Vec<First>>) -> bool {
+    let items: &[Ast] = match node {
+        Ast::Group(g) => return has_ambiguous_quantifier(&g.ast, follow),
+        Ast::Alternation(alt) => return alt.asts.iter().any(|a| has_ambiguous_quantifier(a, follow)),
+        Ast::Concat(c) => &c.asts,
+        other => std::slice::from_ref(other),
+    };
+    items.iter().enumerate().any(|(i, item)| {
+        let after = follow_chars(&items[i + 1..], follow);
+        match item {
+            Ast::Repetition(rep) if is_variable_length(&rep.op.kind) => match (first_chars(&rep.ast), &after) {
+                (Some(own), Some(next)) => own.iter().any(|x| next.iter().any(|y| overlaps(x, y))) || has_ambiguous_quantifier(&rep.ast, &after),
+                _ => true,
+            },
+            Ast::Repetition(rep) => has_ambiguous_quantifier(&rep.ast, &after),
+            Ast::Group(_) | Ast::Alternation(_) | Ast::Concat(_) => has_ambiguous_quantifier(item, &after),
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_quantifier_rewrite() {
+        let a = analyze_pattern("^(a+)+$");
+        assert_eq!(a.issues, vec![RedosIssue::NestedQuantifier]);
+        assert_eq!(a.rewrite.as_deref(), Some("^(a+)$"));
+
+        let a = analyze_pattern(r"(\w*)*x");
+        assert!(!a.is_safe());
+        assert_eq!(a.rewrite.as_deref(), Some(r"(\w*)x"));
+    }
+
+    #[test]
+    fn test_overlapping_alternation() {
+        assert!(analyze_pattern("(a|ab)*c").issues.contains(&RedosIssue::OverlappingAlternation));
+        assert!(analyze_pattern(r"(\w|\d)+$").issues.contains(&RedosIssue::OverlappingAlternation));
+        assert!(analyze_pattern("(a|b)*c").is_safe());
+    }
+
+    #[test]
+    fn test_safe_patterns() {
+        assert!(analyze_pattern(r"^\d{3}-\d{4}$").is_safe());
+        assert!(analyze_pattern("(ab){2}c").is_safe());
+        assert!(analyze_pattern("[a-z]+@[a-z]+").is_safe());
+        assert!(analyze_pattern("(?<=x)y").is_safe());
+    }
+
+    #[test]
+    fn test_ambiguous_quantified_groups() {
+        // each iteration starts with `a`, which `b?` can't take
+        assert!(analyze_pattern("(ab?)+").is_safe());
+        assert!(analyze_pattern(r"(-?\d)+$").is_safe());
+        assert_eq!(analyze_pattern("(a{1,2})+$").issues, vec![RedosIssue::QuantifiedGroupWithQuantifier]);
+        assert_eq!(analyze_pattern(r"(\w?\d)+$").issues, vec![RedosIssue::QuantifiedGroupWithQuantifier]);
+        assert_eq!(analyze_pattern("(xa?a?)+$").issues, vec![RedosIssue::QuantifiedGroupWithQuantifier]);
+    }
+
+    #[test]
+    fn test_lookarounds_and_backreferences_are_analyzed() {
+        let a = analyze_pattern(r"^(?=.*\d)(a+)+$");
+        assert_eq!(a.issues, vec![RedosIssue::NestedQuantifier]);
+        assert_eq!(a.rewrite.as_deref(), Some(r"^(?=.*\d)(a+)$"));
+
+        let a = analyze_pattern(r"(?<!\()(?<q>['\x22])(\w+)+\k<q>");
+        assert_eq!(a.rewrite.as_deref(), Some(r"(?<!\()(?<q>['\x22])(\w+)\k<q>"));
+        // the backreference stands in for its group; it doesn't become one that `+` repeats
+        assert!(analyze_pattern(r"(a)\1+b").is_safe());
+        assert!(analyze_pattern(r"(?!x)[a-z]\d{2}").is_safe());
+    }
+
+    #[test]
+    fn test_unparsable_patterns_are_never_safe() {
+        let a = analyze_pattern("(a");
+        assert!(a.unanalyzable && a.issues.is_empty());
+        assert!(!a.is_safe());
+        assert!(!analyze_pattern("[z-a]").is_safe());
+    }
+}
@@ -0,0 +1,35 @@
+use crate::analyzer::extract::analyze_source;
+use crate::parser;
+use crate::redos::analyze_pattern;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(js_name = parseFile)]
+pub fn parse_file(source: &str) -> Result<String, JsError> {
+    let ast = parser::parse_file(source).map_err(|err| JsError::new(&format!("parse error: {}", err.0)))?;
+    serde_json::to_string(&ast).map_err(|e| JsError::new(&format!("serialize ast failed: {}", e)))
+}
+
+#[derive(Serialize)]
+struct RedosOutput {
+    safe: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rewrite: Option<String>,
+}
+
+/// Same JSON as the CLI's `check-redos`: `{ safe, rewrite? }`
+#[wasm_bindgen(js_name = checkRedos)]
+pub fn check_redos(pattern: &str) -> String {
+    let analysis = analyze_pattern(pattern);
+    let out = RedosOutput { safe: analysis.is_safe(), rewrite: analysis.rewrite };
+    serde_json::to_string(&out).unwrap_or_else(|_| "{}".into())
+}
+
+/// Single-file extraction with the built-in rules; `filename` picks the syntax (`.tsx`, `.vue`, ...).
+/// A file that doesn't parse is an error rather than an empty analysis.
+#[wasm_bindgen(js_name = analyzeFile)]
+pub fn analyze_file(source: &str, filename: &str) -> Result<String, JsError> {
+    // no worker threads on wasm, so no parse timeout either
+    let analysis = analyze_source(source, filename).ok_or_else(|| JsError::new(&format!("parse error in {}", filename)))?;
+    serde_json::to_string(&analysis).map_err(|e| JsError::new(&format!("serialize analysis failed: {}", e)))
+}
@@ -0,0 +1,71 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Run the CLI with `stdin` and return its exit code
+fn run(args: &[&str], stdin: &str) -> i32 {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_perf-linter-core"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait().unwrap().code().unwrap()
+}
+
+/// A fresh directory holding `files`
+fn project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("perf-linter-exit-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for (path, contents) in files {
+        std::fs::write(dir.join(path), contents).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn test_ok() {
+    assert_eq!(run(&["check-redos"], r#"{ "pattern": "(a+)+$" }"#), 0);
+    assert_eq!(run(&["parse", "--filename", "a.tsx"], "const x = <div />;"), 0);
+}
+
+#[test]
+fn test_usage() {
+    assert_eq!(run(&["check-redos"], "not json"), 2);
+    assert_eq!(run(&["compare", "/nonexistent/before.json", "/nonexistent/after.json"], ""), 2);
+    assert_eq!(run(&["no-such-command"], ""), 2);
+    // output modes are exclusive
+    assert_eq!(run(&["index", ".", "--stats", "--memo-priority"], ""), 2);
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(run(&["parse", "--filename", "a.tsx"], "const = ;"), 3);
+    let dir = project("parse", &[("run.json", "{ not json")]);
+    let run_json = dir.join("run.json");
+    assert_eq!(run(&["compare", run_json.to_str().unwrap(), run_json.to_str().unwrap()], ""), 3);
+}
+
+#[test]
+fn test_config() {
+    let dir = project("config", &[(".perflinterrc.json", r#"{ "disabled_rules": [] }"#)]);
+    let root = dir.to_str().unwrap();
+    assert_eq!(run(&["validate-config", root], ""), 4);
+    assert_eq!(run(&["lint", root, "--cache-dir", root], ""), 4);
+    let empty = project("no-config", &[]);
+    assert_eq!(run(&["validate-config", empty.to_str().unwrap()], ""), 4);
+}
+
+#[test]
+fn test_findings_at_threshold() {
+    let source = "import { useState } from 'react';\nexport function A() {\n  const [a] = useState(compute());\n  return <div>{a}</div>;\n}\n";
+    let dir = project("lint", &[("a.tsx", source)]);
+    let root = dir.to_str().unwrap();
+    assert_eq!(run(&["lint", root, "--cache-dir", root, "--fail-on", "warn"], ""), 1);
+    assert_eq!(run(&["lint", root, "--cache-dir", root, "--fail-on", "error"], ""), 0);
+    assert_eq!(run(&["lint", root, "--cache-dir", root, "--fail-on", "warn", "--rule", "eager-state-init=off"], ""), 0);
+    assert_eq!(run(&["lint", root, "--cache-dir", root, "--rule", "eager-state-initt=off"], ""), 2);
+}
@@ -4,6 +4,8 @@
 type NativeAddon = {
   parse_file(source: string): string; // returns JSON string of AST
   traverse_ast(astJson: string): { nodes_visited: number };
+  count_nodes(source: string, filename: string): { nodes_visited: number };
+  list_rules(): string;
 };
 
 let native: NativeAddon | null = null;
@@ -82,3 +84,33 @@ export function traverseAst(ast: AstNode): { nodesVisited: number } | null {
     return null;
   }
 }
+
+export function countNodes(source: string, filename = 'input.tsx'): { nodesVisited: number } | null {
+  const addon = tryLoadNative();
+  if (!addon) return null;
+  try {
+    const stats = addon.count_nodes(source, filename);
+    return { nodesVisited: stats.nodes_visited };
+  } catch {
+    return null;
+  }
+}
+
+export type RuleInfo = {
+  id: string;
+  name: string;
+  default_severity: 'off' | 'info' | 'warning' | 'error';
+  description: string;
+  category: 'performance' | 'correctness' | 'bundle' | 'security' | 'maintainability';
+  enabled_by_default: boolean;
+};
+
+export function listRules(): RuleInfo[] | null {
+  const addon = tryLoadNative();
+  if (!addon) return null;
+  try {
+    return JSON.parse(addon.list_rules()) as RuleInfo[];
+  } catch {
+    return null;
+  }
+}